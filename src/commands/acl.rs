@@ -0,0 +1,114 @@
+// Library
+use crate::{
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ---
+// ACL
+// ---
+
+/// Handles the ACL command.
+/// The ACL command is used to inspect the access control list.
+/// The command is in the format `ACL subcommand`.
+/// The supported subcommands are `WHOAMI` and `LIST`.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Extract the subcommand from the arguments
+    let subcommand = match args.first().and_then(Type::as_str) {
+        Some(subcommand) => subcommand.to_uppercase(),
+        None => {
+            return connection
+                .write_error("ERR wrong number of arguments for 'ACL' command")
+                .await;
+        }
+    };
+
+    // Handle the subcommand
+    match (subcommand.as_str(), &args[1..]) {
+        ("WHOAMI", []) => whoami(connection).await,
+        ("LIST", []) => list(connection, server).await,
+        ("WHOAMI" | "LIST", _) => {
+            connection
+                .write_error(format!(
+                    "ERR wrong number of arguments for 'ACL|{}' command",
+                    subcommand.to_lowercase()
+                ))
+                .await
+        }
+        _ => {
+            connection
+                .write_error(format!(
+                    "ERR unknown subcommand '{}'. Try ACL HELP.",
+                    subcommand
+                ))
+                .await
+        }
+    }
+}
+
+// ------
+// WHOAMI
+// ------
+
+/// Handles the ACL WHOAMI subcommand.
+/// The ACL WHOAMI subcommand returns the name of the user the connection is authenticated as.
+async fn whoami(connection: &mut Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let response = resp::bulk_string(&connection.user);
+    connection.write_all(&response.as_bytes()).await
+}
+
+// ----
+// LIST
+// ----
+
+/// Handles the ACL LIST subcommand.
+/// The ACL LIST subcommand returns a line describing every user, ordered by name.
+async fn list(
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut users: Vec<String> = server
+        .lock()
+        .await
+        .users
+        .iter()
+        .map(|(name, user)| user.describe(name))
+        .collect();
+    users.sort();
+    let response = resp::array(users.iter().map(|user| resp::bulk_string(user)).collect());
+    connection.write_all(&response.as_bytes()).await
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_default_user_before_auth() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["ACL", "WHOAMI"]).await, b"$7\r\ndefault\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_list_default_user() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["ACL", "LIST"]).await,
+            b"*1\r\n$34\r\nuser default on nopass ~* &* +@all\r\n"
+        );
+        assert_eq!(
+            client.send(&["ACL", "SETUSER", "alice"]).await,
+            b"-ERR unknown subcommand 'SETUSER'. Try ACL HELP.\r\n"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// APPEND
+// ------
+
+/// Handles the APPEND command.
+/// The APPEND command appends the value at the end of the string stored at the key.
+/// The command is in the format `APPEND key value`.
+/// If the key does not exist, it is created and set to the value.
+/// Any existing expiry on the key is preserved.
+/// The command returns the length of the string after the append operation.
+/// Returns true if the value was appended, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 2 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'APPEND' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the value to append from the arguments
+    let suffix = match &args[1] {
+        Type::BulkString(suffix) => suffix,
+        _ => {
+            if role.is_master() {
+                connection.write_error("ERR invalid value").await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Concatenate the value to the existing string, treating a missing key as empty
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let value = match shard.get(connection.db, &args[0]) {
+        Some(Type::BulkString(value)) => [value.as_slice(), suffix].concat(),
+        Some(_) => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+        None => suffix.clone(),
+    };
+    let len = value.len();
+    shard.update(connection.db, args[0].clone(), Type::BulkString(value));
+
+    // Respond with the new length of the string
+    if role.is_master() {
+        let response = Type::Integer(len as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(true)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_append_to_existing_and_missing_keys() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["APPEND", "k", "Hello"]).await, b":5\r\n");
+        assert_eq!(client.send(&["APPEND", "k", " World"]).await, b":11\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$11\r\nHello World\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_preserve_expiry() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "a", "PX", "20"]).await;
+        assert_eq!(client.send(&["APPEND", "k", "b"]).await, b":2\r\n");
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_string_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        assert_eq!(
+            client.send(&["APPEND", "s", "v"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{acl::DEFAULT_USER, connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// AUTH
+// ----
+
+/// Handles the AUTH command.
+/// The AUTH command authenticates the connection as one of the users of the access control list.
+/// The command is in the format `AUTH [username] password`. Without a username, the `default` user is used.
+/// The command returns a WRONGPASS error if the user does not exist, is disabled, or rejects the password.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Extract the username and password from the arguments
+    let (username, password) = match args {
+        [password] => (Some(DEFAULT_USER), password.as_str()),
+        [username, password] => (username.as_str(), password.as_str()),
+        _ => {
+            return connection
+                .write_error("ERR wrong number of arguments for 'AUTH' command")
+                .await;
+        }
+    };
+    let (Some(username), Some(password)) = (username, password) else {
+        return connection.write_error("ERR syntax error").await;
+    };
+
+    // Check the password against the user
+    let user = server.lock().await.users.get(username).cloned();
+    match user {
+        // Authenticating as the default user without a username only makes sense once it has a password
+        Some(user) if args.len() == 1 && user.passwords.is_none() => {
+            connection
+                .write_error("ERR AUTH <password> called without any password configured for the default user. Are you sure your configuration is correct?")
+                .await
+        }
+        Some(user) if user.authenticate(password) => {
+            connection.user = username.to_string();
+            connection.write_ok().await
+        }
+        _ => {
+            connection
+                .write_error("WRONGPASS invalid username-password pair or user is disabled.")
+                .await
+        }
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{commands::test_helpers, server::acl::User};
+
+    #[tokio::test]
+    async fn should_authenticate_with_username_and_password() {
+        let mut client = test_helpers::client().await;
+        let alice = User {
+            enabled: true,
+            passwords: Some(vec!["secret".into()]),
+        };
+        client
+            .server
+            .lock()
+            .await
+            .users
+            .insert("alice".into(), alice);
+
+        assert_eq!(
+            client.send(&["AUTH", "alice", "wrong"]).await,
+            b"-WRONGPASS invalid username-password pair or user is disabled.\r\n"
+        );
+        assert_eq!(client.send(&["ACL", "WHOAMI"]).await, b"$7\r\ndefault\r\n");
+        assert_eq!(client.send(&["AUTH", "alice", "secret"]).await, b"+OK\r\n");
+        assert_eq!(client.send(&["ACL", "WHOAMI"]).await, b"$5\r\nalice\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_unknown_users() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["AUTH", "nobody", "pass"]).await,
+            b"-WRONGPASS invalid username-password pair or user is disabled.\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_password_only_form_without_default_password() {
+        let mut client = test_helpers::client().await;
+        let response = client.send(&["AUTH", "pass"]).await;
+        assert!(response.starts_with(b"-ERR AUTH <password> called without any password"));
+        assert_eq!(client.send(&["AUTH", "default", "any"]).await, b"+OK\r\n");
+    }
+}
@@ -0,0 +1,104 @@
+// Library
+use crate::{
+    commands::lrange,
+    errors::Error,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// --------
+// BITCOUNT
+// --------
+
+/// Handles the BITCOUNT command.
+/// The BITCOUNT command counts the set bits in the string stored at the key.
+/// The command is in the format `BITCOUNT key [start end]`.
+/// The optional `start` and `end` are inclusive byte indices, and negative indices count from the end
+/// of the string, as in GETRANGE. The command returns 0 if the key does not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.is_empty() {
+        return connection
+            .write_error("ERR wrong number of arguments for 'BITCOUNT' command")
+            .await;
+    }
+
+    // Extract the byte range from the arguments
+    let range = match &args[1..] {
+        [] => None,
+        [start, end] => match (
+            start.as_str().and_then(|start| start.parse::<i64>().ok()),
+            end.as_str().and_then(|end| end.parse::<i64>().ok()),
+        ) {
+            (Some(start), Some(end)) => Some((start, end)),
+            _ => {
+                return connection.write_error(Error::NotInteger.to_string()).await;
+            }
+        },
+        _ => return connection.write_error("ERR syntax error").await,
+    };
+
+    // Count the set bits within the range of the string stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.expect_string(connection.db, &args[0]) {
+        Ok(value) => {
+            let value = value.unwrap_or_default();
+            let bytes = match range {
+                Some((start, end)) => match lrange::range(value.len(), start, end) {
+                    Some((start, end)) => &value[start..=end],
+                    None => &[],
+                },
+                None => value,
+            };
+            let count: u32 = bytes.iter().map(|byte| byte.count_ones()).sum();
+            Type::Integer(count as i64)
+        }
+        Err(e) => Type::SimpleError(e.to_string()),
+    };
+
+    // Respond with the number of set bits
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_count_bits_in_range() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "foobar"]).await;
+        assert_eq!(client.send(&["BITCOUNT", "k"]).await, b":26\r\n");
+        assert_eq!(client.send(&["BITCOUNT", "k", "0", "0"]).await, b":4\r\n");
+        assert_eq!(client.send(&["BITCOUNT", "k", "1", "1"]).await, b":6\r\n");
+        assert_eq!(client.send(&["BITCOUNT", "k", "-2", "-1"]).await, b":7\r\n");
+        assert_eq!(client.send(&["BITCOUNT", "k", "5", "1"]).await, b":0\r\n");
+        assert_eq!(client.send(&["BITCOUNT", "missing"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_range() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["BITCOUNT", "k", "0"]).await,
+            b"-ERR syntax error\r\n"
+        );
+        assert_eq!(
+            client.send(&["BITCOUNT", "k", "a", "1"]).await,
+            b"-ERR value is not an integer or out of range\r\n"
+        );
+    }
+}
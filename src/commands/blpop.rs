@@ -0,0 +1,211 @@
+// Library
+use crate::{
+    database::Database,
+    helpers,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::{broadcast::error::RecvError, Mutex},
+    time::timeout_at,
+};
+
+// -----------
+// BLPOP/BRPOP
+// -----------
+
+/// Handles the BLPOP and BRPOP commands.
+/// The commands are the blocking versions of LPOP and RPOP, and pop an element from the head (BLPOP)
+/// or tail (BRPOP) of the first non-empty list among the given keys, checked in order.
+/// The commands are in the format `BLPOP key [key ...] timeout` and `BRPOP key [key ...] timeout`.
+/// If all the lists are empty, the command waits until an element is pushed to one of them,
+/// or until the timeout (in seconds, with decimals) elapses. A timeout of 0 waits forever.
+/// The commands return the key and the popped element, or a null array if the timeout elapsed.
+/// Returns the key of the list that was popped from, so that the pop can be propagated as LPOP or RPOP.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+    left: bool,
+) -> Result<Option<Type>, Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() < 2 {
+        connection
+            .write_error(format!(
+                "ERR wrong number of arguments for '{}' command",
+                name
+            ))
+            .await?;
+        return Ok(None);
+    }
+    let (keys, timeout) = args.split_at(args.len() - 1);
+
+    // Parse the timeout
+    let timeout = match timeout[0].as_str().and_then(|t| t.parse::<f64>().ok()) {
+        Some(timeout) if timeout < 0.0 => {
+            connection.write_error("ERR timeout is negative").await?;
+            return Ok(None);
+        }
+        Some(timeout) if timeout.is_finite() => timeout,
+        _ => {
+            connection
+                .write_error("ERR timeout is not a float or out of range")
+                .await?;
+            return Ok(None);
+        }
+    };
+
+    // Subscribe to the list events before popping, so that no element pushed in between is missed
+    let (db, mut events) = {
+        let s = server.lock().await;
+        (s.db.clone(), s.list_events.subscribe())
+    };
+
+    let deadline = match timeout == 0.0 {
+        true => None,
+        false => Duration::try_from_secs_f64(timeout)
+            .ok()
+            .and_then(helpers::deadline),
+    };
+    loop {
+        match pop_first(&db, connection.db, keys, left).await {
+            Ok(Some((key, element))) => {
+                let response = resp::array(vec![key.clone(), resp::bulk_string(&element)]);
+                connection.write_all(&response.as_bytes()).await?;
+                return Ok(Some(key));
+            }
+            Ok(None) => {}
+            Err(err) => {
+                connection.write_error(err).await?;
+                return Ok(None);
+            }
+        }
+
+        // Wait for elements to be pushed to one of the lists
+        loop {
+            let event = match deadline {
+                Some(deadline) => match timeout_at(deadline, events.recv()).await {
+                    Ok(event) => event,
+                    Err(_) => return write_null(connection).await, // Timed out
+                },
+                None => events.recv().await,
+            };
+            match event {
+                // Ignore elements pushed to other lists
+                Ok((db, key)) if db != connection.db || !keys.contains(&key) => continue,
+                Err(RecvError::Closed) => return write_null(connection).await,
+                // Either an element was pushed to one of the lists, or some events were missed
+                _ => break,
+            }
+        }
+    }
+}
+
+/// Pops an element from the first non-empty list among the keys.
+/// Returns the key and the popped element, or `None` if all the lists are empty.
+async fn pop_first(
+    database: &Database,
+    db: usize,
+    keys: &[Type],
+    left: bool,
+) -> Result<Option<(Type, String)>, String> {
+    for key in keys {
+        match database.pop(db, key, left).await {
+            Ok(Some(element)) => return Ok(Some((key.clone(), element))),
+            Ok(None) => continue,
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(None)
+}
+
+/// Responds with a null array as no element was pushed in time
+async fn write_null(
+    connection: &mut Connection,
+) -> Result<Option<Type>, Box<dyn std::error::Error>> {
+    connection.write_all(b"*-1\r\n").await?;
+    Ok(None)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn should_pop_right_away_from_first_non_empty_list() {
+        let mut client = test_helpers::client().await;
+        client.send(&["RPUSH", "b", "1", "2"]).await;
+        assert_eq!(
+            client.send(&["BLPOP", "a", "b", "0"]).await,
+            b"*2\r\n$1\r\nb\r\n$1\r\n1\r\n"
+        );
+        assert_eq!(
+            client.send(&["BRPOP", "a", "b", "0"]).await,
+            b"*2\r\n$1\r\nb\r\n$1\r\n2\r\n"
+        );
+        assert_eq!(client.send(&["EXISTS", "b"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_wake_blocked_pop_when_element_is_pushed() {
+        let mut reader = test_helpers::client().await;
+        let mut writer = test_helpers::client_for(&reader.server).await;
+        let (popped, _) = tokio::join!(reader.send(&["BLPOP", "l", "0"]), async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            writer.send(&["LPUSH", "other", "x"]).await;
+            writer.send(&["RPUSH", "l", "a", "b"]).await
+        });
+        assert_eq!(popped, b"*2\r\n$1\r\nl\r\n$1\r\na\r\n");
+        assert_eq!(
+            writer.send(&["LRANGE", "l", "0", "-1"]).await,
+            b"*1\r\n$1\r\nb\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_null_array_when_timeout_elapses() {
+        let mut client = test_helpers::client().await;
+        let started = Instant::now();
+        assert_eq!(client.send(&["BRPOP", "l", "0.1"]).await, b"*-1\r\n");
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn should_accept_huge_timeout() {
+        let mut client = test_helpers::client().await;
+        client.send(&["RPUSH", "l", "a", "b"]).await;
+        assert_eq!(
+            client.send(&["BLPOP", "l", "1e20"]).await,
+            b"*2\r\n$1\r\nl\r\n$1\r\na\r\n"
+        );
+        assert_eq!(
+            client.send(&["BLPOP", "l", "1e15"]).await,
+            b"*2\r\n$1\r\nl\r\n$1\r\nb\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_arguments() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["BLPOP", "l", "-1"]).await,
+            b"-ERR timeout is negative\r\n"
+        );
+        assert_eq!(
+            client.send(&["BLPOP", "l", "soon"]).await,
+            b"-ERR timeout is not a float or out of range\r\n"
+        );
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["BLPOP", "k", "0"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
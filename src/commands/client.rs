@@ -0,0 +1,231 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// CLIENT
+// ------
+
+/// Handles the CLIENT command.
+/// The CLIENT command is used to inspect and configure the current connection.
+/// The command is in the format `CLIENT subcommand [arguments ...]`.
+/// The supported subcommands are `ID`, `GETNAME`, `SETNAME` and `LIST`.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Extract the subcommand from the arguments
+    let subcommand = match args.first().and_then(Type::as_str) {
+        Some(subcommand) => subcommand.to_uppercase(),
+        None => {
+            return connection
+                .write_error("ERR wrong number of arguments for 'CLIENT' command")
+                .await;
+        }
+    };
+
+    // Handle the subcommand
+    match (subcommand.as_str(), &args[1..]) {
+        ("ID", []) => id(connection).await,
+        ("GETNAME", []) => getname(connection).await,
+        ("SETNAME", [name]) => setname(name, connection, server).await,
+        ("LIST", []) => list(connection, server).await,
+        ("ID" | "GETNAME" | "SETNAME" | "LIST", _) => {
+            connection
+                .write_error(format!(
+                    "ERR wrong number of arguments for 'CLIENT|{}' command",
+                    subcommand.to_lowercase()
+                ))
+                .await
+        }
+        _ => {
+            connection
+                .write_error(format!(
+                    "ERR unknown subcommand '{}'. Try CLIENT HELP.",
+                    subcommand
+                ))
+                .await
+        }
+    }
+}
+
+// --
+// ID
+// --
+
+/// Handles the CLIENT ID subcommand.
+/// The CLIENT ID subcommand returns the unique id of the current connection.
+async fn id(connection: &mut Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let response = Type::Integer(connection.id as i64);
+    connection.write_all(&response.as_bytes()).await
+}
+
+// -------
+// GETNAME
+// -------
+
+/// Handles the CLIENT GETNAME subcommand.
+/// The CLIENT GETNAME subcommand returns the name of the current connection,
+/// or an empty string if no name was set.
+async fn getname(connection: &mut Connection) -> Result<(), Box<dyn std::error::Error>> {
+    let name = connection.name.clone().unwrap_or_default();
+    let response = Type::BulkString(name.into());
+    connection.write_all(&response.as_bytes()).await
+}
+
+// -------
+// SETNAME
+// -------
+
+/// Handles the CLIENT SETNAME subcommand.
+/// The CLIENT SETNAME subcommand assigns a name to the current connection.
+/// The subcommand is in the format `CLIENT SETNAME name`, and an empty name removes the name.
+/// Names cannot contain spaces, newlines or other special characters.
+async fn setname(
+    name: &Type,
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Validate the name
+    let name = match name.as_str() {
+        Some(name) if name.chars().all(|c| c.is_ascii_graphic()) => name,
+        _ => {
+            return connection
+                .write_error(
+                    "ERR Client names cannot contain spaces, newlines or special characters.",
+                )
+                .await;
+        }
+    };
+
+    // Record the name on the connection, and in the list of clients
+    connection.name = match name.is_empty() {
+        true => None,
+        false => Some(name.to_string()),
+    };
+    if let Some(client) = server.lock().await.clients.get_mut(&connection.id) {
+        client.name = connection.name.clone();
+    }
+
+    connection.write_ok().await
+}
+
+// ----
+// LIST
+// ----
+
+/// Handles the CLIENT LIST subcommand.
+/// The CLIENT LIST subcommand returns a line for every connected client, ordered by id.
+/// Each line is in the format `id=<id> addr=<ip:port> name=<name> age=<seconds>`.
+async fn list(
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Describe every connected client
+    let mut clients: Vec<_> = server
+        .lock()
+        .await
+        .clients
+        .iter()
+        .map(|(id, client)| (*id, client.clone()))
+        .collect();
+    clients.sort_by_key(|(id, _)| *id);
+    let lines: String = clients
+        .into_iter()
+        .map(|(id, client)| {
+            format!(
+                "id={} addr={} name={} age={}\n",
+                id,
+                client.addr,
+                client.name.unwrap_or_default(),
+                client.connected_at.elapsed().as_secs()
+            )
+        })
+        .collect();
+
+    // Respond with the lines
+    let response = Type::BulkString(lines.into());
+    connection.write_all(&response.as_bytes()).await
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_set_and_get_name() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["CLIENT", "GETNAME"]).await, b"$0\r\n\r\n");
+        assert_eq!(
+            client.send(&["CLIENT", "SETNAME", "worker-1"]).await,
+            b"+OK\r\n"
+        );
+        assert_eq!(
+            client.send(&["CLIENT", "GETNAME"]).await,
+            b"$8\r\nworker-1\r\n"
+        );
+        assert_eq!(client.send(&["CLIENT", "SETNAME", ""]).await, b"+OK\r\n");
+        assert_eq!(client.send(&["CLIENT", "GETNAME"]).await, b"$0\r\n\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_names_with_spaces_or_newlines() {
+        let mut client = test_helpers::client().await;
+        for name in ["my worker", "worker\n"] {
+            assert_eq!(
+                client.send(&["CLIENT", "SETNAME", name]).await,
+                b"-ERR Client names cannot contain spaces, newlines or special characters.\r\n"
+            );
+        }
+        assert_eq!(client.send(&["CLIENT", "GETNAME"]).await, b"$0\r\n\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_list_connected_clients() {
+        let mut first = test_helpers::client().await;
+        let mut second = test_helpers::client_for(&first.server).await;
+        second.send(&["CLIENT", "SETNAME", "second"]).await;
+
+        let response = String::from_utf8(first.send(&["CLIENT", "LIST"]).await).unwrap();
+        let lines: Vec<&str> = response
+            .lines()
+            .filter(|line| line.starts_with("id="))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(&format!("id={} addr=127.0.0.1:", first.conn.id)));
+        assert!(lines[0].ends_with(" name= age=0"));
+        assert!(lines[1].starts_with(&format!("id={} ", second.conn.id)));
+        assert!(lines[1].ends_with(" name=second age=0"));
+
+        first.server.lock().await.unregister_client(second.conn.id);
+        let response = String::from_utf8(first.send(&["CLIENT", "LIST"]).await).unwrap();
+        assert_eq!(
+            response
+                .lines()
+                .filter(|line| line.starts_with("id="))
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_unique_ids() {
+        let mut first = test_helpers::client().await;
+        let mut second = test_helpers::client_for(&first.server).await;
+        let expected = format!(":{}\r\n", first.conn.id);
+        assert_eq!(first.send(&["CLIENT", "ID"]).await, expected.as_bytes());
+        assert_ne!(
+            first.send(&["CLIENT", "ID"]).await,
+            second.send(&["CLIENT", "ID"]).await
+        );
+    }
+}
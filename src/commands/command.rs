@@ -0,0 +1,117 @@
+// Library
+use super::COMMANDS;
+use crate::{
+    parser::resp::{self, Type},
+    server::connection::Connection,
+};
+
+// -------
+// COMMAND
+// -------
+
+/// Handles the COMMAND command.
+/// The COMMAND command is used by clients to introspect the commands supported by the server.
+/// The command is in the format `COMMAND [COUNT | DOCS [command ...]]`.
+/// `COMMAND COUNT` returns the number of supported commands.
+/// `COMMAND` and `COMMAND DOCS` return an array with an entry for every supported command
+/// (or only the requested ones). The entries are minimal and only contain the command name.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Extract the subcommand, if any
+    let subcommand = match args.first().map(Type::as_str) {
+        Some(Some(subcommand)) => Some(subcommand.to_uppercase()),
+        Some(None) => {
+            return connection
+                .write_error("ERR invalid subcommand for 'COMMAND' command")
+                .await
+        }
+        None => None,
+    };
+
+    let response = match subcommand.as_deref() {
+        // Each entry is an array holding the command name
+        None => resp::array(
+            COMMANDS
+                .iter()
+                .map(|name| resp::array(vec![name_of(name)]))
+                .collect(),
+        ),
+
+        Some("COUNT") => Type::Integer(COMMANDS.len() as i64),
+
+        // Each command name is followed by its (empty) documentation
+        Some("DOCS") => {
+            let names: Vec<String> = match &args[1..] {
+                [] => COMMANDS.iter().map(|name| name.to_string()).collect(),
+                requested => requested
+                    .iter()
+                    .filter_map(|name| name.as_str().map(str::to_uppercase))
+                    .filter(|name| COMMANDS.contains(&name.as_str()))
+                    .collect(),
+            };
+            resp::array(
+                names
+                    .iter()
+                    .flat_map(|name| [name_of(name), resp::array(vec![])])
+                    .collect(),
+            )
+        }
+
+        Some(subcommand) => {
+            return connection
+                .write_error(format!(
+                    "ERR unknown subcommand '{}'. Try COMMAND HELP.",
+                    subcommand
+                ))
+                .await
+        }
+    };
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+/// Returns the command name as a lowercase bulk string, as reported by Redis
+fn name_of(name: &str) -> Type {
+    Type::BulkString(name.to_lowercase().into())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::{test_helpers, COMMANDS};
+
+    #[tokio::test]
+    async fn should_count_registered_commands() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["COMMAND", "COUNT"]).await,
+            format!(":{}\r\n", COMMANDS.len()).as_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_docs_for_requested_commands() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client
+                .send(&["COMMAND", "DOCS", "get", "nosuchcommand"])
+                .await,
+            b"*2\r\n$3\r\nget\r\n*0\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_unknown_subcommand() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["COMMAND", "FOO"]).await,
+            b"-ERR unknown subcommand 'FOO'. Try COMMAND HELP.\r\n"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 // Library
 use crate::{
+    helpers,
     parser::resp::{self, Type},
     server::{connection::Connection, Server},
 };
@@ -13,7 +14,7 @@ use tokio::sync::Mutex;
 /// Handles the CONFIG command.
 /// The CONFIG command is used to read and write configuration parameters.
 pub async fn command(
-    args: &Vec<Type>,
+    args: &[Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -25,8 +26,8 @@ pub async fn command(
     }
 
     // Extract the subcommand from the arguments
-    let subcommand = match args.get(1) {
-        Some(Type::BulkString(subcommand)) => subcommand,
+    let subcommand = match args.get(1).and_then(Type::as_str) {
+        Some(subcommand) => subcommand,
         _ => {
             return connection.write_error("ERR invalid subcommand").await;
         }
@@ -35,7 +36,7 @@ pub async fn command(
     // Handle the subcommand
     match subcommand.to_string().to_uppercase().as_str() {
         "GET" => get(args, connection, server).await?,
-        // "SET" => set(args, connection, server).await?,
+        "SET" => set(args, connection, server).await?,
         x => {
             return connection
                 .write_error(format!("ERR unknown subcommand '{}'", x))
@@ -52,9 +53,11 @@ pub async fn command(
 
 /// Handles the CONFIG GET subcommand.
 /// The CONFIG GET subcommand is used to read configuration parameters.
-/// The subcommand is in the format `CONFIG GET 'key'`.
+/// The subcommand is in the format `CONFIG GET pattern [pattern ...]`.
+/// Each pattern is a glob-style pattern matched against the parameter names (e.g. `*file*`).
+/// The subcommand returns a flat array of the names and values of all the matching parameters.
 async fn get(
-    args: &Vec<Type>,
+    args: &[Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -65,46 +68,191 @@ async fn get(
             .await;
     }
 
-    // Extract the key from the arguments
-    let key = match args.get(2) {
-        Some(Type::BulkString(str)) => str,
-        _ => {
+    // Extract the patterns from the arguments
+    let patterns = match args[2..]
+        .iter()
+        .map(|pattern| pattern.as_str().map(str::to_lowercase))
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(patterns) => patterns,
+        None => {
             return connection.write_error("ERR invalid key").await;
         }
     };
 
-    // Get the value of the key
-    let value = get_config_value(key, server).await?;
+    // Collect the names and values of the matching parameters
+    let s = server.lock().await;
+    let response = resp::array(
+        PARAMETERS
+            .iter()
+            .filter(|name| {
+                patterns
+                    .iter()
+                    .any(|pattern| helpers::glob_match(pattern, name))
+            })
+            .flat_map(|name| {
+                [
+                    resp::bulk_string(name),
+                    resp::bulk_string(&get_config_value(name, &s)),
+                ]
+            })
+            .collect(),
+    );
+    drop(s);
 
-    // Write the value to the client
-    let response = resp::array(vec![resp::bulk_string(&key), resp::bulk_string(&value)]);
+    // Write the parameters to the client
     connection.write_all(&response.as_bytes()).await?;
 
     Ok(())
 }
 
-/// Gets the value of the configuration parameter with the given key.
-async fn get_config_value(
-    key: &String,
+// ---
+// SET
+// ---
+
+/// Handles the CONFIG SET subcommand.
+/// The CONFIG SET subcommand is used to change configuration parameters at runtime.
+/// The subcommand is in the format `CONFIG SET parameter value [parameter value ...]`.
+/// Only the `notify-keyspace-events` parameter can currently be changed.
+async fn set(
+    args: &[Type],
+    connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
-) -> Result<String, Box<dyn std::error::Error>> {
-    // Acquire the server lock
-    let s = server.lock().await;
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() < 4 || !args.len().is_multiple_of(2) {
+        return connection
+            .write_error("ERR wrong number of arguments for 'CONFIG SET' command")
+            .await;
+    }
 
-    // Get the value of the key
-    let value = match key.to_string().to_uppercase().as_str() {
-        "DIR" => s.db.dir.clone(),
+    // Validate all the parameters before changing any of them
+    let mut changes = Vec::new();
+    for pair in args[2..].chunks(2) {
+        let (Some(name), Some(value)) = (pair[0].as_str(), pair[1].as_str()) else {
+            return connection.write_error("ERR invalid parameter").await;
+        };
+        match name.to_lowercase().as_str() {
+            "notify-keyspace-events" => {
+                if !value
+                    .chars()
+                    .all(|flag| KEYSPACE_EVENT_FLAGS.contains(flag))
+                {
+                    return connection
+                        .write_error(format!(
+                            "ERR CONFIG SET failed (possibly related to argument '{}') - Invalid event class character. Use '{}'.",
+                            name, KEYSPACE_EVENT_FLAGS
+                        ))
+                        .await;
+                }
+                changes.push(value.to_string());
+            }
+            _ => {
+                return connection
+                    .write_error(format!(
+                        "ERR Unknown option or number of arguments for CONFIG SET - '{}'",
+                        name
+                    ))
+                    .await;
+            }
+        }
+    }
 
-        "DBFILENAME" => s.db.dbfilename.clone(),
+    // Apply the changes
+    let mut s = server.lock().await;
+    for value in changes {
+        s.notify_keyspace_events = value;
+    }
+    drop(s);
 
-        _ => {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "ERR invalid key",
-            )));
-        }
-    };
+    connection.write_ok().await
+}
+
+/// The flags accepted by the `notify-keyspace-events` parameter.
+/// `K` and `E` enable the keyspace and keyevent channels, and the others select the classes of events.
+const KEYSPACE_EVENT_FLAGS: &str = "KEg$lshzxetmdnA";
+
+/// The names of the configuration parameters that can be read with CONFIG GET
+const PARAMETERS: &[&str] = &[
+    "dir",
+    "dbfilename",
+    "port",
+    "maxmemory",
+    "appendonly",
+    "save",
+    "notify-keyspace-events",
+    "timeout",
+];
+
+/// Gets the value of the configuration parameter with the given name.
+/// Parameters that are not configurable in this server report the Redis defaults.
+fn get_config_value(name: &str, s: &Server) -> String {
+    match name {
+        "dir" => s.db.dir.clone(),
+        "dbfilename" => s.db.dbfilename.clone(),
+        "port" => s.port.to_string(),
+        "maxmemory" => "0".into(),
+        "appendonly" => "no".into(),
+        "notify-keyspace-events" => s.notify_keyspace_events.clone(),
+        "timeout" => s
+            .idle_timeout
+            .map_or(0, |timeout| timeout.as_secs())
+            .to_string(),
+        _ => String::new(),
+    }
+}
 
-    // Return the value
-    Ok(value)
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_parameters_matching_pattern() {
+        let mut client = test_helpers::client().await;
+        client.server.lock().await.db.dbfilename = "dump.rdb".into();
+        assert_eq!(
+            client.send(&["CONFIG", "GET", "*file*"]).await,
+            b"*2\r\n$10\r\ndbfilename\r\n$8\r\ndump.rdb\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_parameters_for_multiple_patterns() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client
+                .send(&["CONFIG", "GET", "MAXMEMORY", "append*", "nosuchparameter"])
+                .await,
+            b"*4\r\n$9\r\nmaxmemory\r\n$1\r\n0\r\n$10\r\nappendonly\r\n$2\r\nno\r\n"
+        );
+        assert_eq!(
+            client.send(&["CONFIG", "GET", "nosuchparameter"]).await,
+            b"*0\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_set_notify_keyspace_events() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client
+                .send(&["CONFIG", "SET", "notify-keyspace-events", "KEA"])
+                .await,
+            b"+OK\r\n"
+        );
+        assert_eq!(
+            client
+                .send(&["CONFIG", "GET", "notify-keyspace-events"])
+                .await,
+            b"*2\r\n$22\r\nnotify-keyspace-events\r\n$3\r\nKEA\r\n"
+        );
+        assert_eq!(
+            client.send(&["CONFIG", "SET", "dir", "/tmp"]).await,
+            b"-ERR Unknown option or number of arguments for CONFIG SET - 'dir'\r\n"
+        );
+    }
 }
@@ -0,0 +1,127 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// COPY
+// ----
+
+/// Handles the COPY command.
+/// The COPY command copies the value stored at the source key to the destination key.
+/// The command is in the format `COPY source destination [REPLACE]`.
+/// The remaining time-to-live of the source key is carried over to the destination.
+/// If the destination key already exists, it is only overwritten if the `REPLACE` option is given.
+/// The command returns 1 if the value was copied, and 0 otherwise.
+/// Returns true if the value was copied, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() < 2 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'COPY' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Parse the REPLACE option
+    let replace = match &args[2..] {
+        [] => false,
+        [option]
+            if option
+                .as_str()
+                .is_some_and(|option| option.eq_ignore_ascii_case("REPLACE")) =>
+        {
+            true
+        }
+        _ => {
+            if role.is_master() {
+                connection.write_error("ERR syntax error").await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // The source and destination must be different keys
+    if args[0] == args[1] {
+        if role.is_master() {
+            connection
+                .write_error("ERR source and destination objects are the same")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Copy the value in the database
+    let db = server.lock().await.db.clone();
+    let copied = db.copy(connection.db, &args[0], &args[1], replace).await;
+
+    // Respond with 1 if the value was copied, 0 otherwise
+    if role.is_master() {
+        let response = Type::Integer(copied as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(copied)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{commands::test_helpers, parser::resp::Type};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_copy_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "src", "v"]).await;
+        assert_eq!(client.send(&["COPY", "src", "dst"]).await, b":1\r\n");
+        assert_eq!(client.send(&["GET", "dst"]).await, b"$1\r\nv\r\n");
+        assert_eq!(client.send(&["COPY", "missing", "dst"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_only_overwrite_with_replace() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "src", "new"]).await;
+        client.send(&["SET", "dst", "old"]).await;
+        assert_eq!(client.send(&["COPY", "src", "dst"]).await, b":0\r\n");
+        assert_eq!(client.send(&["GET", "dst"]).await, b"$3\r\nold\r\n");
+        assert_eq!(
+            client.send(&["COPY", "src", "dst", "REPLACE"]).await,
+            b":1\r\n"
+        );
+        assert_eq!(client.send(&["GET", "dst"]).await, b"$3\r\nnew\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_carry_over_remaining_ttl() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "src", "v", "PX", "1000"]).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(client.send(&["COPY", "src", "dst"]).await, b":1\r\n");
+
+        let db = client.server.lock().await.db.clone();
+        let dst = Type::BulkString("dst".into());
+        let shard = db.read(&dst).await;
+        let ttl = shard.get_item(0, &dst).and_then(|item| item.ttl()).unwrap();
+        assert!((700..=800).contains(&ttl), "unexpected ttl {}", ttl);
+    }
+}
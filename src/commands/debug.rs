@@ -0,0 +1,306 @@
+// Library
+use crate::{
+    errors::Error,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
+use tokio::sync::Mutex;
+
+// -----
+// DEBUG
+// -----
+
+/// Handles the DEBUG command.
+/// The DEBUG command provides hooks to test the behaviour of the server.
+/// The command is in the format `DEBUG subcommand [argument ...]`.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Extract the subcommand from the arguments
+    let subcommand = match args.first().and_then(Type::as_str) {
+        Some(subcommand) => subcommand.to_uppercase(),
+        None => {
+            return connection
+                .write_error("ERR wrong number of arguments for 'DEBUG' command")
+                .await;
+        }
+    };
+
+    // Handle the subcommand
+    match subcommand.as_str() {
+        "OBJECT" => object(&args[1..], connection, server).await,
+        "SLEEP" => sleep(&args[1..], connection).await,
+        "SET-ACTIVE-EXPIRE" => set_active_expire(&args[1..], connection, server).await,
+        _ => {
+            connection
+                .write_error("ERR DEBUG subcommand not supported")
+                .await
+        }
+    }
+}
+
+// ------
+// OBJECT
+// ------
+
+/// Handles the DEBUG OBJECT subcommand.
+/// The DEBUG OBJECT subcommand returns information about the value of a key as a single line:
+/// its encoding and the length of its serialization, and for streams the number of entries and the last ID.
+/// The subcommand is in the format `DEBUG OBJECT key`.
+async fn object(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let key = match args {
+        [key] => key,
+        _ => {
+            return connection
+                .write_error("ERR wrong number of arguments for 'DEBUG OBJECT' command")
+                .await;
+        }
+    };
+
+    // Get the value of the key
+    let db = server.lock().await.db.clone();
+    let value = match db.get(connection.db, key).await {
+        Some(value) => value,
+        None => return connection.write_error("ERR no such key").await,
+    };
+
+    let mut line = format!(
+        "Value at:0 refcount:1 encoding:{} serializedlength:{}",
+        encoding(&value),
+        value.as_bytes().len()
+    );
+    if let Type::Stream(stream) = &value {
+        line.push_str(&format!(
+            " entries:{} last-id:{}",
+            stream.entries.len(),
+            stream.last_id
+        ));
+    }
+
+    let response = Type::SimpleString(line);
+    connection.write_all(&response.as_bytes()).await?;
+    Ok(())
+}
+
+/// The number of elements up to which aggregate values are kept in the compact `listpack` encoding
+const LISTPACK_MAX_ENTRIES: usize = 128;
+
+/// The length of the elements up to which aggregate values are kept in the compact `listpack` encoding
+const LISTPACK_MAX_VALUE: usize = 64;
+
+/// The number of members up to which a set of integers is kept in the `intset` encoding
+const INTSET_MAX_ENTRIES: usize = 512;
+
+/// Returns the name of the encoding Redis would use to store the value
+fn encoding(value: &Type) -> &'static str {
+    match value {
+        Type::BulkString(s) => match std::str::from_utf8(s).map(str::parse::<i64>) {
+            Ok(Ok(_)) => "int",
+            _ if s.len() <= 44 => "embstr",
+            _ => "raw",
+        },
+        Type::List(list) if is_compact(list.len(), list.iter().map(String::len)) => "listpack",
+        Type::List(_) => "quicklist",
+        Type::Set(set) => {
+            let members: Vec<&str> = set.iter().filter_map(Type::as_str).collect();
+            if set.len() <= INTSET_MAX_ENTRIES
+                && members.len() == set.len()
+                && members.iter().all(|member| member.parse::<i64>().is_ok())
+            {
+                "intset"
+            } else if is_compact(set.len(), members.iter().map(|member| member.len())) {
+                "listpack"
+            } else {
+                "hashtable"
+            }
+        }
+        Type::Hash(hash) => {
+            let lengths = hash
+                .iter()
+                .flat_map(|(field, value)| [field.len(), value.len()]);
+            match is_compact(hash.len(), lengths) {
+                true => "listpack",
+                false => "hashtable",
+            }
+        }
+        Type::SortedSet(members) => {
+            match is_compact(
+                members.len(),
+                members.iter().map(|(member, _)| member.len()),
+            ) {
+                true => "listpack",
+                false => "skiplist",
+            }
+        }
+        Type::Stream(_) => "stream",
+        _ => "unknown",
+    }
+}
+
+/// Returns true if an aggregate value with the given number of elements, and element lengths,
+/// is small enough for the compact `listpack` encoding
+fn is_compact(len: usize, mut lengths: impl Iterator<Item = usize>) -> bool {
+    len <= LISTPACK_MAX_ENTRIES && lengths.all(|len| len <= LISTPACK_MAX_VALUE)
+}
+
+// -----
+// SLEEP
+// -----
+
+/// Handles the DEBUG SLEEP subcommand.
+/// The DEBUG SLEEP subcommand pauses the handling of the connection for the given number of seconds.
+/// The subcommand is in the format `DEBUG SLEEP seconds`, where the seconds may be fractional.
+async fn sleep(
+    args: &[Type],
+    connection: &mut Connection,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let seconds = match args {
+        [seconds] => seconds.as_str().and_then(|s| s.parse::<f64>().ok()),
+        _ => {
+            return connection
+                .write_error("ERR wrong number of arguments for 'DEBUG SLEEP' command")
+                .await;
+        }
+    };
+    let duration = match seconds.and_then(|s| Duration::try_from_secs_f64(s).ok()) {
+        Some(duration) => duration,
+        None => {
+            return connection
+                .write_error("ERR value is not a valid float")
+                .await
+        }
+    };
+
+    tokio::time::sleep(duration).await;
+    connection.write_ok().await
+}
+
+// -----------------
+// SET-ACTIVE-EXPIRE
+// -----------------
+
+/// Handles the DEBUG SET-ACTIVE-EXPIRE subcommand.
+/// The DEBUG SET-ACTIVE-EXPIRE subcommand enables (1) or disables (0) the background eviction of expired keys.
+/// Expired keys are still removed when they are accessed while it is disabled.
+/// The subcommand is in the format `DEBUG SET-ACTIVE-EXPIRE 0|1`.
+async fn set_active_expire(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let active = match args {
+        [flag] => match flag.as_str() {
+            Some("0") => false,
+            Some("1") => true,
+            _ => {
+                return connection.write_error(Error::NotInteger.to_string()).await;
+            }
+        },
+        _ => {
+            return connection
+                .write_error("ERR wrong number of arguments for 'DEBUG SET-ACTIVE-EXPIRE' command")
+                .await;
+        }
+    };
+
+    server
+        .lock()
+        .await
+        .active_expire
+        .store(active, Ordering::Relaxed);
+    connection.write_ok().await
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::{sync::atomic::Ordering, time::Duration};
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn should_reply_ok_after_sleeping() {
+        let mut client = test_helpers::client().await;
+        let reply = timeout(
+            Duration::from_millis(50),
+            client.send(&["DEBUG", "SLEEP", "0"]),
+        );
+        assert_eq!(reply.await.unwrap(), b"+OK\r\n");
+        assert_eq!(
+            client.send(&["DEBUG", "SLEEP", "-1"]).await,
+            b"-ERR value is not a valid float\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_toggle_active_expire() {
+        let mut client = test_helpers::client().await;
+        let active = client.server.lock().await.active_expire.clone();
+        assert_eq!(
+            client.send(&["DEBUG", "SET-ACTIVE-EXPIRE", "0"]).await,
+            b"+OK\r\n"
+        );
+        assert!(!active.load(Ordering::Relaxed));
+        assert_eq!(
+            client.send(&["DEBUG", "set-active-expire", "1"]).await,
+            b"+OK\r\n"
+        );
+        assert!(active.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn should_describe_string_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "n", "12345"]).await;
+        client.send(&["SET", "s", "hello"]).await;
+        assert_eq!(
+            client.send(&["DEBUG", "OBJECT", "n"]).await,
+            b"+Value at:0 refcount:1 encoding:int serializedlength:11\r\n"
+        );
+        assert_eq!(
+            client.send(&["DEBUG", "OBJECT", "s"]).await,
+            b"+Value at:0 refcount:1 encoding:embstr serializedlength:11\r\n"
+        );
+        assert_eq!(
+            client.send(&["DEBUG", "OBJECT", "missing"]).await,
+            b"-ERR no such key\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_describe_stream_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        client.send(&["XADD", "s", "2-5", "f", "v"]).await;
+        let response = String::from_utf8(client.send(&["DEBUG", "OBJECT", "s"]).await).unwrap();
+        assert!(response.starts_with("+Value at:0 refcount:1 encoding:stream serializedlength:"));
+        assert!(response.ends_with(" entries:2 last-id:2-5\r\n"));
+
+        // The last ID is kept even when the stream is emptied
+        client.send(&["XDEL", "s", "1-1", "2-5"]).await;
+        let response = String::from_utf8(client.send(&["DEBUG", "OBJECT", "s"]).await).unwrap();
+        assert!(response.ends_with(" entries:0 last-id:2-5\r\n"));
+    }
+
+    #[tokio::test]
+    async fn should_reject_unknown_subcommand() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["DEBUG", "SEGFAULT"]).await,
+            b"-ERR DEBUG subcommand not supported\r\n"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ---
+// DEL
+// ---
+
+/// Handles the DEL command.
+/// The DEL command removes the specified keys from the database.
+/// The command is in the format `DEL key [key ...]`.
+/// Keys that do not exist are ignored.
+/// The command returns the number of keys that were actually removed.
+/// Returns true if any key was deleted, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.is_empty() {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'DEL' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Remove each key from the database, counting the ones that existed
+    let db = server.lock().await.db.clone();
+    let mut deleted = 0;
+    for key in args {
+        if db.remove(connection.db, key).await.is_some() {
+            deleted += 1;
+        }
+    }
+
+    // Respond with the number of deleted keys
+    if role.is_master() {
+        let response = Type::Integer(deleted as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(deleted > 0)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_delete_existing_keys() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        client.send(&["SET", "b", "2"]).await;
+        assert_eq!(client.send(&["DEL", "a", "b", "c"]).await, b":2\r\n");
+        assert_eq!(client.send(&["GET", "a"]).await, b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_return_zero_for_missing_keys() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["DEL", "missing"]).await, b":0\r\n");
+    }
+}
@@ -7,19 +7,20 @@ use crate::{parser::resp::Type, server::connection::Connection};
 
 /// Handles the ECHO command.
 /// The ECHO command simply returns the argument provided to it.
+/// The command is in the format `ECHO message`.
 pub async fn command(
     args: &[Type],
     connection: &mut Connection,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Check the number of arguments
-    if args.len() < 1 {
+    if args.len() != 1 {
         return connection
-            .write_error("ERR wrong number of arguments for 'ECHO' command. Usage ECHO message")
+            .write_error("ERR wrong number of arguments for 'echo' command")
             .await;
     }
 
     // Respond with the argument
-    let response = match args.get(0) {
+    let response = match args.first() {
         Some(Type::BulkString(arg)) => Type::BulkString(arg.clone()),
         _ => Type::SimpleError("ERR invalid argument type for 'ECHO' command".into()),
     };
@@ -27,3 +28,29 @@ pub async fn command(
 
     Ok(())
 }
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_echo_binary_message() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send_bytes(&[b"ECHO", b"\x00\xFF\r\n"]).await,
+            b"$4\r\n\x00\xFF\r\n\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_wrong_number_of_arguments() {
+        let mut client = test_helpers::client().await;
+        let error = b"-ERR wrong number of arguments for 'echo' command\r\n";
+        assert_eq!(client.send(&["ECHO"]).await, error);
+        assert_eq!(client.send(&["ECHO", "a", "b"]).await, error);
+    }
+}
@@ -0,0 +1,76 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// EXISTS
+// ------
+
+/// Handles the EXISTS command.
+/// The EXISTS command checks whether the specified keys exist in the database.
+/// The command is in the format `EXISTS key [key ...]`.
+/// The command returns the number of keys that exist. If the same key is
+/// mentioned multiple times, it is counted multiple times.
+/// Expired keys are treated as if they do not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.is_empty() {
+        return connection
+            .write_error("ERR wrong number of arguments for 'EXISTS' command")
+            .await;
+    }
+
+    // Count the keys that exist in the database
+    let db = server.lock().await.db.clone();
+    let mut count = 0;
+    for key in args {
+        if db.get(connection.db, key).await.is_some() {
+            count += 1;
+        }
+    }
+
+    // Respond with the number of existing keys
+    let response = Type::Integer(count as i64);
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_return_zero_for_missing_key() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["EXISTS", "missing"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_not_count_expired_keys() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1", "PX", "1"]).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(client.send(&["EXISTS", "a"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_count_duplicate_keys() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        assert_eq!(client.send(&["EXISTS", "a", "a", "b"]).await, b":2\r\n");
+    }
+}
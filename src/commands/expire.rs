@@ -0,0 +1,110 @@
+// Library
+use crate::{
+    errors::Error,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// --------------
+// EXPIRE/PEXPIRE
+// --------------
+
+/// Handles the EXPIRE and PEXPIRE commands.
+/// The commands set a timeout on an existing key, after which the key is deleted.
+/// The commands are in the format `EXPIRE key seconds` and `PEXPIRE key milliseconds`.
+/// The `unit` is the number of milliseconds per unit of the timeout: 1000 for EXPIRE and 1 for PEXPIRE.
+/// The command returns 1 if the timeout was set, and 0 if the key does not exist.
+/// Returns true if the timeout was set, in which case the command should be propagated to replicas.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+    unit: usize,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 2 {
+        if role.is_master() {
+            connection
+                .write_error(format!(
+                    "ERR wrong number of arguments for '{}' command",
+                    name
+                ))
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the timeout from the arguments and convert it to milliseconds
+    let expiry = args[1]
+        .as_str()
+        .and_then(|timeout| timeout.parse::<usize>().ok())
+        .and_then(|timeout| timeout.checked_mul(unit));
+    let expiry = match expiry {
+        Some(expiry) => expiry,
+        None => {
+            if role.is_master() {
+                connection
+                    .write_error(Error::NotInteger.to_string())
+                    .await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Set the expiry on the key
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let updated = shard.set_expiry(connection.db, &args[0], Some(expiry));
+
+    // Respond with 1 if the key existed, 0 otherwise
+    if role.is_master() {
+        let response = Type::Integer(updated as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(updated)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_expire_existing_key() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        assert_eq!(client.send(&["PEXPIRE", "a", "1"]).await, b":1\r\n");
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(client.send(&["EXISTS", "a"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_return_zero_for_missing_key() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["EXPIRE", "missing", "10"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_timeout() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        assert_eq!(
+            client.send(&["EXPIRE", "a", "soon"]).await,
+            b"-ERR value is not an integer or out of range\r\n"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----------------
+// FLUSHALL/FLUSHDB
+// ----------------
+
+/// Handles the FLUSHALL and FLUSHDB commands.
+/// FLUSHALL removes all keys from every database, while FLUSHDB only removes the keys
+/// from the database currently selected by the connection.
+/// The commands are in the format `FLUSHALL [ASYNC | SYNC]` and `FLUSHDB [ASYNC | SYNC]`.
+/// The flush is always performed synchronously.
+/// The command returns OK once the database has been cleared.
+/// Returns true if the database was cleared, in which case the command should be propagated to replicas.
+pub async fn command(
+    all: bool,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Validate the optional flush mode
+    let valid = match args {
+        [] => true,
+        [mode] => mode
+            .as_str()
+            .is_some_and(|mode| matches!(mode.to_uppercase().as_str(), "ASYNC" | "SYNC")),
+        _ => false,
+    };
+    if !valid {
+        if role.is_master() {
+            connection.write_error("ERR syntax error").await?;
+        }
+        return Ok(false);
+    }
+
+    // Clear the database(s)
+    let db = server.lock().await.db.clone();
+    if all {
+        db.clear_all().await;
+    } else {
+        db.clear(connection.db).await;
+    }
+
+    if role.is_master() {
+        connection.write_ok().await?;
+    }
+
+    Ok(true)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_remove_all_keys() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        client.send(&["SET", "b", "2"]).await;
+        assert_eq!(client.send(&["FLUSHALL"]).await, b"+OK\r\n");
+        assert!(client
+            .server
+            .lock()
+            .await
+            .db
+            .clone()
+            .keys(0)
+            .await
+            .is_empty());
+
+        client.send(&["SET", "c", "3"]).await;
+        assert_eq!(client.send(&["FLUSHDB", "ASYNC"]).await, b"+OK\r\n");
+        assert!(client
+            .server
+            .lock()
+            .await
+            .db
+            .clone()
+            .keys(0)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_only_flush_selected_database() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        client.send(&["SELECT", "1"]).await;
+        client.send(&["SET", "b", "2"]).await;
+        assert_eq!(client.send(&["FLUSHDB"]).await, b"+OK\r\n");
+        assert!(client
+            .server
+            .lock()
+            .await
+            .db
+            .clone()
+            .keys(1)
+            .await
+            .is_empty());
+        assert_eq!(client.server.lock().await.db.clone().keys(0).await.len(), 1);
+    }
+}
@@ -12,23 +12,23 @@ use tokio::sync::Mutex;
 
 /// Handles the GET command.
 /// The GET command gets the value of a key in the database.
-/// The command returns the value if the key exists.
-/// The command returns an error if the number of arguments is invalid.
-/// The command returns an error if the key does not exist.
+/// The command returns the value if the key exists, and null if it does not.
+/// The command returns an error if the number of arguments is invalid,
+/// or if the key holds a value that is not a string.
 pub async fn command(
     args: &[Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Check the number of arguments
-    if args.len() < 1 {
+    if args.is_empty() {
         return connection
             .write_error("ERR wrong number of arguments for 'GET' command. Usage GET key")
             .await;
     }
 
     // Extract the key from the arguments
-    let key = match args.get(0) {
+    let key = match args.first() {
         Some(key) => key,
         None => {
             return connection.write_error("ERR invalid key").await;
@@ -36,10 +36,12 @@ pub async fn command(
     };
 
     // Get the value from the database
-    let server = server.lock().await;
-    let response = match server.db.get(key) {
-        Some(value) => value.clone(),
-        None => Type::BulkString("".into()),
+    let db = server.lock().await.db.clone();
+    let shard = db.read(key).await;
+    let response = match shard.expect_string(connection.db, key) {
+        Ok(Some(value)) => Type::BulkString(value.to_vec()),
+        Ok(None) => Type::Null,
+        Err(e) => Type::SimpleError(e.to_string()),
     };
 
     // Respond with the value
@@ -47,3 +49,48 @@ pub async fn command(
 
     Ok(())
 }
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_null_for_missing_key() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["GET", "missing"]).await, b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_distinguish_empty_value_from_missing_key() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SET", "k", ""]).await, b"+OK\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$0\r\n\r\n");
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_store_binary_values() {
+        let mut client = test_helpers::client().await;
+        let value = b"\xFF\x00a\r\nb";
+        client.send_bytes(&[b"SET", b"k", value]).await;
+        assert_eq!(
+            client.send(&["GET", "k"]).await,
+            b"$6\r\n\xFF\x00a\r\nb\r\n"
+        );
+        assert_eq!(client.send(&["STRLEN", "k"]).await, b":6\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_string_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        assert_eq!(
+            client.send(&["GET", "s"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+// Library
+use crate::{
+    commands::setbit,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// GETBIT
+// ------
+
+/// Handles the GETBIT command.
+/// The GETBIT command returns the bit at the offset in the string stored at the key.
+/// The command is in the format `GETBIT key offset`.
+/// Bit `0` is the most significant bit of the first byte. Bits beyond the end of the string,
+/// and bits of a missing key, are 0.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'GETBIT' command")
+            .await;
+    }
+
+    // Extract the offset from the arguments
+    let Some(offset) = setbit::parse_offset(&args[1]) else {
+        return connection
+            .write_error("ERR bit offset is not an integer or out of range")
+            .await;
+    };
+
+    // Read the bit from the string stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.expect_string(connection.db, &args[0]) {
+        Ok(value) => {
+            let byte = value.unwrap_or_default().get(offset / 8).copied();
+            let bit = byte.unwrap_or(0) & (0x80 >> (offset % 8)) != 0;
+            Type::Integer(bit as i64)
+        }
+        Err(e) => Type::SimpleError(e.to_string()),
+    };
+
+    // Respond with the bit
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_bits_of_string() {
+        let mut client = test_helpers::client().await;
+        // `a` is 0b01100001
+        client.send(&["SET", "k", "a"]).await;
+        assert_eq!(client.send(&["GETBIT", "k", "0"]).await, b":0\r\n");
+        assert_eq!(client.send(&["GETBIT", "k", "1"]).await, b":1\r\n");
+        assert_eq!(client.send(&["GETBIT", "k", "7"]).await, b":1\r\n");
+        assert_eq!(client.send(&["GETBIT", "k", "100"]).await, b":0\r\n");
+        assert_eq!(client.send(&["GETBIT", "missing", "0"]).await, b":0\r\n");
+    }
+}
@@ -0,0 +1,90 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// GETDEL
+// ------
+
+/// Handles the GETDEL command.
+/// The GETDEL command gets the value of a key and removes the key from the database.
+/// The command is in the format `GETDEL key`.
+/// The command returns the value of the key, or null if the key does not exist.
+/// The command returns an error (and leaves the key untouched) if the value is not a string.
+/// Returns true if the key was deleted, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 1 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'GETDEL' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Remove the key from the database, if it holds a string
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::BulkString(_)) => match shard.remove(connection.db, &args[0]) {
+            Some(item) => item.value,
+            None => Type::Null,
+        },
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => Type::Null,
+    };
+
+    let deleted = matches!(response, Type::BulkString(_));
+
+    // Respond with the removed value
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(deleted)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_and_remove_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(client.send(&["GETDEL", "k"]).await, b"$1\r\nv\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$-1\r\n");
+        assert_eq!(client.send(&["GETDEL", "k"]).await, b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_stream_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        assert_eq!(
+            client.send(&["GETDEL", "s"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+        assert_eq!(client.send(&["EXISTS", "s"]).await, b":1\r\n");
+    }
+}
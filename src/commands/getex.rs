@@ -0,0 +1,159 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -----
+// GETEX
+// -----
+
+/// Handles the GETEX command.
+/// The GETEX command gets the value of a key and optionally updates its expiry.
+/// The command is in the format `GETEX key [EX seconds | PX milliseconds | PERSIST]`.
+/// `EX` and `PX` set a new expiry, `PERSIST` removes the existing expiry,
+/// and without an option the expiry is left unchanged.
+/// The command returns the value of the key, or null if the key does not exist.
+/// Returns true if the expiry was changed, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.is_empty() {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'GETEX' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Parse the expiry option. The outer `Option` is whether the expiry should be changed,
+    // and the inner `Option` is the new expiry in milliseconds (`None` to persist the key).
+    let expiry = match parse_expiry(&args[1..]) {
+        Ok(expiry) => expiry,
+        Err(err) => {
+            if role.is_master() {
+                connection.write_error(err).await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Get the value, and update the expiry if it is a string
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]).cloned() {
+        Some(Type::BulkString(value)) => {
+            if let Some(expiry) = expiry {
+                shard.set_expiry(connection.db, &args[0], expiry);
+            }
+            Type::BulkString(value)
+        }
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => Type::Null,
+    };
+
+    let updated = expiry.is_some() && matches!(response, Type::BulkString(_));
+
+    // Respond with the value
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(updated)
+}
+
+/// Parses the GETEX expiry option from the arguments following the key.
+fn parse_expiry(args: &[Type]) -> Result<Option<Option<usize>>, String> {
+    let option = match args.first().map(Type::as_str) {
+        Some(Some(option)) => option.to_uppercase(),
+        Some(None) => return Err("ERR syntax error".into()),
+        None => return Ok(None),
+    };
+
+    // Determine the number of milliseconds per unit of the expiry
+    let unit = match (option.as_str(), args.len()) {
+        ("PERSIST", 1) => return Ok(Some(None)),
+        ("EX", 2) => 1000,
+        ("PX", 2) => 1,
+        _ => return Err("ERR syntax error".into()),
+    };
+
+    // Parse the expiry and convert it to milliseconds
+    let expiry = args[1]
+        .as_str()
+        .and_then(|expiry| expiry.parse::<usize>().ok())
+        .filter(|expiry| *expiry > 0)
+        .and_then(|expiry| expiry.checked_mul(unit))
+        .ok_or("ERR invalid expire time in 'getex' command")?;
+
+    Ok(Some(Some(expiry)))
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_return_value_without_changing_expiry() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(client.send(&["GETEX", "k"]).await, b"$1\r\nv\r\n");
+        assert_eq!(client.send(&["PTTL", "k"]).await, b":-1\r\n");
+        assert_eq!(client.send(&["GETEX", "missing"]).await, b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_set_new_expiry() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["GETEX", "k", "PX", "10"]).await,
+            b"$1\r\nv\r\n"
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(client.send(&["GET", "k"]).await, b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_persist_key() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v", "EX", "100"]).await;
+        assert_eq!(
+            client.send(&["GETEX", "k", "PERSIST"]).await,
+            b"$1\r\nv\r\n"
+        );
+        assert_eq!(client.send(&["PTTL", "k"]).await, b":-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_options() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["GETEX", "k", "EX"]).await,
+            b"-ERR syntax error\r\n"
+        );
+        assert_eq!(
+            client.send(&["GETEX", "k", "EX", "0"]).await,
+            b"-ERR invalid expire time in 'getex' command\r\n"
+        );
+    }
+}
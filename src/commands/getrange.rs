@@ -0,0 +1,102 @@
+// Library
+use crate::{
+    commands::lrange,
+    errors::Error,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// --------
+// GETRANGE
+// --------
+
+/// Handles the GETRANGE command.
+/// The GETRANGE command returns the substring of the string stored at the key between two byte offsets.
+/// The command is in the format `GETRANGE key start end`.
+/// Both offsets are inclusive, and negative offsets count from the end of the string, so `-1` is the last byte.
+/// Out of range offsets are clamped to the string bounds.
+/// The command returns an empty string if the key does not exist or the range is empty.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 3 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'GETRANGE' command")
+            .await;
+    }
+
+    // Extract the start and end offsets from the arguments
+    let (start, end) = match (
+        args[1].as_str().and_then(|start| start.parse::<i64>().ok()),
+        args[2].as_str().and_then(|end| end.parse::<i64>().ok()),
+    ) {
+        (Some(start), Some(end)) => (start, end),
+        _ => {
+            return connection.write_error(Error::NotInteger.to_string()).await;
+        }
+    };
+
+    // Extract the range from the string stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.expect_string(connection.db, &args[0]) {
+        Ok(value) => {
+            let value = value.unwrap_or_default();
+            match lrange::range(value.len(), start, end) {
+                Some((start, end)) => Type::BulkString(value[start..=end].to_vec()),
+                None => Type::BulkString(Vec::new()),
+            }
+        }
+        Err(e) => Type::SimpleError(e.to_string()),
+    };
+
+    // Respond with the substring
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_substring_with_negative_indices() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "This is a string"]).await;
+        assert_eq!(
+            client.send(&["GETRANGE", "k", "0", "3"]).await,
+            b"$4\r\nThis\r\n"
+        );
+        assert_eq!(
+            client.send(&["GETRANGE", "k", "-3", "-1"]).await,
+            b"$3\r\ning\r\n"
+        );
+        assert_eq!(
+            client.send(&["GETRANGE", "k", "10", "100"]).await,
+            b"$6\r\nstring\r\n"
+        );
+        assert_eq!(
+            client.send(&["GETRANGE", "k", "5", "2"]).await,
+            b"$0\r\n\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_empty_string_for_missing_key() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["GETRANGE", "missing", "0", "-1"]).await,
+            b"$0\r\n\r\n"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// HDEL
+// ----
+
+/// Handles the HDEL command.
+/// The HDEL command removes the specified fields from the hash stored at the key.
+/// The command is in the format `HDEL key field [field ...]`.
+/// Fields that do not exist are ignored, and the key is removed once the hash is empty.
+/// The command returns the number of fields that were removed.
+/// Returns true if any field was removed, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() < 2 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'HDEL' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Remove the fields from the hash stored at the key
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let removed = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::Hash(hash)) => {
+            let removed = args[1..]
+                .iter()
+                .filter(|field| match field.as_str() {
+                    Some(field) => hash.remove(field).is_some(),
+                    None => false,
+                })
+                .count();
+            if hash.is_empty() {
+                shard.remove(connection.db, &args[0]);
+            }
+            removed
+        }
+        Some(_) => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+        None => 0,
+    };
+
+    // Respond with the number of removed fields
+    if role.is_master() {
+        let response = Type::Integer(removed as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(removed > 0)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_remove_fields_and_empty_hash() {
+        let mut client = test_helpers::client().await;
+        client.send(&["HSET", "h", "a", "1", "b", "2"]).await;
+        assert_eq!(client.send(&["HDEL", "h", "a", "c"]).await, b":1\r\n");
+        assert_eq!(client.send(&["HLEN", "h"]).await, b":1\r\n");
+        assert_eq!(client.send(&["HDEL", "h", "b"]).await, b":1\r\n");
+        assert_eq!(client.send(&["EXISTS", "h"]).await, b":0\r\n");
+    }
+}
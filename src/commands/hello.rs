@@ -0,0 +1,145 @@
+// Library
+use crate::{
+    parser::resp::{self, Type},
+    server::{self, connection::Connection, Server},
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+// -----
+// HELLO
+// -----
+
+/// Handles the HELLO command.
+/// The HELLO command negotiates the RESP protocol version used by the connection.
+/// The command is in the format `HELLO [protover]`. Without a version, the current protocol is kept.
+/// The command returns information about the server as a map on RESP3,
+/// or as a flat array of alternating keys and values on RESP2.
+/// The command returns a NOPROTO error if the requested version is not supported.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Parse the requested protocol version, if any
+    let protocol = match args {
+        [] => connection.protocol,
+        [version] => match version
+            .as_str()
+            .and_then(|version| version.parse::<i64>().ok())
+        {
+            Some(2) => 2,
+            Some(3) => 3,
+            Some(_) => {
+                return connection
+                    .write_error("NOPROTO unsupported protocol version")
+                    .await
+            }
+            None => {
+                return connection
+                    .write_error("ERR Protocol version is not an integer or out of range")
+                    .await
+            }
+        },
+        _ => return connection.write_error("ERR syntax error").await,
+    };
+
+    // Record the negotiated protocol on the connection
+    connection.protocol = protocol;
+
+    // Describe the server
+    let role = {
+        let server = server.lock().await;
+        match server.role.is_master() {
+            true => "master",
+            false => "replica",
+        }
+    };
+    let fields = vec![
+        ("server", resp::bulk_string("redis")),
+        ("version", resp::bulk_string(server::VERSION)),
+        ("proto", Type::Integer(protocol as i64)),
+        ("id", Type::Integer(connection.id as i64)),
+        ("mode", resp::bulk_string("standalone")),
+        ("role", resp::bulk_string(role)),
+    ];
+
+    // Respond with a map on RESP3, and a flat array on RESP2
+    let response = match protocol {
+        3 => Type::Map(
+            fields
+                .into_iter()
+                .map(|(key, value)| (resp::bulk_string(key), value))
+                .collect::<HashMap<_, _>>(),
+        ),
+        _ => resp::array(
+            fields
+                .into_iter()
+                .flat_map(|(key, value)| [resp::bulk_string(key), value])
+                .collect(),
+        ),
+    };
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::test_helpers,
+        parser::{self, resp::Type},
+    };
+
+    /// Parses the single RESP value in the response
+    fn parse(response: &[u8]) -> Type {
+        parser::parse(response).unwrap().remove(0)
+    }
+
+    #[tokio::test]
+    async fn should_reply_with_map_on_resp3() {
+        let mut client = test_helpers::client().await;
+        let map = match parse(&client.send(&["HELLO", "3"]).await) {
+            Type::Map(map) => map,
+            other => panic!("expected a map, got {:?}", other),
+        };
+        let get = |key: &str| map.get(&Type::BulkString(key.into())).cloned();
+        assert_eq!(get("server"), Some(Type::BulkString("redis".into())));
+        assert_eq!(get("proto"), Some(Type::Integer(3)));
+        assert_eq!(get("mode"), Some(Type::BulkString("standalone".into())));
+        assert_eq!(get("role"), Some(Type::BulkString("master".into())));
+        assert!(matches!(get("version"), Some(Type::BulkString(_))));
+        assert!(matches!(get("id"), Some(Type::Integer(_))));
+    }
+
+    #[tokio::test]
+    async fn should_reply_with_array_on_resp2() {
+        let mut client = test_helpers::client().await;
+        let response = parse(&client.send(&["HELLO"]).await);
+        match response {
+            Type::Array(elements) => {
+                assert_eq!(elements.len(), 12);
+                assert_eq!(elements[4], Type::BulkString("proto".into()));
+                assert_eq!(elements[5], Type::Integer(2));
+            }
+            other => panic!("expected an array, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_reject_unsupported_protocol() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["HELLO", "4"]).await,
+            b"-NOPROTO unsupported protocol version\r\n"
+        );
+        assert_eq!(
+            client.send(&["HELLO", "three"]).await,
+            b"-ERR Protocol version is not an integer or out of range\r\n"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// HGET
+// ----
+
+/// Handles the HGET command.
+/// The HGET command returns the value associated with the field in the hash stored at the key.
+/// The command is in the format `HGET key field`.
+/// The command returns null if the field or the key does not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'HGET' command")
+            .await;
+    }
+
+    // Extract the field from the arguments
+    let field = match args[1].as_str() {
+        Some(field) => field,
+        None => return connection.write_error("ERR invalid field").await,
+    };
+
+    // Get the value of the field from the hash stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::Hash(hash)) => match hash.get(field) {
+            Some(value) => resp::bulk_string(value),
+            None => Type::Null,
+        },
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => Type::Null,
+    };
+
+    // Respond with the value
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_null_for_missing_field() {
+        let mut client = test_helpers::client().await;
+        client.send(&["HSET", "h", "a", "1"]).await;
+        assert_eq!(client.send(&["HGET", "h", "b"]).await, b"$-1\r\n");
+        assert_eq!(client.send(&["HGET", "missing", "a"]).await, b"$-1\r\n");
+    }
+}
@@ -0,0 +1,68 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -------
+// HGETALL
+// -------
+
+/// Handles the HGETALL command.
+/// The HGETALL command returns all fields and values of the hash stored at the key.
+/// The command is in the format `HGETALL key`.
+/// The command returns a flat array in which every field is followed by its value,
+/// or an empty array if the key does not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 1 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'HGETALL' command")
+            .await;
+    }
+
+    // Flatten the hash stored at the key into field/value pairs
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::Hash(hash)) => resp::array(
+            hash.iter()
+                .flat_map(|(field, value)| [resp::bulk_string(field), resp::bulk_string(value)])
+                .collect(),
+        ),
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => resp::array(Vec::new()),
+    };
+
+    // Respond with the fields and values
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_flat_field_value_pairs() {
+        let mut client = test_helpers::client().await;
+        client.send(&["HSET", "h", "a", "1"]).await;
+        assert_eq!(
+            client.send(&["HGETALL", "h"]).await,
+            b"*2\r\n$1\r\na\r\n$1\r\n1\r\n"
+        );
+        assert_eq!(client.send(&["HGETALL", "missing"]).await, b"*0\r\n");
+    }
+}
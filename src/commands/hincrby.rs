@@ -0,0 +1,120 @@
+// Library
+use crate::{
+    database::WrongType,
+    errors::Error,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+// -------
+// HINCRBY
+// -------
+
+/// Handles the HINCRBY command.
+/// The HINCRBY command increments the integer value of the field in the hash stored at the key by the given amount.
+/// The command is in the format `HINCRBY key field increment`.
+/// A missing field is set to 0 before performing the operation, and a missing key creates a new hash.
+/// The command returns the value of the field after the operation.
+/// Returns true if the field was incremented, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 3 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'HINCRBY' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Apply the increment and respond with the new value
+    let response = increment(connection.db, args, server).await;
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(matches!(response, Type::Integer(_)))
+}
+
+/// Increments the integer value of the field by the delta, given the key, field and delta arguments.
+/// Returns the new value as an `Integer`, or a `SimpleError` if the operation could not be performed.
+async fn increment(db: usize, args: &[Type], server: &Arc<Mutex<Server>>) -> Type {
+    // Extract the field and the delta from the arguments
+    let Some(field) = args[1].as_str() else {
+        return Type::SimpleError("ERR invalid field".into());
+    };
+    let Some(delta) = args[2].as_str().and_then(|delta| delta.parse::<i64>().ok()) else {
+        return Type::SimpleError(Error::NotInteger.to_string());
+    };
+
+    // Get the hash stored at the key, creating it if it does not exist
+    let database = server.lock().await.db.clone();
+    let mut shard = database.write(&args[0]).await;
+    if shard.get(db, &args[0]).is_none() {
+        shard.set(db, args[0].clone(), Type::Hash(HashMap::new()), None);
+    }
+    let hash = match shard.get_mut(db, &args[0]) {
+        Some(Type::Hash(hash)) => hash,
+        _ => return Type::SimpleError(WrongType.to_string()),
+    };
+
+    // Get the current value, treating a missing field as 0
+    let current = match hash.get(field) {
+        Some(value) => match value.parse::<i64>() {
+            Ok(value) => value,
+            Err(_) => return Type::SimpleError("ERR hash value is not an integer".into()),
+        },
+        None => 0,
+    };
+
+    // Apply the delta, checking for overflow
+    let Some(value) = current.checked_add(delta) else {
+        return Type::SimpleError("ERR increment or decrement would overflow".into());
+    };
+    hash.insert(field.to_string(), value.to_string());
+
+    Type::Integer(value)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_create_field_when_missing() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["HINCRBY", "h", "f", "5"]).await, b":5\r\n");
+        assert_eq!(client.send(&["HINCRBY", "h", "f", "-7"]).await, b":-2\r\n");
+        assert_eq!(client.send(&["HGET", "h", "f"]).await, b"$2\r\n-2\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_integer_field_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["HSET", "h", "f", "abc"]).await;
+        assert_eq!(
+            client.send(&["HINCRBY", "h", "f", "1"]).await,
+            b"-ERR hash value is not an integer\r\n"
+        );
+        assert_eq!(
+            client.send(&["HINCRBY", "h", "g", "1.5"]).await,
+            b"-ERR value is not an integer or out of range\r\n"
+        );
+    }
+}
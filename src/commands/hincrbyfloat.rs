@@ -0,0 +1,128 @@
+// Library
+use super::incrbyfloat::parse_float;
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+// ------------
+// HINCRBYFLOAT
+// ------------
+
+/// Handles the HINCRBYFLOAT command.
+/// The HINCRBYFLOAT command increments the floating point value of the field in the hash stored at the key
+/// by the given amount.
+/// The command is in the format `HINCRBYFLOAT key field increment`.
+/// A missing field is set to 0 before performing the operation, and a missing key creates a new hash.
+/// The command returns the value of the field after the operation as a bulk string,
+/// formatted the same way as INCRBYFLOAT.
+/// Returns true if the field was incremented, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 3 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'HINCRBYFLOAT' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Apply the increment and respond with the new value
+    let response = increment(connection.db, args, server).await;
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(matches!(response, Type::BulkString(_)))
+}
+
+/// Increments the float value of the field by the delta, given the key, field and delta arguments.
+/// Returns the new value as a `BulkString`, or a `SimpleError` if the operation could not be performed.
+async fn increment(db: usize, args: &[Type], server: &Arc<Mutex<Server>>) -> Type {
+    // Extract the field and the delta from the arguments, rejecting NaN and infinity
+    let Some(field) = args[1].as_str() else {
+        return Type::SimpleError("ERR invalid field".into());
+    };
+    let Some(delta) = args[2]
+        .as_str()
+        .and_then(|delta| parse_float(delta.as_bytes()))
+    else {
+        return Type::SimpleError("ERR value is not a valid float".into());
+    };
+
+    // Get the hash stored at the key, creating it if it does not exist
+    let database = server.lock().await.db.clone();
+    let mut shard = database.write(&args[0]).await;
+    if shard.get(db, &args[0]).is_none() {
+        shard.set(db, args[0].clone(), Type::Hash(HashMap::new()), None);
+    }
+    let hash = match shard.get_mut(db, &args[0]) {
+        Some(Type::Hash(hash)) => hash,
+        _ => return Type::SimpleError(WrongType.to_string()),
+    };
+
+    // Get the current value, treating a missing field as 0
+    let current = match hash.get(field) {
+        Some(value) => match parse_float(value.as_bytes()) {
+            Some(value) => value,
+            None => return Type::SimpleError("ERR hash value is not a float".into()),
+        },
+        None => 0.0,
+    };
+
+    // Apply the delta
+    let value = current + delta;
+    if !value.is_finite() {
+        return Type::SimpleError("ERR increment would produce NaN or Infinity".into());
+    }
+    let value = value.to_string();
+    hash.insert(field.to_string(), value.clone());
+
+    Type::BulkString(value.into())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_increment_field_by_float() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["HINCRBYFLOAT", "h", "f", "10.5"]).await,
+            b"$4\r\n10.5\r\n"
+        );
+        assert_eq!(
+            client.send(&["HINCRBYFLOAT", "h", "f", "0.5"]).await,
+            b"$2\r\n11\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_float_field_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["HSET", "h", "f", "abc"]).await;
+        assert_eq!(
+            client.send(&["HINCRBYFLOAT", "h", "f", "1"]).await,
+            b"-ERR hash value is not a float\r\n"
+        );
+    }
+}
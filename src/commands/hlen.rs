@@ -0,0 +1,43 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// HLEN
+// ----
+
+/// Handles the HLEN command.
+/// The HLEN command returns the number of fields in the hash stored at the key.
+/// The command is in the format `HLEN key`.
+/// The command returns 0 if the key does not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 1 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'HLEN' command")
+            .await;
+    }
+
+    // Get the number of fields in the stored hash
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::Hash(hash)) => Type::Integer(hash.len() as i64),
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => Type::Integer(0),
+    };
+
+    // Respond with the number of fields
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
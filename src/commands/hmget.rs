@@ -0,0 +1,95 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -----
+// HMGET
+// -----
+
+/// Handles the HMGET command.
+/// The HMGET command returns the values associated with the fields in the hash stored at the key.
+/// The command is in the format `HMGET key field [field ...]`.
+/// The command returns an array with the value of each field, in order,
+/// with null for the fields that do not exist. A missing key behaves like an empty hash.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() < 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'HMGET' command")
+            .await;
+    }
+
+    // Extract the fields from the arguments
+    let fields = match args[1..]
+        .iter()
+        .map(Type::as_str)
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(fields) => fields,
+        None => return connection.write_error("ERR invalid field").await,
+    };
+
+    // Get the values of the fields from the hash stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::Hash(hash)) => resp::array(
+            fields
+                .iter()
+                .map(|field| {
+                    hash.get(*field)
+                        .map_or(Type::Null, |v| resp::bulk_string(v))
+                })
+                .collect(),
+        ),
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => resp::array(vec![Type::Null; fields.len()]),
+    };
+
+    // Respond with the values
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_null_for_missing_fields() {
+        let mut client = test_helpers::client().await;
+        client.send(&["HSET", "h", "a", "1", "c", "3"]).await;
+        assert_eq!(
+            client.send(&["HMGET", "h", "a", "b", "c"]).await,
+            b"*3\r\n$1\r\n1\r\n$-1\r\n$1\r\n3\r\n"
+        );
+        assert_eq!(
+            client.send(&["HMGET", "missing", "a", "b"]).await,
+            b"*2\r\n$-1\r\n$-1\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_hash_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["HMGET", "k", "a"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
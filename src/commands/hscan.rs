@@ -0,0 +1,112 @@
+// Library
+use crate::{
+    commands::scan::{self, ScanOptions},
+    database::WrongType,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -----
+// HSCAN
+// -----
+
+/// Handles the HSCAN command.
+/// The HSCAN command incrementally iterates over the fields of the hash stored at the key.
+/// The command is in the format `HSCAN key cursor [MATCH pattern] [COUNT count]`.
+/// The cursor works as in SCAN, with the fields iterated in sorted order and matched against the pattern.
+/// The items are returned as a flat array in which every field is followed by its value,
+/// or an empty array if the key does not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() < 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'HSCAN' command")
+            .await;
+    }
+
+    // Parse the cursor and the options
+    let options = match ScanOptions::parse(&args[1..]) {
+        Ok(options) => options,
+        Err(e) => return connection.write_error(e).await,
+    };
+
+    // Snapshot the fields of the hash in sorted order
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let mut fields: Vec<(String, String)> = match shard.get(connection.db, &args[0]) {
+        Some(Type::Hash(hash)) => hash.clone().into_iter().collect(),
+        Some(_) => {
+            drop(shard);
+            return connection.write_error(WrongType.to_string()).await;
+        }
+        None => Vec::new(),
+    };
+    drop(shard);
+    fields.sort();
+
+    // Examine the next batch of fields, and respond with the matching field/value pairs
+    let (next_cursor, batch) = options.page(&fields, |(field, _)| field.clone());
+    let batch = batch
+        .into_iter()
+        .flat_map(|(field, value)| [resp::bulk_string(field), resp::bulk_string(value)])
+        .collect();
+    let response = scan::reply(next_cursor, batch);
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_iterate_fields_with_cursor() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["HSET", "h", "a", "1", "b", "2", "c", "3"])
+            .await;
+
+        let (cursor, items) = client.send_scan(&["HSCAN", "h", "0", "COUNT", "2"]).await;
+        assert_eq!(cursor, "2");
+        assert_eq!(items, vec!["a", "1", "b", "2"]);
+        let (cursor, items) = client.send_scan(&["HSCAN", "h", "2", "COUNT", "2"]).await;
+        assert_eq!(cursor, "0");
+        assert_eq!(items, vec!["c", "3"]);
+    }
+
+    #[tokio::test]
+    async fn should_only_return_fields_matching_pattern() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["HSET", "h", "name", "x", "nick", "y", "age", "3"])
+            .await;
+        let (cursor, items) = client.send_scan(&["HSCAN", "h", "0", "MATCH", "n*"]).await;
+        assert_eq!(cursor, "0");
+        assert_eq!(items, vec!["name", "x", "nick", "y"]);
+    }
+
+    #[tokio::test]
+    async fn should_handle_missing_keys_and_wrong_types() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "s", "v"]).await;
+        assert_eq!(
+            client.send(&["HSCAN", "missing", "0"]).await,
+            b"*2\r\n$1\r\n0\r\n*0\r\n"
+        );
+        assert_eq!(
+            client.send(&["HSCAN", "s", "0"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
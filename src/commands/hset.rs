@@ -0,0 +1,122 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+// ----
+// HSET
+// ----
+
+/// Handles the HSET command.
+/// The HSET command sets the specified fields to their respective values in the hash stored at the key.
+/// The command is in the format `HSET key field value [field value ...]`.
+/// Existing fields are overwritten. If the key does not exist, a new hash is created.
+/// The command returns the number of fields that were added (not counting overwritten fields).
+/// Returns true if the fields were set, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments, which must be a key followed by field/value pairs
+    if args.len() < 3 || args.len().is_multiple_of(2) {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'HSET' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the field/value pairs from the arguments
+    let mut pairs = Vec::new();
+    for pair in args[1..].chunks(2) {
+        match (pair[0].as_str(), pair[1].as_str()) {
+            (Some(field), Some(value)) => pairs.push((field.to_string(), value.to_string())),
+            _ => {
+                if role.is_master() {
+                    connection.write_error("ERR invalid field or value").await?;
+                }
+                return Ok(false);
+            }
+        }
+    }
+
+    // Get the hash stored at the key, creating it if it does not exist
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    if shard.get(connection.db, &args[0]).is_none() {
+        shard.set(
+            connection.db,
+            args[0].clone(),
+            Type::Hash(HashMap::new()),
+            None,
+        );
+    }
+    let hash = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::Hash(hash)) => hash,
+        _ => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Set the fields, counting the ones that are new
+    let added = pairs
+        .into_iter()
+        .filter(|(field, value)| hash.insert(field.clone(), value.clone()).is_none())
+        .count();
+
+    // Respond with the number of added fields
+    if role.is_master() {
+        let response = Type::Integer(added as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(true)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_count_new_fields() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["HSET", "h", "a", "1", "b", "2"]).await,
+            b":2\r\n"
+        );
+        assert_eq!(
+            client.send(&["HSET", "h", "a", "3", "c", "4"]).await,
+            b":1\r\n"
+        );
+        assert_eq!(client.send(&["HGET", "h", "a"]).await, b"$1\r\n3\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_hash_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["HSET", "k", "a", "1"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
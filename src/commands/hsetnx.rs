@@ -0,0 +1,91 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+// ------
+// HSETNX
+// ------
+
+/// Handles the HSETNX command.
+/// The HSETNX command sets the field to the value in the hash stored at the key, only if the field does not exist.
+/// The command is in the format `HSETNX key field value`.
+/// If the key does not exist, a new hash is created.
+/// The command returns 1 if the field was set, and 0 if it already existed.
+/// Returns true if the field was written, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 3 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'HSETNX' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the field and the value from the arguments
+    let (Some(field), Some(value)) = (args[1].as_str(), args[2].as_str()) else {
+        if role.is_master() {
+            connection.write_error("ERR invalid field or value").await?;
+        }
+        return Ok(false);
+    };
+
+    // Set the field unless it already exists, creating the hash if the key does not exist
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let response = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::Hash(hash)) if hash.contains_key(field) => Type::Integer(0),
+        Some(Type::Hash(hash)) => {
+            hash.insert(field.to_string(), value.to_string());
+            Type::Integer(1)
+        }
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => {
+            let hash = HashMap::from([(field.to_string(), value.to_string())]);
+            shard.set(connection.db, args[0].clone(), Type::Hash(hash), None);
+            Type::Integer(1)
+        }
+    };
+    drop(shard);
+
+    // Respond with whether the field was set
+    let written = response == Type::Integer(1);
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(written)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_only_set_missing_field() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["HSETNX", "h", "a", "1"]).await, b":1\r\n");
+        assert_eq!(client.send(&["HSETNX", "h", "a", "2"]).await, b":0\r\n");
+        assert_eq!(client.send(&["HGET", "h", "a"]).await, b"$1\r\n1\r\n");
+    }
+}
@@ -0,0 +1,141 @@
+// Library
+use crate::{
+    database::WrongType,
+    errors::Error,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ---------
+// INCR/DECR
+// ---------
+
+/// Handles the INCR and DECR commands.
+/// The commands increment (or decrement) the integer value stored at the key by the given `delta`.
+/// The commands are in the format `INCR key` and `DECR key`.
+/// If the key does not exist, it is set to 0 before performing the operation.
+/// The command returns the value of the key after the operation.
+/// The command returns an error if the stored value cannot be represented as an integer,
+/// or if the operation would overflow.
+/// Returns true if the value was incremented, in which case the command should be propagated to replicas.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+    delta: i64,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 1 {
+        if role.is_master() {
+            connection
+                .write_error(format!(
+                    "ERR wrong number of arguments for '{}' command",
+                    name
+                ))
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Apply the increment and respond with the new value
+    let response = increment(connection.db, &args[0], delta, server).await;
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(matches!(response, Type::Integer(_)))
+}
+
+/// Increments the integer value stored at the key (in database `db`) by `delta` and stores the result back in the database.
+/// Returns the new value as an `Integer`, or a `SimpleError` if the operation could not be performed.
+pub async fn increment(db: usize, key: &Type, delta: i64, server: &Arc<Mutex<Server>>) -> Type {
+    let database = server.lock().await.db.clone();
+    let mut shard = database.write(key).await;
+
+    // Get the current value, treating a missing key as 0
+    let current = match shard.get(db, key) {
+        Some(value @ Type::BulkString(_)) => {
+            match value.as_str().and_then(|value| value.parse::<i64>().ok()) {
+                Some(value) => value,
+                None => return not_an_integer(),
+            }
+        }
+        Some(_) => return Type::SimpleError(WrongType.to_string()),
+        None => 0,
+    };
+
+    // Apply the delta, guarding against overflow
+    let value = match current.checked_add(delta) {
+        Some(value) => value,
+        None => return not_an_integer(),
+    };
+
+    // Store the new value back as a bulk string
+    shard.update(db, key.clone(), Type::BulkString(value.to_string().into()));
+
+    Type::Integer(value)
+}
+
+/// The error returned when a value is not an integer or is out of range
+fn not_an_integer() -> Type {
+    Type::SimpleError(Error::NotInteger.to_string())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_increment_missing_key_from_zero() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["INCR", "counter"]).await, b":1\r\n");
+        assert_eq!(client.send(&["INCR", "counter"]).await, b":2\r\n");
+        assert_eq!(client.send(&["GET", "counter"]).await, b"$1\r\n2\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_decrement() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "counter", "10"]).await;
+        assert_eq!(client.send(&["DECR", "counter"]).await, b":9\r\n");
+        assert_eq!(client.send(&["DECR", "missing"]).await, b":-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_error_on_non_integer_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "key", "abc"]).await;
+        assert_eq!(
+            client.send(&["INCR", "key"]).await,
+            b"-ERR value is not an integer or out of range\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_error_on_overflow() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "key", &i64::MAX.to_string()]).await;
+        assert_eq!(
+            client.send(&["INCR", "key"]).await,
+            b"-ERR value is not an integer or out of range\r\n"
+        );
+        // The value must be left untouched
+        assert_eq!(
+            client.send(&["GET", "key"]).await,
+            format!("$19\r\n{}\r\n", i64::MAX).as_bytes()
+        );
+    }
+}
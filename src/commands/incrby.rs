@@ -0,0 +1,100 @@
+// Library
+use super::incr;
+use crate::{
+    errors::Error,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -------------
+// INCRBY/DECRBY
+// -------------
+
+/// Handles the INCRBY and DECRBY commands.
+/// The commands increment (or decrement) the integer value stored at the key by the given amount.
+/// The commands are in the format `INCRBY key increment` and `DECRBY key decrement`.
+/// If the key does not exist, it is set to 0 before performing the operation.
+/// The command returns the value of the key after the operation.
+/// The `sign` determines the direction: 1 for INCRBY and -1 for DECRBY.
+/// Returns true if the value was incremented, in which case the command should be propagated to replicas.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+    sign: i64,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 2 {
+        if role.is_master() {
+            connection
+                .write_error(format!(
+                    "ERR wrong number of arguments for '{}' command",
+                    name
+                ))
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the delta from the arguments
+    let delta = args[1]
+        .as_str()
+        .and_then(|delta| delta.parse::<i64>().ok())
+        .and_then(|delta| delta.checked_mul(sign));
+
+    // Apply the increment and respond with the new value
+    let response = match delta {
+        Some(delta) => incr::increment(connection.db, &args[0], delta, server).await,
+        None => Type::SimpleError(Error::NotInteger.to_string()),
+    };
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(matches!(response, Type::Integer(_)))
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_increment_by_delta() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["INCRBY", "key", "5"]).await, b":5\r\n");
+        assert_eq!(client.send(&["INCRBY", "key", "-7"]).await, b":-2\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_decrement_by_delta() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "key", "10"]).await;
+        assert_eq!(client.send(&["DECRBY", "key", "3"]).await, b":7\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_integer_delta() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["INCRBY", "key", "1.5"]).await,
+            b"-ERR value is not an integer or out of range\r\n"
+        );
+        assert_eq!(
+            client.send(&["DECRBY", "key", &i64::MIN.to_string()]).await,
+            b"-ERR value is not an integer or out of range\r\n"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -----------
+// INCRBYFLOAT
+// -----------
+
+/// Handles the INCRBYFLOAT command.
+/// The INCRBYFLOAT command increments the floating point value stored at the key by the given amount.
+/// The command is in the format `INCRBYFLOAT key increment`.
+/// If the key does not exist, it is set to 0 before performing the operation.
+/// The command returns the value of the key after the operation as a bulk string.
+/// Whole numbers are formatted without a trailing `.0` (e.g. `3000` instead of `3000.0`).
+/// Returns true if the value was incremented, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 2 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'INCRBYFLOAT' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Apply the increment and respond with the new value
+    let response = increment(connection.db, &args[0], &args[1], server).await;
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(matches!(response, Type::BulkString(_)))
+}
+
+/// Increments the float value stored at the key (in database `db`) by the given delta and stores the result back in the database.
+/// Returns the new value as a `BulkString`, or a `SimpleError` if the operation could not be performed.
+async fn increment(db: usize, key: &Type, delta: &Type, server: &Arc<Mutex<Server>>) -> Type {
+    // Parse the delta, rejecting NaN and infinity
+    let delta = match delta {
+        Type::BulkString(delta) => match parse_float(delta) {
+            Some(delta) => delta,
+            None => return not_a_float(),
+        },
+        _ => return not_a_float(),
+    };
+
+    let database = server.lock().await.db.clone();
+    let mut shard = database.write(key).await;
+
+    // Get the current value, treating a missing key as 0
+    let current = match shard.get(db, key) {
+        Some(Type::BulkString(value)) => match parse_float(value) {
+            Some(value) => value,
+            None => return not_a_float(),
+        },
+        Some(_) => return Type::SimpleError(WrongType.to_string()),
+        None => 0.0,
+    };
+
+    // Apply the delta
+    let value = current + delta;
+    if !value.is_finite() {
+        return Type::SimpleError("ERR increment would produce NaN or Infinity".into());
+    }
+
+    // Store the new value back as a bulk string.
+    // The Display implementation for f64 omits the trailing `.0` for whole numbers.
+    let value = value.to_string();
+    shard.update(db, key.clone(), Type::BulkString(value.clone().into()));
+
+    Type::BulkString(value.into())
+}
+
+/// Parses the bulk string data as a finite f64
+pub fn parse_float(value: &[u8]) -> Option<f64> {
+    std::str::from_utf8(value)
+        .ok()?
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+/// The error returned when a value is not a valid float
+fn not_a_float() -> Type {
+    Type::SimpleError("ERR value is not a valid float".into())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_increment_by_float() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "key", "10.50"]).await;
+        assert_eq!(
+            client.send(&["INCRBYFLOAT", "key", "0.1"]).await,
+            b"$4\r\n10.6\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_format_whole_numbers_without_decimal_point() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["INCRBYFLOAT", "k", "3.0e3"]).await,
+            b"$4\r\n3000\r\n"
+        );
+        assert_eq!(client.send(&["GET", "k"]).await, b"$4\r\n3000\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_nan_and_infinity() {
+        let mut client = test_helpers::client().await;
+        for delta in ["nan", "inf", "-inf"] {
+            assert_eq!(
+                client.send(&["INCRBYFLOAT", "k", delta]).await,
+                b"-ERR value is not a valid float\r\n"
+            );
+        }
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":0\r\n");
+    }
+}
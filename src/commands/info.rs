@@ -1,7 +1,8 @@
 // Library
 use crate::{
+    database::{Database, DATABASES},
     parser::resp::Type,
-    server::{connection::Connection, replication::Role, Server},
+    server::{connection::Connection, replication::Role, stats::Stats, Server, VERSION},
 };
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -12,43 +13,210 @@ use tokio::sync::Mutex;
 
 /// Handles the INFO command.
 /// The INFO command returns information and statistics about the server.
+/// The command is in the format `INFO [section]`.
+/// The sections are `server`, `stats`, `replication`, `commandstats` and `keyspace`.
+/// Without a section (or with `default`), all the sections except `commandstats` are returned,
+/// and `all` or `everything` return all of them. Unknown sections are left out.
 pub async fn command(
     args: &[Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Check the number of arguments
-    if args.len() < 1 {
-        return connection
-            .write_error("ERR wrong number of arguments for 'INFO' command")
-            .await;
+    // Extract the requested section
+    let section = match args.first().map(Type::as_str) {
+        Some(Some(section)) => section.to_lowercase(),
+        Some(None) => return connection.write_error("ERR syntax error").await,
+        None => "default".into(),
+    };
+
+    // Generate the requested sections
+    let all = matches!(section.as_str(), "all" | "everything" | "default");
+    let mut sections: Vec<String> = Vec::new();
+    let s = server.lock().await;
+    if all || section == "server" {
+        sections.push(server_section(&s));
+    }
+    if all || section == "stats" {
+        sections.push(stats_section(&s.stats));
+    }
+    if all || section == "replication" {
+        sections.push(replication_section(&s));
+    }
+    if matches!(section.as_str(), "all" | "everything" | "commandstats") {
+        sections.push(commandstats_section(&s.stats));
+    }
+    let db = s.db.clone();
+    drop(s);
+    if all || section == "keyspace" {
+        sections.push(keyspace_section(&db).await);
+    }
+
+    // Respond with the server information
+    let response = Type::BulkString(sections.join("\r\n").into());
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+/// Formats a section with the given title and `field:value` lines
+fn section(title: &str, lines: Vec<String>) -> String {
+    let mut section = format!("# {}\r\n", title);
+    for line in lines {
+        section.push_str(&line);
+        section.push_str("\r\n");
     }
+    section
+}
 
-    // Lock the server instance
-    let server = server.lock().await;
+/// Generates the `Server` section
+fn server_section(server: &Server) -> String {
+    section(
+        "Server",
+        vec![
+            format!("redis_version:{}", VERSION),
+            format!("run_id:{}", server.run_id),
+            format!("tcp_port:{}", server.port),
+        ],
+    )
+}
 
+/// Generates the `Stats` section
+fn stats_section(stats: &Stats) -> String {
+    section(
+        "Stats",
+        vec![
+            format!(
+                "total_connections_received:{}",
+                stats.total_connections_received()
+            ),
+            format!(
+                "total_commands_processed:{}",
+                stats.total_commands_processed()
+            ),
+        ],
+    )
+}
+
+/// Generates the `Commandstats` section, with a line for every command that was called
+fn commandstats_section(stats: &Stats) -> String {
+    let lines = stats
+        .calls()
+        .into_iter()
+        .map(|(command, calls)| format!("cmdstat_{}:calls={}", command.to_lowercase(), calls))
+        .collect();
+    section("Commandstats", lines)
+}
+
+/// Generates the `Replication` section
+fn replication_section(server: &Server) -> String {
     // Get the role of the server
     let role = match server.role {
         Role::Master => "role:master",
         Role::Replica(_) => "role:slave",
     };
 
-    // Get Master Replication ID and Offset
-    let master_replid = server.master_replid.clone();
-    let master_repl_offset = server.master_repl_offset;
-
-    // Generate the response
-    let response: String = vec![
-        "# Replication".to_string(),
+    let mut lines = vec![
         role.to_string(),
-        format!("master_replid:{}", master_replid),
-        format!("master_repl_offset:{}", master_repl_offset),
-    ]
-    .join("\r\n");
+        format!("connected_slaves:{}", server.replicas.len()),
+    ];
+    for (i, replica) in server.replicas.iter().enumerate() {
+        lines.push(format!(
+            "slave{}:ip={},port={},state=online,offset={},lag=0",
+            i,
+            replica.addr.ip(),
+            replica.port,
+            replica.offset
+        ));
+    }
+    lines.push(format!("master_replid:{}", server.master_replid));
+    lines.push(format!("master_repl_offset:{}", server.master_repl_offset));
 
-    // Respond with the server information
-    let response = Type::BulkString(response);
-    connection.write_all(&response.as_bytes()).await?;
+    section("Replication", lines)
+}
 
-    Ok(())
+/// Generates the `Keyspace` section, with a line for every database that holds keys
+async fn keyspace_section(database: &Database) -> String {
+    let mut lines = Vec::new();
+    for db in 0..DATABASES {
+        let keys = database.keys(db).await.len();
+        if keys > 0 {
+            lines.push(format!("db{}:keys={}", db, keys));
+        }
+    }
+    section("Keyspace", lines)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::{psync, test_helpers},
+        parser::resp,
+    };
+
+    /// Sends the INFO command and returns the response as text
+    async fn info(client: &mut test_helpers::Client, args: &[&str]) -> String {
+        let mut cmd = vec!["INFO"];
+        cmd.extend_from_slice(args);
+        String::from_utf8(client.send(&cmd).await).unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_return_requested_section() {
+        let mut client = test_helpers::client().await;
+        let response = info(&mut client, &["replication"]).await;
+        assert!(response.contains("# Replication\r\n"));
+        assert!(response.contains("role:master\r\n"));
+        assert!(response.contains("connected_slaves:0\r\n"));
+        assert!(response.contains("master_repl_offset:"));
+        assert!(!response.contains("# Server"));
+    }
+
+    #[tokio::test]
+    async fn should_list_replicas_after_handshake() {
+        let mut replica = test_helpers::client().await;
+        replica.send(&["REPLCONF", "listening-port", "6380"]).await;
+        replica.send(&["REPLCONF", "capa", "psync2"]).await;
+        // PSYNC is handled directly, as the command handler keeps streaming to the replica afterwards
+        let args = [resp::bulk_string("?"), resp::bulk_string("-1")];
+        psync::command(&args, &mut replica.conn, &replica.server)
+            .await
+            .unwrap();
+
+        let mut client = test_helpers::client_for(&replica.server).await;
+        let response = info(&mut client, &["replication"]).await;
+        assert!(response.contains("connected_slaves:1\r\n"));
+        assert!(response.contains("slave0:ip=127.0.0.1,port=6380,state=online,offset=0,lag=0\r\n"));
+    }
+
+    #[tokio::test]
+    async fn should_return_all_sections() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        client.send(&["SET", "b", "2"]).await;
+        let response = info(&mut client, &[]).await;
+        assert!(response.contains("# Server\r\nredis_version:"));
+        assert!(response.contains("# Replication\r\n"));
+        assert!(response.contains("# Keyspace\r\ndb0:keys=2\r\n"));
+        assert_eq!(info(&mut client, &["nosuchsection"]).await, "$0\r\n\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_count_calls_per_command() {
+        let mut client = test_helpers::client().await;
+        for _ in 0..3 {
+            client.send(&["PING"]).await;
+        }
+        let response = info(&mut client, &["commandstats"]).await;
+        assert!(response.contains("# Commandstats\r\n"));
+        assert!(response.contains("cmdstat_ping:calls=3\r\n"));
+        assert!(!response.contains("cmdstat_get"));
+
+        let response = info(&mut client, &["stats"]).await;
+        // The three PINGs and the two INFO commands
+        assert!(response.contains("total_commands_processed:5\r\n"));
+    }
 }
@@ -4,6 +4,7 @@ use tokio::sync::Mutex;
 
 // Library
 use crate::{
+    helpers,
     parser::resp::{self, Type},
     server::{connection::Connection, Server},
 };
@@ -15,9 +16,9 @@ use crate::{
 /// Handles the KEYS command.
 /// The KEYS command is used to return all keys matching a given pattern.
 /// The command is in the format `KEYS 'pattern'`.
-/// The pattern can contain the `*` and `?` wildcards.
+/// The pattern can contain the `*` and `?` wildcards, and `[abc]` or `[a-z]` character classes.
 pub async fn command(
-    args: &Vec<Type>,
+    args: &[Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -28,19 +29,26 @@ pub async fn command(
             .await;
     }
 
-    // // Extract the pattern from the arguments
-    // let pattern = match args.get(1) {
-    //     Some(Type::BulkString(pattern)) => pattern,
-    //     _ => {
-    //         return connection.write_error("ERR invalid pattern").await;
-    //     }
-    // };
-
-    // Get the server lock
-    let server = server.lock().await;
+    // Extract the pattern from the arguments
+    let pattern = match args.get(1).and_then(Type::as_str) {
+        Some(pattern) => pattern,
+        _ => {
+            return connection.write_error("ERR invalid pattern").await;
+        }
+    };
 
     // Get the keys that match the pattern
-    let keys = server.db.keys();
+    let db = server.lock().await.db.clone();
+    let keys = db
+        .keys(connection.db)
+        .await
+        .into_iter()
+        // Binary keys are matched on their lossy UTF-8 form, like SCAN does
+        .filter(|key| match key {
+            Type::BulkString(key) => helpers::glob_match(pattern, &String::from_utf8_lossy(key)),
+            _ => false,
+        })
+        .collect();
 
     // Write the keys to the connection
     let response = resp::array(keys);
@@ -48,3 +56,49 @@ pub async fn command(
 
     Ok(())
 }
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_all_keys_for_star() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        assert_eq!(client.send(&["KEYS", "*"]).await, b"*1\r\n$1\r\na\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_filter_keys_by_pattern() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "user:1", "a"]).await;
+        client.send(&["SET", "session:1", "b"]).await;
+        client.send(&["SET", "hello", "c"]).await;
+        client.send(&["SET", "42", "d"]).await;
+        assert_eq!(
+            client.send(&["KEYS", "user:*"]).await,
+            b"*1\r\n$6\r\nuser:1\r\n"
+        );
+        assert_eq!(
+            client.send(&["KEYS", "h?llo"]).await,
+            b"*1\r\n$5\r\nhello\r\n"
+        );
+        assert_eq!(
+            client.send(&["KEYS", "[0-9][0-9]"]).await,
+            b"*1\r\n$2\r\n42\r\n"
+        );
+        assert_eq!(client.send(&["KEYS", "nomatch*"]).await, b"*0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_return_binary_keys() {
+        let mut client = test_helpers::client().await;
+        client.send_bytes(&[b"SET", b"k\xff", b"v"]).await;
+        assert_eq!(client.send(&["KEYS", "*"]).await, b"*1\r\n$2\r\nk\xff\r\n");
+        assert_eq!(client.send(&["KEYS", "k?"]).await, b"*1\r\n$2\r\nk\xff\r\n");
+    }
+}
@@ -0,0 +1,61 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// LLEN
+// ----
+
+/// Handles the LLEN command.
+/// The LLEN command returns the length of the list stored at the key.
+/// The command is in the format `LLEN key`.
+/// The command returns 0 if the key does not exist.
+/// The command returns an error if the key holds a value that is not a list.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 1 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'LLEN' command")
+            .await;
+    }
+
+    // Get the length of the stored list
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::List(list)) => Type::Integer(list.len() as i64),
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => Type::Integer(0),
+    };
+
+    // Respond with the length
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_list_length() {
+        let mut client = test_helpers::client().await;
+        client.send(&["RPUSH", "l", "a", "b"]).await;
+        assert_eq!(client.send(&["LLEN", "l"]).await, b":2\r\n");
+        assert_eq!(client.send(&["LLEN", "missing"]).await, b":0\r\n");
+    }
+}
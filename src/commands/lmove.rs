@@ -0,0 +1,154 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ---------------
+// LMOVE/RPOPLPUSH
+// ---------------
+
+/// Handles the LMOVE and RPOPLPUSH commands.
+/// The commands atomically pop an element from one end of the source list, and push it onto one end
+/// of the destination list, creating the destination if it does not exist.
+/// The commands are in the format `LMOVE source destination LEFT|RIGHT LEFT|RIGHT`
+/// and `RPOPLPUSH source destination` (which is the same as `LMOVE source destination RIGHT LEFT`).
+/// The source and destination may be the same list, which rotates the list.
+/// The commands return the moved element, or null if the source does not exist.
+/// Returns true if an element was moved, in which case the command should be propagated to replicas.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Extract the ends of the lists to move the element between
+    let directions = match (name, args) {
+        ("RPOPLPUSH", [_, _]) => Some((false, true)),
+        ("LMOVE", [_, _, from, to]) => match (side(from), side(to)) {
+            (Some(from), Some(to)) => Some((from, to)),
+            _ => None,
+        },
+        _ => {
+            if role.is_master() {
+                connection
+                    .write_error(format!(
+                        "ERR wrong number of arguments for '{}' command",
+                        name
+                    ))
+                    .await?;
+            }
+            return Ok(false);
+        }
+    };
+    let Some((from_left, to_left)) = directions else {
+        if role.is_master() {
+            connection.write_error("ERR syntax error").await?;
+        }
+        return Ok(false);
+    };
+
+    // Move the element
+    let db = server.lock().await.db.clone();
+    let response = match db
+        .lmove(connection.db, &args[0], &args[1], from_left, to_left)
+        .await
+    {
+        Ok(Some(element)) => {
+            // Wake up the clients blocked on the destination list
+            let _ = server
+                .lock()
+                .await
+                .list_events
+                .send((connection.db, args[1].clone()));
+            Type::BulkString(element.into())
+        }
+        Ok(None) => Type::Null,
+        Err(e) => Type::SimpleError(e.to_string()),
+    };
+
+    // Respond with the moved element
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(matches!(response, Type::BulkString(_)))
+}
+
+/// Parses the end of a list, returning true for `LEFT` (the head) and false for `RIGHT` (the tail)
+fn side(value: &Type) -> Option<bool> {
+    match value.as_str()?.to_uppercase().as_str() {
+        "LEFT" => Some(true),
+        "RIGHT" => Some(false),
+        _ => None,
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_rotate_single_list() {
+        let mut client = test_helpers::client().await;
+        client.send(&["RPUSH", "list", "a", "b", "c"]).await;
+        assert_eq!(
+            client
+                .send(&["LMOVE", "list", "list", "RIGHT", "LEFT"])
+                .await,
+            b"$1\r\nc\r\n"
+        );
+        assert_eq!(
+            client.send(&["LRANGE", "list", "0", "-1"]).await,
+            b"*3\r\n$1\r\nc\r\n$1\r\na\r\n$1\r\nb\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_move_between_lists_and_remove_empty_source() {
+        let mut client = test_helpers::client().await;
+        client.send(&["RPUSH", "src", "a", "b"]).await;
+        assert_eq!(
+            client.send(&["RPOPLPUSH", "src", "dst"]).await,
+            b"$1\r\nb\r\n"
+        );
+        assert_eq!(
+            client.send(&["LMOVE", "src", "dst", "LEFT", "RIGHT"]).await,
+            b"$1\r\na\r\n"
+        );
+        assert_eq!(
+            client.send(&["LRANGE", "dst", "0", "-1"]).await,
+            b"*2\r\n$1\r\nb\r\n$1\r\na\r\n"
+        );
+        assert_eq!(client.send(&["EXISTS", "src"]).await, b":0\r\n");
+        assert_eq!(client.send(&["RPOPLPUSH", "src", "dst"]).await, b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_not_move_into_wrong_type() {
+        let mut client = test_helpers::client().await;
+        client.send(&["RPUSH", "src", "a"]).await;
+        client.send(&["SET", "dst", "v"]).await;
+        assert_eq!(
+            client.send(&["RPOPLPUSH", "src", "dst"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+        assert_eq!(client.send(&["LLEN", "src"]).await, b":1\r\n");
+        assert_eq!(
+            client.send(&["LMOVE", "src", "dst", "UP", "LEFT"]).await,
+            b"-ERR syntax error\r\n"
+        );
+    }
+}
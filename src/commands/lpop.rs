@@ -0,0 +1,143 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ---------
+// LPOP/RPOP
+// ---------
+
+/// Handles the LPOP and RPOP commands.
+/// The commands remove and return elements from the head (LPOP) or tail (RPOP) of the list stored at the key.
+/// The commands are in the format `LPOP key [count]` and `RPOP key [count]`.
+/// Without a count, the command returns the popped element, or null if the key does not exist.
+/// With a count, the command returns an array of up to `count` popped elements.
+/// The key is removed once the last element has been popped.
+/// Returns true if any element was popped, in which case the command should be propagated to replicas.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+    left: bool,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.is_empty() || args.len() > 2 {
+        if role.is_master() {
+            connection
+                .write_error(format!(
+                    "ERR wrong number of arguments for '{}' command",
+                    name
+                ))
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the optional count from the arguments
+    let count = match args.get(1) {
+        Some(count) => match count.as_str().and_then(|count| count.parse::<usize>().ok()) {
+            Some(count) => Some(count),
+            None => {
+                if role.is_master() {
+                    connection
+                        .write_error("ERR value is out of range, must be positive")
+                        .await?;
+                }
+                return Ok(false);
+            }
+        },
+        None => None,
+    };
+
+    // Get the list stored at the key
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let list = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::List(list)) => list,
+        Some(_) => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+        None => {
+            if role.is_master() {
+                let response = Type::Null;
+                connection.write_all(&response.as_bytes()).await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Pop the elements from the list
+    let n = count.unwrap_or(1).min(list.len());
+    let popped: Vec<String> = if left {
+        list.drain(..n).collect()
+    } else {
+        list.drain(list.len() - n..).rev().collect()
+    };
+
+    // Remove the key once the list is empty
+    if list.is_empty() {
+        shard.remove(connection.db, &args[0]);
+    }
+
+    // Respond with the popped element(s)
+    if role.is_master() {
+        let response = match count {
+            Some(_) => resp::array(
+                popped
+                    .iter()
+                    .map(|element| resp::bulk_string(element))
+                    .collect(),
+            ),
+            None => popped
+                .first()
+                .map_or(Type::Null, |element| resp::bulk_string(element)),
+        };
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(!popped.is_empty())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_pop_from_both_ends() {
+        let mut client = test_helpers::client().await;
+        client.send(&["RPUSH", "l", "a", "b", "c", "d"]).await;
+        assert_eq!(client.send(&["LPOP", "l"]).await, b"$1\r\na\r\n");
+        assert_eq!(client.send(&["RPOP", "l"]).await, b"$1\r\nd\r\n");
+        assert_eq!(
+            client.send(&["RPOP", "l", "5"]).await,
+            b"*2\r\n$1\r\nc\r\n$1\r\nb\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_delete_key_after_popping_last_element() {
+        let mut client = test_helpers::client().await;
+        client.send(&["RPUSH", "l", "a"]).await;
+        assert_eq!(client.send(&["LPOP", "l"]).await, b"$1\r\na\r\n");
+        assert_eq!(client.send(&["EXISTS", "l"]).await, b":0\r\n");
+        assert_eq!(client.send(&["LPOP", "l"]).await, b"$-1\r\n");
+    }
+}
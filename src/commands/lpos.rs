@@ -0,0 +1,167 @@
+// Library
+use crate::{
+    database::WrongType,
+    errors::Error,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// LPOS
+// ----
+
+/// Handles the LPOS command.
+/// The LPOS command returns the index of the elements matching the element in the list stored at the key.
+/// The command is in the format `LPOS key element [RANK rank] [COUNT count]`.
+/// `RANK` skips the first `rank - 1` matches, and a negative rank searches from the tail of the list.
+/// Without `COUNT`, the index of the match is returned, or a null if there is none.
+/// With `COUNT`, an array of the indices of up to `count` matches is returned, where `0` returns all of them.
+/// Indices always count from the head of the list.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() < 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'LPOS' command")
+            .await;
+    }
+
+    // Extract the element from the arguments
+    let element = match args[1].as_str() {
+        Some(element) => element,
+        None => return connection.write_error("ERR invalid element").await,
+    };
+
+    // Parse the options
+    let mut rank: i64 = 1;
+    let mut count = None;
+    for option in args[2..].chunks(2) {
+        let name = option[0].as_str().map(str::to_uppercase);
+        let value = match option.get(1).and_then(Type::as_str).map(str::parse::<i64>) {
+            Some(Ok(value)) => value,
+            Some(Err(_)) => {
+                return connection.write_error(Error::NotInteger.to_string()).await;
+            }
+            None => return connection.write_error("ERR syntax error").await,
+        };
+        match name.as_deref() {
+            Some("RANK") if value == 0 => {
+                return connection
+                    .write_error("ERR RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list")
+                    .await;
+            }
+            Some("RANK") => rank = value,
+            Some("COUNT") if value < 0 => {
+                return connection.write_error("ERR COUNT can't be negative").await;
+            }
+            // A count of 0 returns all the matches
+            Some("COUNT") if value == 0 => count = Some(usize::MAX),
+            Some("COUNT") => count = Some(value as usize),
+            _ => return connection.write_error("ERR syntax error").await,
+        }
+    }
+
+    // Get the list stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let list = match shard.get(connection.db, &args[0]) {
+        Some(Type::List(list)) => list.as_slice(),
+        Some(_) => {
+            return connection.write_error(WrongType.to_string()).await;
+        }
+        None => &[],
+    };
+
+    // Find the matches, walking from the tail of the list for a negative rank
+    let mut matches: Vec<usize> = (0..list.len())
+        .filter(|index| list[*index] == element)
+        .collect();
+    if rank < 0 {
+        matches.reverse();
+    }
+    let mut matches = matches.into_iter().skip(rank.unsigned_abs() as usize - 1);
+
+    // Respond with the index of the match, or with the indices of the requested number of matches
+    let response = match count {
+        None => match matches.next() {
+            Some(index) => Type::Integer(index as i64),
+            None => Type::Null,
+        },
+        Some(count) => resp::array(
+            matches
+                .take(count)
+                .map(|index| Type::Integer(index as i64))
+                .collect(),
+        ),
+    };
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_find_first_match_from_head() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["RPUSH", "l", "a", "b", "c", "1", "2", "3", "c", "c"])
+            .await;
+        assert_eq!(client.send(&["LPOS", "l", "c"]).await, b":2\r\n");
+        assert_eq!(
+            client.send(&["LPOS", "l", "c", "RANK", "2"]).await,
+            b":6\r\n"
+        );
+        assert_eq!(client.send(&["LPOS", "l", "x"]).await, b"$-1\r\n");
+        assert_eq!(client.send(&["LPOS", "missing", "a"]).await, b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_search_from_tail_with_negative_rank() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["RPUSH", "l", "a", "b", "c", "1", "2", "3", "c", "c"])
+            .await;
+        assert_eq!(
+            client.send(&["LPOS", "l", "c", "RANK", "-1"]).await,
+            b":7\r\n"
+        );
+        assert_eq!(
+            client
+                .send(&["LPOS", "l", "c", "RANK", "-1", "COUNT", "2"])
+                .await,
+            b"*2\r\n:7\r\n:6\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_all_matches_with_count_zero() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["RPUSH", "l", "a", "b", "c", "1", "2", "3", "c", "c"])
+            .await;
+        assert_eq!(
+            client.send(&["LPOS", "l", "c", "COUNT", "0"]).await,
+            b"*3\r\n:2\r\n:6\r\n:7\r\n"
+        );
+        assert_eq!(
+            client.send(&["LPOS", "l", "x", "COUNT", "0"]).await,
+            b"*0\r\n"
+        );
+        assert_eq!(
+            client.send(&["LPOS", "l", "c", "RANK", "0"]).await,
+            b"-ERR RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list\r\n"
+        );
+    }
+}
@@ -0,0 +1,133 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -----------
+// LPUSH/RPUSH
+// -----------
+
+/// Handles the LPUSH and RPUSH commands.
+/// The commands insert the elements at the head (LPUSH) or tail (RPUSH) of the list stored at the key.
+/// The commands are in the format `LPUSH key element [element ...]` and `RPUSH key element [element ...]`.
+/// Elements are inserted one after the other, so `LPUSH key a b c` results in the list `c b a`.
+/// If the key does not exist, an empty list is created before performing the operation.
+/// The command returns the length of the list after the push operation.
+/// Returns true if the elements were pushed, in which case the command should be propagated to replicas.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+    left: bool,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() < 2 {
+        if role.is_master() {
+            connection
+                .write_error(format!(
+                    "ERR wrong number of arguments for '{}' command",
+                    name
+                ))
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the elements from the arguments
+    let mut elements = Vec::new();
+    for arg in &args[1..] {
+        match arg.as_str() {
+            Some(element) => elements.push(element.to_string()),
+            None => {
+                if role.is_master() {
+                    connection.write_error("ERR invalid element").await?;
+                }
+                return Ok(false);
+            }
+        }
+    }
+
+    // Get the list stored at the key, creating it if it does not exist
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    if shard.get(connection.db, &args[0]).is_none() {
+        shard.set(connection.db, args[0].clone(), Type::List(Vec::new()), None);
+    }
+    let list = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::List(list)) => list,
+        _ => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Push the elements onto the list
+    if left {
+        for element in elements {
+            list.insert(0, element);
+        }
+    } else {
+        list.extend(elements);
+    }
+
+    let len = list.len();
+    drop(shard);
+
+    // Wake up the clients blocked on the list. Sending only fails if no client is blocked
+    let _ = server
+        .lock()
+        .await
+        .list_events
+        .send((connection.db, args[0].clone()));
+
+    // Respond with the new length of the list
+    if role.is_master() {
+        let response = Type::Integer(len as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(true)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_push_to_both_ends() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["RPUSH", "l", "b", "c"]).await, b":2\r\n");
+        assert_eq!(client.send(&["LPUSH", "l", "a", "z"]).await, b":4\r\n");
+        assert_eq!(
+            client.send(&["LRANGE", "l", "0", "-1"]).await,
+            b"*4\r\n$1\r\nz\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nc\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_list_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["LPUSH", "k", "a"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+// Library
+use crate::{
+    database::WrongType,
+    errors::Error,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// LRANGE
+// ------
+
+/// Handles the LRANGE command.
+/// The LRANGE command returns the elements of the list stored at the key within the given range.
+/// The command is in the format `LRANGE key start stop`.
+/// Both `start` and `stop` are zero-based and inclusive. Negative indices count from the end
+/// of the list, so `-1` is the last element. Out of range indices are clamped to the list bounds.
+/// The command returns an empty array if the key does not exist or the range is empty.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 3 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'LRANGE' command")
+            .await;
+    }
+
+    // Extract the start and stop indices from the arguments
+    let (start, stop) = match (args[1].as_str(), args[2].as_str()) {
+        (Some(start), Some(stop)) => match (start.parse::<i64>(), stop.parse::<i64>()) {
+            (Ok(start), Ok(stop)) => (start, stop),
+            _ => {
+                return connection.write_error(Error::NotInteger.to_string()).await;
+            }
+        },
+        _ => {
+            return connection.write_error(Error::NotInteger.to_string()).await;
+        }
+    };
+
+    // Get the list stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let list = match shard.get(connection.db, &args[0]) {
+        Some(Type::List(list)) => list.as_slice(),
+        Some(_) => {
+            return connection.write_error(WrongType.to_string()).await;
+        }
+        None => &[],
+    };
+
+    // Respond with the elements in the range
+    let elements = match range(list.len(), start, stop) {
+        Some((start, stop)) => list[start..=stop]
+            .iter()
+            .map(|element| Type::BulkString(element.clone().into()))
+            .collect(),
+        None => Vec::new(),
+    };
+    let response = resp::array(elements);
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+/// Resolves the (possibly negative) inclusive `start` and `stop` indices against a list of length `len`.
+/// Returns the clamped, non-negative bounds, or `None` if the range is empty.
+pub fn range(len: usize, start: i64, stop: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 { len + start } else { start }.max(0);
+    let stop = if stop < 0 { len + stop } else { stop }.min(len - 1);
+    if start > stop {
+        return None;
+    }
+    Some((start as usize, stop as usize))
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_helpers;
+
+    #[test]
+    fn should_resolve_range() {
+        assert_eq!(range(5, 0, -1), Some((0, 4)));
+        assert_eq!(range(5, -2, -1), Some((3, 4)));
+        assert_eq!(range(5, -100, 100), Some((0, 4)));
+        assert_eq!(range(5, 3, 1), None);
+        assert_eq!(range(5, 5, 10), None);
+        assert_eq!(range(0, 0, -1), None);
+    }
+
+    #[tokio::test]
+    async fn should_support_negative_indices() {
+        let mut client = test_helpers::client().await;
+        client.send(&["RPUSH", "l", "a", "b", "c"]).await;
+        assert_eq!(
+            client.send(&["LRANGE", "l", "-2", "-1"]).await,
+            b"*2\r\n$1\r\nb\r\n$1\r\nc\r\n"
+        );
+        assert_eq!(
+            client.send(&["LRANGE", "l", "1", "100"]).await,
+            b"*2\r\n$1\r\nb\r\n$1\r\nc\r\n"
+        );
+        assert_eq!(
+            client.send(&["LRANGE", "missing", "0", "-1"]).await,
+            b"*0\r\n"
+        );
+    }
+}
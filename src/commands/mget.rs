@@ -0,0 +1,67 @@
+// Library
+use crate::{
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// MGET
+// ----
+
+/// Handles the MGET command.
+/// The MGET command gets the values of all the specified keys.
+/// The command is in the format `MGET key [key ...]`.
+/// The command returns an array with the value of each key, in order.
+/// Keys that do not exist (or have expired) or that do not hold a string are returned as null.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.is_empty() {
+        return connection
+            .write_error("ERR wrong number of arguments for 'MGET' command")
+            .await;
+    }
+
+    // Get the value of each key from the database
+    let db = server.lock().await.db.clone();
+    let mut values = Vec::with_capacity(args.len());
+    for key in args {
+        values.push(match db.get(connection.db, key).await {
+            Some(value @ Type::BulkString(_)) => value,
+            _ => Type::Null,
+        });
+    }
+
+    // Respond with the array of values
+    let response = resp::array(values);
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_return_values_and_nulls() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        client.send(&["SET", "expired", "2", "PX", "1"]).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(
+            client.send(&["MGET", "a", "missing", "expired"]).await,
+            b"*3\r\n$1\r\n1\r\n$-1\r\n$-1\r\n"
+        );
+    }
+}
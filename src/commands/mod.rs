@@ -1,29 +1,256 @@
 // Library
 use crate::{
-    parser::{self, resp},
-    server::{connection::Connection, Server},
-};
-use std::{sync::Arc, time::Duration};
-use tokio::{
-    sync::{mpsc, Mutex},
-    time::timeout,
+    parser::resp,
+    server::{
+        self,
+        connection::{Connection, Kind},
+        Server,
+    },
 };
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
 
 // Commands
+mod acl;
+mod append;
+mod auth;
+mod bitcount;
+mod blpop;
+mod client;
+mod command;
 mod config;
+mod copy;
+mod debug;
+mod del;
 mod echo;
+mod exists;
+mod expire;
+mod flush;
 mod get;
+mod getbit;
+mod getdel;
+mod getex;
+mod getrange;
+mod hdel;
+mod hello;
+mod hget;
+mod hgetall;
+mod hincrby;
+mod hincrbyfloat;
+mod hlen;
+mod hmget;
+mod hscan;
+mod hset;
+mod hsetnx;
+mod incr;
+mod incrby;
+mod incrbyfloat;
 mod info;
 mod keys;
+mod llen;
+mod lmove;
+mod lpop;
+mod lpos;
+mod lpush;
+mod lrange;
+mod mget;
+mod move_cmd;
+mod mset;
+mod persist;
 mod ping;
+mod psubscribe;
 mod psync;
+mod publish;
+mod punsubscribe;
 mod replconf;
+mod sadd;
+mod scan;
+mod scard;
+mod select;
 mod set;
+mod setbit;
+mod setex;
+mod setrange;
+mod shutdown;
+mod sinterstore;
+mod sismember;
+mod smembers;
+mod smismember;
+mod srandmember;
+mod srem;
+mod sscan;
+mod strlen;
+mod subscribe;
+mod ttl;
 mod type_cmd;
+mod unsubscribe;
 mod wait;
 mod xadd;
+mod xdel;
+mod xlen;
 mod xrange;
 mod xread;
+mod zadd;
+mod zrange;
+mod zrangebyscore;
+mod zrank;
+mod zrem;
+mod zscore;
+
+#[cfg(test)]
+pub mod test_helpers;
+
+/// The names of all the commands handled by the server.
+/// Any command added to the `handle` function must also be listed here,
+/// as this list is reported by the COMMAND command.
+pub const COMMANDS: &[&str] = &[
+    "ACL",
+    "APPEND",
+    "AUTH",
+    "BITCOUNT",
+    "BLPOP",
+    "BRPOP",
+    "CLIENT",
+    "COMMAND",
+    "CONFIG",
+    "COPY",
+    "DEBUG",
+    "DECR",
+    "DECRBY",
+    "DEL",
+    "ECHO",
+    "EXISTS",
+    "EXPIRE",
+    "FLUSHALL",
+    "FLUSHDB",
+    "GET",
+    "GETBIT",
+    "GETDEL",
+    "GETEX",
+    "GETRANGE",
+    "HDEL",
+    "HELLO",
+    "HGET",
+    "HGETALL",
+    "HINCRBY",
+    "HINCRBYFLOAT",
+    "HLEN",
+    "HMGET",
+    "HSCAN",
+    "HSET",
+    "HSETNX",
+    "INCR",
+    "INCRBY",
+    "INCRBYFLOAT",
+    "INFO",
+    "KEYS",
+    "LLEN",
+    "LMOVE",
+    "LPOP",
+    "LPUSH",
+    "LPOS",
+    "LRANGE",
+    "MGET",
+    "MOVE",
+    "MSET",
+    "PERSIST",
+    "PEXPIRE",
+    "PING",
+    "PSETEX",
+    "PSUBSCRIBE",
+    "PSYNC",
+    "PTTL",
+    "PUBLISH",
+    "PUNSUBSCRIBE",
+    "REPLCONF",
+    "RPOP",
+    "RPOPLPUSH",
+    "RPUSH",
+    "SADD",
+    "SCAN",
+    "SCARD",
+    "SDIFFSTORE",
+    "SELECT",
+    "SET",
+    "SETBIT",
+    "SETEX",
+    "SETRANGE",
+    "SHUTDOWN",
+    "SINTERSTORE",
+    "SISMEMBER",
+    "SMEMBERS",
+    "SMISMEMBER",
+    "SRANDMEMBER",
+    "SREM",
+    "SSCAN",
+    "STRLEN",
+    "SUBSCRIBE",
+    "SUNIONSTORE",
+    "TTL",
+    "TYPE",
+    "UNSUBSCRIBE",
+    "WAIT",
+    "XADD",
+    "XDEL",
+    "XLEN",
+    "XRANGE",
+    "XREAD",
+    "ZADD",
+    "ZRANGE",
+    "ZRANGEBYSCORE",
+    "ZRANK",
+    "ZREM",
+    "ZSCORE",
+];
+
+/// The names of the commands that modify the dataset.
+/// A replica rejects these from its clients, and only applies the ones propagated by its master.
+const WRITE_COMMANDS: &[&str] = &[
+    "APPEND",
+    "BLPOP",
+    "BRPOP",
+    "COPY",
+    "DECR",
+    "DECRBY",
+    "DEL",
+    "EXPIRE",
+    "FLUSHALL",
+    "FLUSHDB",
+    "GETDEL",
+    "GETEX",
+    "HDEL",
+    "HINCRBY",
+    "HINCRBYFLOAT",
+    "HSET",
+    "HSETNX",
+    "INCR",
+    "INCRBY",
+    "INCRBYFLOAT",
+    "LMOVE",
+    "LPOP",
+    "LPUSH",
+    "MOVE",
+    "MSET",
+    "PERSIST",
+    "PEXPIRE",
+    "PSETEX",
+    "RPOP",
+    "RPOPLPUSH",
+    "RPUSH",
+    "SADD",
+    "SDIFFSTORE",
+    "SET",
+    "SETBIT",
+    "SETEX",
+    "SETRANGE",
+    "SINTERSTORE",
+    "SREM",
+    "SUNIONSTORE",
+    "XADD",
+    "XDEL",
+    "ZADD",
+    "ZREM",
+];
 
 /// Handles the incoming command by parsing it and calling the appropriate command handler.
 pub async fn handle(
@@ -33,8 +260,8 @@ pub async fn handle(
     wait_channel: &Arc<Mutex<(mpsc::Sender<u64>, mpsc::Receiver<u64>)>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Extract the command from the parsed data
-    let command = match cmd.get(0) {
-        Some(resp::Type::BulkString(command)) => command,
+    let command = match cmd.first().and_then(resp::Type::as_str) {
+        Some(command) => command,
         _ => {
             let response = resp::Type::SimpleError("ERR unknown command\r\n".into());
             conn.write_all(&response.as_bytes()).await?;
@@ -42,41 +269,462 @@ pub async fn handle(
         }
     };
 
+    // Count the command
+    let command = command.to_uppercase();
+    let stats = Arc::clone(&server.lock().await.stats);
+    stats.record_command(&command);
+
+    // A replica is read-only for its clients, as the writes come from its master over the replication connection
+    if conn.kind == Kind::Main
+        && WRITE_COMMANDS.contains(&command.as_str())
+        && !server.lock().await.role.is_master()
+    {
+        return conn
+            .write_error("READONLY You can't write against a read only replica")
+            .await;
+    }
+
     // Handle the command
-    match command.to_uppercase().as_str() {
-        "PING" => ping::command(cmd, conn, server).await?,
+    match command.as_str() {
+        "PING" => ping::command(cmd, conn).await?,
 
         "ECHO" => echo::command(&cmd[1..], conn).await?,
 
+        "COMMAND" => command::command(&cmd[1..], conn).await?,
+
+        "CLIENT" => client::command(&cmd[1..], conn, server).await?,
+
+        "AUTH" => auth::command(&cmd[1..], conn, server).await?,
+
+        "ACL" => acl::command(&cmd[1..], conn, server).await?,
+
+        "HELLO" => hello::command(&cmd[1..], conn, server).await?,
+
+        "SELECT" => select::command(&cmd[1..], conn).await?,
+
         "SET" => {
-            set::command(cmd, conn, server).await?;
-            broadcast(server, cmd).await?;
+            if set::command(cmd, conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "SETEX" => {
+            if setex::command("SETEX", &cmd[1..], conn, server, 1000).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "PSETEX" => {
+            if setex::command("PSETEX", &cmd[1..], conn, server, 1).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
         }
 
         "GET" => get::command(&cmd[1..], conn, server).await?,
 
+        "GETDEL" => {
+            if getdel::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "GETEX" => {
+            if getex::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "MGET" => mget::command(&cmd[1..], conn, server).await?,
+
+        "MSET" => {
+            if mset::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "DEL" => {
+            if del::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "INCR" => {
+            if incr::command("INCR", &cmd[1..], conn, server, 1).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "DECR" => {
+            if incr::command("DECR", &cmd[1..], conn, server, -1).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "INCRBY" => {
+            if incrby::command("INCRBY", &cmd[1..], conn, server, 1).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "DECRBY" => {
+            if incrby::command("DECRBY", &cmd[1..], conn, server, -1).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "INCRBYFLOAT" => {
+            if incrbyfloat::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "APPEND" => {
+            if append::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "STRLEN" => strlen::command(&cmd[1..], conn, server).await?,
+
+        "SETBIT" => {
+            if setbit::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "GETBIT" => getbit::command(&cmd[1..], conn, server).await?,
+
+        "BITCOUNT" => bitcount::command(&cmd[1..], conn, server).await?,
+
+        "SETRANGE" => {
+            if setrange::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "GETRANGE" => getrange::command(&cmd[1..], conn, server).await?,
+
+        "COPY" => {
+            if copy::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "MOVE" => {
+            if move_cmd::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "LPUSH" => {
+            if lpush::command("LPUSH", &cmd[1..], conn, server, true).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "RPUSH" => {
+            if lpush::command("RPUSH", &cmd[1..], conn, server, false).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "LPOP" => {
+            if lpop::command("LPOP", &cmd[1..], conn, server, true).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "RPOP" => {
+            if lpop::command("RPOP", &cmd[1..], conn, server, false).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        // The blocking pops are propagated as LPOP and RPOP, so that replicas never block
+        "BLPOP" => {
+            let popped = blpop::command("BLPOP", &cmd[1..], conn, server, true).await?;
+            if let Some(key) = popped {
+                let pop = vec![resp::bulk_string("LPOP"), key];
+                broadcast(server, conn, &pop).await?;
+                notify(server, conn, &pop).await;
+            }
+        }
+
+        "BRPOP" => {
+            let popped = blpop::command("BRPOP", &cmd[1..], conn, server, false).await?;
+            if let Some(key) = popped {
+                let pop = vec![resp::bulk_string("RPOP"), key];
+                broadcast(server, conn, &pop).await?;
+                notify(server, conn, &pop).await;
+            }
+        }
+
+        "LMOVE" => {
+            if lmove::command("LMOVE", &cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "RPOPLPUSH" => {
+            if lmove::command("RPOPLPUSH", &cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "LLEN" => llen::command(&cmd[1..], conn, server).await?,
+
+        "LRANGE" => lrange::command(&cmd[1..], conn, server).await?,
+
+        "LPOS" => lpos::command(&cmd[1..], conn, server).await?,
+
+        "HSET" => {
+            if hset::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "HSETNX" => {
+            if hsetnx::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "HGET" => hget::command(&cmd[1..], conn, server).await?,
+
+        "HMGET" => hmget::command(&cmd[1..], conn, server).await?,
+
+        "HGETALL" => hgetall::command(&cmd[1..], conn, server).await?,
+
+        "HDEL" => {
+            if hdel::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "HINCRBY" => {
+            if hincrby::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "HINCRBYFLOAT" => {
+            if hincrbyfloat::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "HLEN" => hlen::command(&cmd[1..], conn, server).await?,
+
+        "HSCAN" => hscan::command(&cmd[1..], conn, server).await?,
+
+        "SADD" => {
+            if sadd::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "SREM" => {
+            if srem::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "SINTERSTORE" => {
+            let op = sinterstore::Operation::Intersection;
+            if sinterstore::command("SINTERSTORE", &cmd[1..], conn, server, op).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "SUNIONSTORE" => {
+            let op = sinterstore::Operation::Union;
+            if sinterstore::command("SUNIONSTORE", &cmd[1..], conn, server, op).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "SDIFFSTORE" => {
+            let op = sinterstore::Operation::Difference;
+            if sinterstore::command("SDIFFSTORE", &cmd[1..], conn, server, op).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "SMEMBERS" => smembers::command(&cmd[1..], conn, server).await?,
+
+        "SISMEMBER" => sismember::command(&cmd[1..], conn, server).await?,
+
+        "SMISMEMBER" => smismember::command(&cmd[1..], conn, server).await?,
+
+        "SRANDMEMBER" => srandmember::command(&cmd[1..], conn, server).await?,
+
+        "SSCAN" => sscan::command(&cmd[1..], conn, server).await?,
+
+        "SCARD" => scard::command(&cmd[1..], conn, server).await?,
+
+        "ZADD" => {
+            if zadd::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "ZREM" => {
+            if zrem::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "ZSCORE" => zscore::command(&cmd[1..], conn, server).await?,
+
+        "ZRANK" => zrank::command(&cmd[1..], conn, server).await?,
+
+        "ZRANGE" => zrange::command(&cmd[1..], conn, server).await?,
+
+        "ZRANGEBYSCORE" => zrangebyscore::command(&cmd[1..], conn, server).await?,
+
+        "EXISTS" => exists::command(&cmd[1..], conn, server).await?,
+
+        "EXPIRE" => {
+            if expire::command("EXPIRE", &cmd[1..], conn, server, 1000).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "PEXPIRE" => {
+            if expire::command("PEXPIRE", &cmd[1..], conn, server, 1).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "PERSIST" => {
+            if persist::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "FLUSHALL" => {
+            if flush::command(true, &cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "FLUSHDB" => {
+            if flush::command(false, &cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
+
+        "TTL" => ttl::command("TTL", &cmd[1..], conn, server, 1000).await?,
+
+        "PTTL" => ttl::command("PTTL", &cmd[1..], conn, server, 1).await?,
+
         "INFO" => info::command(&cmd[1..], conn, server).await?,
 
         "REPLCONF" => replconf::command(&cmd[1..], conn, server, wait_channel).await?,
 
         "PSYNC" => {
             psync::command(&cmd[1..], conn, server).await?;
-            receive(server, conn, wait_channel).await?;
+            // The error is kept as a string, as the boxed error cannot be held across an await
+            let result = receive(server, conn, wait_channel)
+                .await
+                .map_err(|e| e.to_string());
+
+            // The replica has disconnected once the replication stream ends
+            server
+                .lock()
+                .await
+                .replicas
+                .retain(|replica| replica.addr != conn.addr);
+            result?;
         }
 
         "WAIT" => wait::command(&cmd[1..], conn, server, wait_channel).await?,
 
-        "CONFIG" => config::command(&cmd, conn, server).await?,
+        "SHUTDOWN" => shutdown::command(&cmd[1..], conn, server).await?,
 
-        "KEYS" => keys::command(&cmd, conn, server).await?,
+        "DEBUG" => debug::command(&cmd[1..], conn, server).await?,
 
-        "TYPE" => type_cmd::command(&cmd, conn, server).await?,
+        "CONFIG" => config::command(cmd, conn, server).await?,
+
+        "KEYS" => keys::command(cmd, conn, server).await?,
+
+        "SCAN" => scan::command(&cmd[1..], conn, server).await?,
+
+        "TYPE" => type_cmd::command(cmd, conn, server).await?,
+
+        "SUBSCRIBE" => subscribe::command(&cmd[1..], conn, server).await?,
+
+        "UNSUBSCRIBE" => unsubscribe::command(&cmd[1..], conn, server).await?,
+
+        "PSUBSCRIBE" => psubscribe::command(&cmd[1..], conn, server).await?,
+
+        "PUNSUBSCRIBE" => punsubscribe::command(&cmd[1..], conn, server).await?,
+
+        "PUBLISH" => publish::command(&cmd[1..], conn, server).await?,
+
+        // The entry is propagated with its ID, so that replicas do not generate a different one
+        "XADD" => {
+            let added = xadd::command(cmd, conn, server).await?;
+            if let Some(id) = added {
+                let mut add = cmd.clone();
+                add[2] = resp::bulk_string(&id.to_string());
+                broadcast(server, conn, &add).await?;
+                notify(server, conn, &add).await;
+            }
+        }
+
+        "XDEL" => {
+            if xdel::command(&cmd[1..], conn, server).await? {
+                broadcast(server, conn, cmd).await?;
+                notify(server, conn, cmd).await;
+            }
+        }
 
-        "XADD" => xadd::command(&cmd, conn, server).await?,
+        "XLEN" => xlen::command(&cmd[1..], conn, server).await?,
 
-        "XRANGE" => xrange::command(&cmd, conn, server).await?,
+        "XRANGE" => xrange::command(cmd, conn, server).await?,
 
-        "XREAD" => xread::command(&cmd, conn, server).await?,
+        "XREAD" => xread::command(cmd, conn, server).await?,
 
         _ => {
             let response = resp::Type::SimpleError(format!("ERR unknown command: {:?}\r\n", cmd));
@@ -91,13 +739,16 @@ pub async fn handle(
 // HELPER FUNCTIONS
 // ----------------
 
-/// Broadcast the value on the server's broadcast sender channel
+/// Broadcast the value on the server's broadcast sender channel.
+/// If the connection has a different database selected than the replication stream,
+/// a SELECT command is broadcast first so that replicas apply the write to the same database.
 async fn broadcast(
     server: &Arc<Mutex<Server>>,
+    conn: &Connection,
     cmd: &Vec<resp::Type>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Get the server instance from the Arc<Mutex<Server>>
-    let server = server.lock().await;
+    let mut server = server.lock().await;
     let addr = server.addr.clone();
     let role = server.role.clone();
 
@@ -106,6 +757,17 @@ async fn broadcast(
         return Ok(());
     }
 
+    // Switch the replication stream to the connection's database
+    if server.repl_db != conn.db {
+        let select = resp::Type::Array(vec![
+            resp::Type::BulkString("SELECT".into()),
+            resp::Type::BulkString(conn.db.to_string().into()),
+        ]);
+        server.master_repl_offset += select.as_bytes().len() as u64;
+        server.sender.send(select)?;
+        server.repl_db = conn.db;
+    }
+
     // Broadcast the value to all receivers
     println!(
         "[{} - {}] Broadcasting: {:?} to {} receivers",
@@ -114,153 +776,264 @@ async fn broadcast(
         cmd,
         server.sender.receiver_count()
     );
-    server.sender.send(resp::Type::Array(cmd.clone()))?;
+    let value = resp::Type::Array(cmd.clone());
+    // The master's offset counts every byte propagated to the replicas
+    server.master_repl_offset += value.as_bytes().len() as u64;
+    server.sender.send(value)?;
     Ok(())
 }
 
-/// Receive messages from the broadcast channel
+/// Publishes the keyspace notifications for the write command, if they are enabled.
+/// A `__keyspace@<db>__:<key>` message carries the event name,
+/// and a `__keyevent@<db>__:<event>` message carries the key.
+async fn notify(server: &Arc<Mutex<Server>>, conn: &Connection, cmd: &[resp::Type]) {
+    let mut server = server.lock().await;
+    let flags = server.notify_keyspace_events.clone();
+    if flags.is_empty() {
+        return;
+    }
+
+    for (class, event, key) in keyspace_events(cmd) {
+        // Skip the events whose class is not enabled (`A` is an alias for all the classes)
+        let enabled = flags.contains(class) || (flags.contains('A') && "g$lshzxet".contains(class));
+        if !enabled {
+            continue;
+        }
+        if flags.contains('K') {
+            let channel = format!("__keyspace@{}__:{}", conn.db, key);
+            server.publish(&channel, resp::bulk_string(event));
+        }
+        if flags.contains('E') {
+            let channel = format!("__keyevent@{}__:{}", conn.db, event);
+            server.publish(&channel, resp::bulk_string(key));
+        }
+    }
+}
+
+/// Returns the keyspace events generated by the write command.
+/// Each event holds the class of the event (as a `notify-keyspace-events` flag),
+/// the name of the event and the key it affects.
+fn keyspace_events(cmd: &[resp::Type]) -> Vec<(char, &'static str, &str)> {
+    let command = match cmd.first().and_then(resp::Type::as_str) {
+        Some(command) => command.to_uppercase(),
+        None => return Vec::new(),
+    };
+    let keys: Vec<&str> = cmd[1..].iter().filter_map(resp::Type::as_str).collect();
+    let first = keys.first().copied().into_iter();
+
+    match command.as_str() {
+        "SET" | "SETEX" | "PSETEX" => first.map(|key| ('$', "set", key)).collect(),
+        "MSET" => keys
+            .iter()
+            .step_by(2)
+            .map(|key| ('$', "set", *key))
+            .collect(),
+        "INCR" | "DECR" | "INCRBY" | "DECRBY" => first.map(|key| ('$', "incrby", key)).collect(),
+        "INCRBYFLOAT" => first.map(|key| ('$', "incrbyfloat", key)).collect(),
+        "APPEND" => first.map(|key| ('$', "append", key)).collect(),
+        "SETBIT" => first.map(|key| ('$', "setbit", key)).collect(),
+        "SETRANGE" => first.map(|key| ('$', "setrange", key)).collect(),
+        "DEL" => keys.iter().map(|key| ('g', "del", *key)).collect(),
+        "GETDEL" => first.map(|key| ('g', "del", key)).collect(),
+        "EXPIRE" | "PEXPIRE" => first.map(|key| ('g', "expire", key)).collect(),
+        "PERSIST" => first.map(|key| ('g', "persist", key)).collect(),
+        "COPY" => keys
+            .get(1)
+            .map(|key| ('g', "copy_to", *key))
+            .into_iter()
+            .collect(),
+        "MOVE" => first.map(|key| ('g', "move_from", key)).collect(),
+        "LPUSH" => first.map(|key| ('l', "lpush", key)).collect(),
+        "RPUSH" => first.map(|key| ('l', "rpush", key)).collect(),
+        "LPOP" => first.map(|key| ('l', "lpop", key)).collect(),
+        "RPOP" => first.map(|key| ('l', "rpop", key)).collect(),
+        "RPOPLPUSH" | "LMOVE" => match keys.as_slice() {
+            [src, dst] => vec![('l', "rpop", *src), ('l', "lpush", *dst)],
+            [src, dst, from, to] => {
+                let pop = match from.eq_ignore_ascii_case("LEFT") {
+                    true => "lpop",
+                    false => "rpop",
+                };
+                let push = match to.eq_ignore_ascii_case("LEFT") {
+                    true => "lpush",
+                    false => "rpush",
+                };
+                vec![('l', pop, *src), ('l', push, *dst)]
+            }
+            _ => Vec::new(),
+        },
+        "HSET" | "HSETNX" => first.map(|key| ('h', "hset", key)).collect(),
+        "HDEL" => first.map(|key| ('h', "hdel", key)).collect(),
+        "HINCRBY" => first.map(|key| ('h', "hincrby", key)).collect(),
+        "HINCRBYFLOAT" => first.map(|key| ('h', "hincrbyfloat", key)).collect(),
+        "SADD" => first.map(|key| ('s', "sadd", key)).collect(),
+        "SREM" => first.map(|key| ('s', "srem", key)).collect(),
+        "SINTERSTORE" => first.map(|key| ('s', "sinterstore", key)).collect(),
+        "SUNIONSTORE" => first.map(|key| ('s', "sunionstore", key)).collect(),
+        "SDIFFSTORE" => first.map(|key| ('s', "sdiffstore", key)).collect(),
+        "ZADD" => first.map(|key| ('z', "zadd", key)).collect(),
+        "ZREM" => first.map(|key| ('z', "zrem", key)).collect(),
+        "XADD" => first.map(|key| ('t', "xadd", key)).collect(),
+        "XDEL" => first.map(|key| ('t', "xdel", key)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Forwards the replication stream to the replica, and reads the replica's acknowledgements.
+/// The replica answers `REPLCONF GETACK` with `REPLCONF ACK <offset>`, which is recorded for the replica
+/// and sent to the wait channel. Returns once the replica disconnects or the stream is closed.
 async fn receive(
     server: &Arc<Mutex<Server>>,
     conn: &mut Connection,
     wait_channel: &Arc<Mutex<(mpsc::Sender<u64>, mpsc::Receiver<u64>)>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Acquire the server lock and create a receiver
-    let (addr, role, mut receiver) = {
-        println!("receive locking ...");
+    // Take the replication stream the replica was subscribed to when it was accepted
+    let mut receiver = match conn.replication.take() {
+        Some(receiver) => receiver,
+        None => return Ok(()),
+    };
+    let (addr, role, mut shutdown) = {
         let server = server.lock().await;
-        print!("locked 🔒");
         (
             server.addr.clone(),
             server.role.clone(),
-            server.sender.subscribe(),
+            server.shutdown_signal(),
         )
     };
 
     loop {
-        match receiver.recv().await {
-            Ok(cmd) => {
-                let array = match &cmd {
-                    resp::Type::Array(array) => array,
-                    _ => continue,
-                };
-
-                let command = match array.get(0) {
-                    Some(resp::Type::BulkString(command)) => command,
-                    _ => continue,
-                };
-
-                let subcommand = match array.get(1) {
-                    Some(resp::Type::BulkString(subcommand)) => subcommand,
-                    _ => continue,
-                };
-
-                let is_wait_cmd =
-                    command.to_uppercase() == "REPLCONF" && subcommand.to_uppercase() == "GETACK";
-
-                println!("Received broadcast: {:?}", cmd);
-
-                if is_wait_cmd {
-                    let mut buf = [0; 512];
-                    loop {
-                        match conn.stream.try_read(&mut buf) {
-                            Ok(0) => {
-                                break;
-                            }
-                            Ok(_) => {
-                                continue;
-                            }
-                            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                                println!("Would block");
-                                break;
-                            }
-                            Err(e) => {
-                                eprintln!("Error reading from socket: {}", e);
-                                break;
-                            }
-                        }
+        tokio::select! {
+            _ = server::shutdown_requested(&mut shutdown) => break,
+            cmd = receiver.recv() => {
+                let cmd = match cmd {
+                    Ok(cmd) => cmd,
+                    Err(e) => {
+                        println!("[{} - {}] Receiver Error: {:?}", addr, role, e);
+                        break;
                     }
-                }
+                };
 
-                println!("Forwarding broadcast to connection: {:?}", cmd);
-                // Forward all broadcast messages to the connection
+                // Forward all broadcast messages to the replica
                 conn.write_all(&cmd.as_bytes()).await?;
-
-                if is_wait_cmd {
-                    println!("Received REPLCONF ACK command");
-                    let offset = match array.get(2) {
-                        Some(resp::Type::BulkString(offset)) => offset,
-                        _ => continue,
-                    };
-
-                    println!("offset: {:?}", offset);
-                    let duration = Duration::from_millis(200);
-                    let res = timeout(duration, conn.stream.readable()).await;
-
-                    if res.is_err() {
-                        println!("[{} - {}] Replica did not respond", addr, role);
-                        continue;
-                    }
-
-                    let mut bytes_read_vec = Vec::new();
-                    let buf = &mut [0; 1024];
-                    match conn.stream.try_read(buf) {
-                        Ok(0) => {
-                            println!("[{}] Connection closed", addr);
-                            break;
-                        }
-                        Ok(n) => {
-                            bytes_read_vec.extend_from_slice(&buf[..n]);
-                        }
-                        Err(e) => {
-                            println!("[{} - {}] Error: {:?}", addr, role, e);
-                        }
+            }
+            // The error is kept as a string, as the boxed error cannot be held across an await
+            frames = async { conn.read_frames().await.map_err(|e| e.to_string()) } => {
+                let frames = match frames {
+                    Ok(Some(frames)) => frames,
+                    Ok(None) => {
+                        println!("[{} - {}] Replica {} disconnected", addr, role, conn.addr);
+                        break;
                     }
-
-                    if bytes_read_vec.is_empty() {
-                        let response = String::from_utf8_lossy(&bytes_read_vec);
-                        println!("[{} - {}] Received: {:?}", addr, role, response);
+                    Err(e) => {
+                        println!("[{} - {}] Invalid response from replica: {}", addr, role, e);
                         continue;
                     }
+                };
 
-                    println!("GOT BYTES: {:?}", bytes_read_vec);
-                    let response = parser::parse(&bytes_read_vec)?;
-                    println!("PARSED: {:?}", response);
-
-                    let array = match response.get(0) {
-                        Some(resp::Type::Array(array)) => array,
-                        _ => {
-                            println!("Invalid response: {:?}", response);
-                            continue;
-                        }
-                    };
-
-                    let offset = match array.get(2) {
-                        Some(resp::Type::BulkString(offset)) => offset.parse::<u64>()?,
-                        x => {
-                            println!("Invalid offset: {:?}", x);
-                            continue;
-                        }
+                for (frame, _) in frames {
+                    let Some(offset) = ack_offset(&frame) else {
+                        println!("[{} - {}] Unexpected response from replica: {:?}", addr, role, frame);
+                        continue;
                     };
-
                     println!("[{} - {}] Received ACK with offset {}", addr, role, offset);
 
-                    // Send the offset to the wait channel
+                    // Record the offset acknowledged by the replica
+                    conn.ack_offset = offset;
+                    let mut s = server.lock().await;
+                    if let Some(replica) = s
+                        .replicas
+                        .iter_mut()
+                        .find(|replica| replica.addr == conn.addr)
                     {
-                        println!("receive locking wait ...");
-                        let wc = wait_channel.lock().await;
-                        print!("locked 🔒");
-                        wc.0.send(offset)
-                            .await
-                            .expect("Failed to send offset to wait channel");
+                        replica.offset = offset;
+                    }
+                    drop(s);
+
+                    // Send the offset to the wait channel.
+                    // The sender is cloned so that the lock is not held while the channel is full.
+                    // The ACK is dropped if the channel is closed, as the offset has already been recorded.
+                    let sender = wait_channel.lock().await.0.clone();
+                    if sender.send(offset).await.is_err() {
+                        println!("[{} - {}] Wait channel closed, dropping ACK", addr, role);
                     }
                 }
             }
-            Err(e) => {
-                println!("[{} - {}] Receiver Error: {:?}", addr, role, e);
-                break;
-            }
         }
     }
 
     Ok(())
 }
+
+/// Extracts the offset from a `REPLCONF ACK <offset>` response
+fn ack_offset(frame: &resp::Type) -> Option<u64> {
+    let resp::Type::Array(array) = frame else {
+        return None;
+    };
+    match array.as_slice() {
+        [command, subcommand, offset]
+            if command.as_str()?.eq_ignore_ascii_case("REPLCONF")
+                && subcommand.as_str()?.eq_ignore_ascii_case("ACK") =>
+        {
+            offset.as_str()?.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::test_helpers;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn should_not_notify_or_propagate_failed_or_no_op_writes() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "n", "1"]).await;
+        client.send(&["SET", "s", "v"]).await;
+        client.send(&["RPUSH", "l", "a"]).await;
+        client
+            .send(&["CONFIG", "SET", "notify-keyspace-events", "KEA"])
+            .await;
+
+        // Listen to every keyspace notification, and to the replication stream
+        let (tx, mut events) = mpsc::unbounded_channel();
+        let mut replica = {
+            let mut server = client.server.lock().await;
+            server.patterns.entry("*".into()).or_default().insert(0, tx);
+            server.sender.subscribe()
+        };
+
+        let writes: &[&[&str]] = &[
+            &["DEL", "missing"],
+            &["INCRBY", "l", "1"],
+            &["INCRBY", "s", "1"],
+            &["INCRBY", "n", "x"],
+            &["APPEND", "l", "x"],
+            &["EXPIRE", "missing", "10"],
+            &["PERSIST", "n"],
+            &["LPOP", "missing"],
+            &["SADD", "l", "x"],
+            &["SREM", "missing", "x"],
+            &["HDEL", "missing", "f"],
+            &["ZREM", "missing", "m"],
+            &["GETDEL", "missing"],
+            &["GETEX", "n"],
+            &["COPY", "missing", "other"],
+            &["XDEL", "l", "1-1"],
+        ];
+        for write in writes {
+            client.send(write).await;
+            assert!(events.try_recv().is_err(), "{:?} notified", write);
+            assert!(replica.try_recv().is_err(), "{:?} propagated", write);
+        }
+
+        // A write that changes the data is still notified and propagated
+        client.send(&["DEL", "n"]).await;
+        assert!(events.try_recv().is_ok());
+        assert!(replica.try_recv().is_ok());
+    }
+}
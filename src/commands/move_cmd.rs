@@ -0,0 +1,132 @@
+// Library
+use crate::{
+    database::DATABASES,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// MOVE
+// ----
+
+/// Handles the MOVE command.
+/// The MOVE command moves a key from the currently selected database to the given database.
+/// The command is in the format `MOVE key db`.
+/// The time-to-live of the key is preserved. An existing key in the destination is never overwritten.
+/// The command returns 1 if the key was moved, and 0 if the key does not exist
+/// or already exists in the destination database.
+/// Returns true if the key was moved, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 2 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'MOVE' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Parse the destination database index
+    let dst = args[1]
+        .as_str()
+        .and_then(|index| index.parse::<usize>().ok());
+    let dst = match dst {
+        Some(dst) if dst < DATABASES => dst,
+        _ => {
+            if role.is_master() {
+                connection
+                    .write_error("ERR DB index is out of range")
+                    .await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // The source and destination must be different databases
+    if dst == connection.db {
+        if role.is_master() {
+            connection
+                .write_error("ERR source and destination objects are the same")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Move the key in the database
+    let db = server.lock().await.db.clone();
+    let moved = db.move_key(connection.db, dst, &args[0]).await;
+
+    // Respond with 1 if the key was moved, 0 otherwise
+    if role.is_master() {
+        let response = Type::Integer(moved as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(moved)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_move_key_to_other_database() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v", "EX", "100"]).await;
+        assert_eq!(client.send(&["MOVE", "k", "1"]).await, b":1\r\n");
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":0\r\n");
+        client.send(&["SELECT", "1"]).await;
+        assert_eq!(client.send(&["GET", "k"]).await, b"$1\r\nv\r\n");
+        assert_eq!(client.send(&["TTL", "k"]).await, b":100\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_not_move_missing_key() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["MOVE", "missing", "1"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_not_overwrite_existing_destination_key() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SELECT", "1"]).await;
+        client.send(&["SET", "k", "dst"]).await;
+        client.send(&["SELECT", "0"]).await;
+        client.send(&["SET", "k", "src"]).await;
+        assert_eq!(client.send(&["MOVE", "k", "1"]).await, b":0\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$3\r\nsrc\r\n");
+        client.send(&["SELECT", "1"]).await;
+        assert_eq!(client.send(&["GET", "k"]).await, b"$3\r\ndst\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_destination() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["MOVE", "k", "0"]).await,
+            b"-ERR source and destination objects are the same\r\n"
+        );
+        assert_eq!(
+            client.send(&["MOVE", "k", "16"]).await,
+            b"-ERR DB index is out of range\r\n"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// MSET
+// ----
+
+/// Handles the MSET command.
+/// The MSET command sets the values of multiple keys at once.
+/// The command is in the format `MSET key value [key value ...]`.
+/// Existing values are overwritten, and any existing expiry is cleared, just like SET.
+/// The command always returns OK, unless the number of arguments is invalid.
+/// Returns true if the values were written, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments, which must be key/value pairs
+    if args.is_empty() || !args.len().is_multiple_of(2) {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'mset' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Set each key/value pair in the database
+    let db = server.lock().await.db.clone();
+    for pair in args.chunks(2) {
+        db.set(connection.db, pair[0].clone(), pair[1].clone(), None)
+            .await;
+    }
+
+    if role.is_master() {
+        connection.write_ok().await?;
+    }
+
+    Ok(true)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_set_all_pairs() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["MSET", "a", "1", "b", "2"]).await, b"+OK\r\n");
+        assert_eq!(
+            client.send(&["MGET", "a", "b"]).await,
+            b"*2\r\n$1\r\n1\r\n$1\r\n2\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_odd_number_of_arguments() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["MSET", "a", "1", "b"]).await,
+            b"-ERR wrong number of arguments for 'mset' command\r\n"
+        );
+        assert_eq!(client.send(&["EXISTS", "a"]).await, b":0\r\n");
+    }
+}
@@ -0,0 +1,82 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -------
+// PERSIST
+// -------
+
+/// Handles the PERSIST command.
+/// The PERSIST command removes the existing timeout on a key, making it persistent.
+/// The command is in the format `PERSIST key`.
+/// The command returns 1 if the timeout was removed, and 0 if the key does not exist
+/// or does not have an associated timeout.
+/// Returns true if the timeout was removed, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 1 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'PERSIST' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Clear the expiry only if the key has one
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let has_expiry = shard
+        .get_item(connection.db, &args[0])
+        .is_some_and(|item| item.has_expiry());
+    let removed = has_expiry && shard.set_expiry(connection.db, &args[0], None);
+
+    // Respond with 1 if an expiry was removed, 0 otherwise
+    if role.is_master() {
+        let response = Type::Integer(removed as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(removed)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_remove_expiry() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1", "PX", "20"]).await;
+        assert_eq!(client.send(&["PERSIST", "a"]).await, b":1\r\n");
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(client.send(&["EXISTS", "a"]).await, b":1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_return_zero_without_expiry() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "a", "1"]).await;
+        assert_eq!(client.send(&["PERSIST", "a"]).await, b":0\r\n");
+        assert_eq!(client.send(&["PERSIST", "missing"]).await, b":0\r\n");
+    }
+}
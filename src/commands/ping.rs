@@ -1,43 +1,60 @@
 // Library
 use crate::{
     parser::resp,
-    server::{
-        connection::{Connection, Kind},
-        Server,
-    },
+    server::connection::{Connection, Kind},
 };
-use std::sync::Arc;
-use tokio::sync::Mutex;
 
 // ----
 // PING
 // ----
 
 /// Handles the PING command.
-/// The PING command simply returns a PONG response.
+/// The PING command is in the format `PING [message]`.
+/// Without a message, the command returns a PONG response.
+/// With a message, the command returns the message as a bulk string.
 pub async fn command(
-    args: &Vec<resp::Type>,
+    args: &[resp::Type],
     connection: &mut Connection,
-    server: &Arc<Mutex<Server>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Generate the response
-    let response = resp::Type::SimpleString("PONG".into());
+    // Generate the response (the first argument is the command name itself)
+    let response = match args {
+        [_] => resp::Type::SimpleString("PONG".into()),
+        [_, message] => message.clone(),
+        _ => resp::Type::SimpleError("ERR wrong number of arguments for 'ping' command".into()),
+    };
 
     // Send the response only if you are the master
     if connection.kind == Kind::Main {
         connection.write_all(&response.as_bytes()).await?;
-    } else {
-        // If you are a replica, update the replication offset
-        let len = resp::array(args.clone()).as_bytes().len() as u64;
-        let mut s = server.lock().await;
-        println!(
-            "PING(replica) {} + {} = {}",
-            s.repl_offset,
-            len,
-            s.repl_offset + len
-        );
-        s.repl_offset += len;
     }
 
     Ok(())
 }
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_reply_with_pong() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["PING"]).await, b"+PONG\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_echo_message() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["PING", "hello world"]).await,
+            b"$11\r\nhello world\r\n"
+        );
+        assert_eq!(
+            client.send(&["PING", "a", "b"]).await,
+            b"-ERR wrong number of arguments for 'ping' command\r\n"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+// Library
+use crate::{
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----------
+// PSUBSCRIBE
+// ----------
+
+/// Handles the PSUBSCRIBE command.
+/// The PSUBSCRIBE command subscribes the client to the channels matching the given glob-style patterns.
+/// Messages published to a matching channel are then pushed to the client as a `pmessage`.
+/// The command is in the format `PSUBSCRIBE pattern [pattern ...]`.
+/// The command replies with a `psubscribe` message for every pattern,
+/// along with the number of channels and patterns the client is subscribed to.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.is_empty() {
+        return connection
+            .write_error("ERR wrong number of arguments for 'psubscribe' command")
+            .await;
+    }
+
+    // Get the patterns
+    let Some(patterns) = args.iter().map(Type::as_str).collect::<Option<Vec<_>>>() else {
+        return connection
+            .write_error("ERR invalid pattern for 'psubscribe' command")
+            .await;
+    };
+
+    for pattern in patterns {
+        // Register the connection as a subscriber of the pattern
+        server
+            .lock()
+            .await
+            .patterns
+            .entry(pattern.to_string())
+            .or_default()
+            .insert(connection.id, connection.messages_sender.clone());
+        connection.pattern_subscriptions.insert(pattern.to_string());
+
+        // Confirm the subscription
+        let count = connection.subscription_count() as i64;
+        connection
+            .write_push(vec![
+                resp::bulk_string("psubscribe"),
+                resp::bulk_string(pattern),
+                Type::Integer(count),
+            ])
+            .await?;
+    }
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_count_channels_and_patterns() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SUBSCRIBE", "news"]).await;
+        assert_eq!(
+            client.send(&["PSUBSCRIBE", "news.*"]).await,
+            b"*3\r\n$10\r\npsubscribe\r\n$6\r\nnews.*\r\n:2\r\n"
+        );
+        assert_eq!(client.server.lock().await.patterns.len(), 1);
+    }
+}
@@ -1,8 +1,7 @@
 // Library
 use crate::{
-    database, helpers,
     parser::resp,
-    server::{connection::Connection, Server},
+    server::{connection::Connection, replication::Replica, Server},
 };
 use std::{sync::Arc, time::Duration};
 use tokio::sync::Mutex;
@@ -14,7 +13,8 @@ use tokio::sync::Mutex;
 /// Handles the PSYNC command
 /// The PSYNC command is used to synchronize a replica server with a master server.
 /// The command is used by the replica to request a full synchronization from the master.
-/// The master sends an RDB file to the replica, which is used to synchronize the replica server.
+/// The master sends a snapshot of its dataset as an RDB file to the replica, which is used to synchronize the replica server.
+/// The writes made after the snapshot is taken are propagated through the replication stream.
 pub async fn command(
     args: &[resp::Type],
     connection: &mut Connection,
@@ -58,18 +58,213 @@ pub async fn command(
         resp::Type::SimpleString(format!("FULLRESYNC {} {}", repl_id, master_repl_offset));
     connection.write_all(&response.as_bytes()).await?;
 
-    // Add the replica to the list of replicas
-    server.replicas.push(connection.addr.clone());
+    // Add the replica to the list of replicas, and subscribe it to the replication stream
+    server.replicas.push(Replica {
+        addr: connection.addr,
+        port: connection.listening_port.unwrap_or(connection.addr.port()),
+        offset: 0,
+    });
+    connection.replication = Some(server.sender.subscribe());
+    let db = server.db.clone();
+    drop(server);
+
+    // Take the snapshot right after subscribing, so that no write in between is missed
+    let rdb_bytes = db.dump().await;
 
     // Sleep for a short duration to give the replica time to setup the main connection
     let duration = Duration::from_millis(500);
     tokio::time::sleep(duration).await;
 
-    // Send an empty RDB file to the replica
-    let rdb = database::rdb::EMPTY_RDB;
-    let rdb_bytes = helpers::base64_to_bytes(rdb);
+    // Send the snapshot to the replica
     let response = resp::Type::RDBFile(rdb_bytes);
     connection.write_all(&response.as_bytes()).await?;
 
     Ok(())
 }
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::{self, test_helpers},
+        config::DEFAULT_BUFFER_SIZE,
+        parser::resp,
+        server::{self, connection, connection::Kind, replication::Role},
+    };
+    use std::{sync::Arc, time::Duration};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+        sync::{mpsc, Mutex},
+        time::timeout,
+    };
+
+    #[tokio::test]
+    async fn should_track_replica_until_it_disconnects() {
+        let server = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+        let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let replica = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (accepted, addr) = listener.accept().await.unwrap();
+        let mut conn = connection::new(accepted, addr, Kind::Main, DEFAULT_BUFFER_SIZE);
+
+        let psync = vec![
+            resp::bulk_string("PSYNC"),
+            resp::bulk_string("?"),
+            resp::bulk_string("-1"),
+        ];
+        let disconnect = async {
+            // Wait for the handshake to complete
+            tokio::time::sleep(Duration::from_millis(600)).await;
+            assert_eq!(server.lock().await.replicas.len(), 1);
+
+            // The replication stream ends once the replica has disconnected
+            drop(replica);
+        };
+        let (result, _) = tokio::join!(
+            commands::handle(&psync, &mut conn, &server, &wait_channel),
+            disconnect
+        );
+
+        assert!(result.is_ok());
+        assert!(server.lock().await.replicas.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_send_replica_ack_offset_to_wait_channel() {
+        let server = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+        let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut replica = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (accepted, addr) = listener.accept().await.unwrap();
+        let mut conn = connection::new(accepted, addr, Kind::Main, DEFAULT_BUFFER_SIZE);
+
+        let (s, wc) = (Arc::clone(&server), Arc::clone(&wait_channel));
+        tokio::spawn(async move {
+            let psync = vec![
+                resp::bulk_string("PSYNC"),
+                resp::bulk_string("?"),
+                resp::bulk_string("-1"),
+            ];
+            let _ = commands::handle(&psync, &mut conn, &s, &wc).await;
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The GETACK is forwarded to the replica after the snapshot
+        let getack = resp::array(vec![
+            resp::bulk_string("REPLCONF"),
+            resp::bulk_string("GETACK"),
+            resp::bulk_string("*"),
+        ]);
+        server.lock().await.sender.send(getack.clone()).unwrap();
+        let mut received = Vec::new();
+        let mut buf = [0; 1024];
+        while !received.ends_with(&getack.as_bytes()) {
+            let n = timeout(Duration::from_secs(2), replica.read(&mut buf))
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(n > 0, "connection closed before the GETACK");
+            received.extend_from_slice(&buf[..n]);
+        }
+
+        // The ACK is framed even when it arrives in pieces
+        replica
+            .write_all(b"*3\r\n$8\r\nREPLCONF\r\n$3\r\nACK\r\n")
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        replica.write_all(b"$2\r\n42\r\n").await.unwrap();
+
+        let offset = timeout(Duration::from_secs(1), async {
+            loop {
+                if let Ok(offset) = wait_channel.lock().await.1.try_recv() {
+                    return offset;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        assert_eq!(offset.await.unwrap(), 42);
+        assert_eq!(server.lock().await.replicas[0].offset, 42);
+    }
+
+    #[tokio::test]
+    async fn should_not_block_other_clients_during_handshake() {
+        let server = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+
+        // The handshake waits before sending the RDB file to the replica
+        let mut replica = test_helpers::client_for(&server).await;
+        tokio::spawn(async move { replica.send(&["PSYNC", "?", "-1"]).await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // ... while other clients are served in the meantime
+        let mut client = test_helpers::client_for(&server).await;
+        let ping = timeout(Duration::from_millis(100), client.send(&["PING"]));
+        assert_eq!(ping.await.unwrap(), b"+PONG\r\n");
+        let get = timeout(Duration::from_millis(100), client.send(&["GET", "k"]));
+        assert_eq!(get.await.unwrap(), b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_sync_existing_keys_to_replica() {
+        // A master with keys set before the replica connects
+        let master = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+        let mut client = test_helpers::client_for(&master).await;
+        client.send(&["SET", "k", "v"]).await;
+        client.send(&["RPUSH", "list", "a", "b"]).await;
+        client.send(&["SELECT", "2"]).await;
+        client.send(&["SET", "other", "1"]).await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let master_addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
+            let (stream, addr) = listener.accept().await.unwrap();
+            let mut conn = connection::new(stream, addr, Kind::Main, DEFAULT_BUFFER_SIZE);
+            let _ = conn.handle(&master, &wait_channel).await;
+        });
+
+        // The replica performs the handshake and then handles the replication stream
+        let mut replica = server::new("127.0.0.1", 0);
+        replica.role = Role::Replica(master_addr.to_string());
+        let mut conn = replica
+            .role
+            .send_handshake(6380, DEFAULT_BUFFER_SIZE)
+            .await
+            .unwrap();
+        let replica = Arc::new(Mutex::new(replica));
+        let server = Arc::clone(&replica);
+        tokio::spawn(async move {
+            let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
+            let _ = conn.handle(&server, &wait_channel).await;
+        });
+
+        // Wait for the snapshot to be received
+        let db = replica.lock().await.db.clone();
+        let k = resp::bulk_string("k");
+        timeout(Duration::from_secs(2), async {
+            while db.get(0, &k).await.is_none() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the replica did not receive the snapshot");
+
+        assert_eq!(db.get(0, &k).await, Some(resp::bulk_string("v")));
+        assert_eq!(
+            db.get(0, &resp::bulk_string("list")).await,
+            Some(resp::Type::List(vec!["a".into(), "b".into()]))
+        );
+        assert_eq!(
+            db.get(2, &resp::bulk_string("other")).await,
+            Some(resp::bulk_string("1"))
+        );
+    }
+}
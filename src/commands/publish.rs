@@ -0,0 +1,176 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -------
+// PUBLISH
+// -------
+
+/// Handles the PUBLISH command.
+/// The PUBLISH command posts a message to the given channel,
+/// which is pushed to every client subscribed to the channel as a `message`,
+/// and to every client subscribed to a pattern matching the channel as a `pmessage`.
+/// The command is in the format `PUBLISH channel message`.
+/// The command returns the number of clients that received the message.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'publish' command")
+            .await;
+    }
+
+    // Get the channel name
+    let Some(channel) = args[0].as_str() else {
+        return connection
+            .write_error("ERR invalid channel name for 'publish' command")
+            .await;
+    };
+
+    // Send the message to the subscribers
+    let received = server.lock().await.publish(channel, args[1].clone());
+
+    // Respond with the number of clients that received the message
+    let response = Type::Integer(received as i64);
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::test_helpers,
+        config::DEFAULT_BUFFER_SIZE,
+        server::{self, connection, connection::Kind, Server},
+    };
+    use std::sync::Arc;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+        sync::{mpsc, Mutex},
+    };
+
+    /// Reads from the stream until the expected number of bytes have been received
+    async fn read_exact(stream: &mut TcpStream, len: usize) -> Vec<u8> {
+        let mut response = vec![0; len];
+        stream.read_exact(&mut response).await.unwrap();
+        response
+    }
+
+    /// Connects a client to a real connection handler, so published messages are forwarded to it
+    async fn spawn_connection(server: &Arc<Mutex<Server>>) -> TcpStream {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (accepted, addr) = listener.accept().await.unwrap();
+        let mut conn = connection::new(accepted, addr, Kind::Main, DEFAULT_BUFFER_SIZE);
+        let server = Arc::clone(server);
+        tokio::spawn(async move {
+            let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
+            conn.handle(&server, &wait_channel)
+                .await
+                .expect("Failed to handle connection");
+        });
+        client
+    }
+
+    #[tokio::test]
+    async fn should_deliver_message_to_subscriber() {
+        let server = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+        let mut subscriber = spawn_connection(&server).await;
+
+        subscriber
+            .write_all(b"*2\r\n$9\r\nSUBSCRIBE\r\n$4\r\nnews\r\n")
+            .await
+            .unwrap();
+        let confirmation = b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n";
+        assert_eq!(
+            read_exact(&mut subscriber, confirmation.len()).await,
+            confirmation
+        );
+
+        // Publish from another client
+        let mut publisher = test_helpers::client_for(&server).await;
+        assert_eq!(
+            publisher.send(&["PUBLISH", "news", "hello"]).await,
+            b":1\r\n"
+        );
+        assert_eq!(
+            publisher.send(&["PUBLISH", "other", "hello"]).await,
+            b":0\r\n"
+        );
+
+        let message = b"*3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n";
+        assert_eq!(read_exact(&mut subscriber, message.len()).await, message);
+    }
+
+    #[tokio::test]
+    async fn should_deliver_message_to_pattern_subscriber() {
+        let server = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+        let mut subscriber = spawn_connection(&server).await;
+
+        subscriber
+            .write_all(b"*2\r\n$10\r\nPSUBSCRIBE\r\n$6\r\nnews.*\r\n")
+            .await
+            .unwrap();
+        let confirmation = b"*3\r\n$10\r\npsubscribe\r\n$6\r\nnews.*\r\n:1\r\n";
+        assert_eq!(
+            read_exact(&mut subscriber, confirmation.len()).await,
+            confirmation
+        );
+
+        let mut publisher = test_helpers::client_for(&server).await;
+        assert_eq!(
+            publisher.send(&["PUBLISH", "news.tech", "hello"]).await,
+            b":1\r\n"
+        );
+        assert_eq!(
+            publisher.send(&["PUBLISH", "sports", "hello"]).await,
+            b":0\r\n"
+        );
+
+        let message = b"*4\r\n$8\r\npmessage\r\n$6\r\nnews.*\r\n$9\r\nnews.tech\r\n$5\r\nhello\r\n";
+        assert_eq!(read_exact(&mut subscriber, message.len()).await, message);
+    }
+
+    #[tokio::test]
+    async fn should_publish_keyspace_notifications() {
+        let server = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+        let mut subscriber = spawn_connection(&server).await;
+
+        subscriber
+            .write_all(b"*2\r\n$10\r\nPSUBSCRIBE\r\n$18\r\n__keyevent@0__:set\r\n")
+            .await
+            .unwrap();
+        let confirmation = b"*3\r\n$10\r\npsubscribe\r\n$18\r\n__keyevent@0__:set\r\n:1\r\n";
+        assert_eq!(
+            read_exact(&mut subscriber, confirmation.len()).await,
+            confirmation
+        );
+
+        // Notifications are only published once they are enabled
+        let mut client = test_helpers::client_for(&server).await;
+        client.send(&["SET", "before", "v"]).await;
+        client
+            .send(&["CONFIG", "SET", "notify-keyspace-events", "E$"])
+            .await;
+        client.send(&["SET", "after", "v"]).await;
+
+        let message = b"*4\r\n$8\r\npmessage\r\n$18\r\n__keyevent@0__:set\r\n$18\r\n__keyevent@0__:set\r\n$5\r\nafter\r\n";
+        assert_eq!(read_exact(&mut subscriber, message.len()).await, message);
+    }
+}
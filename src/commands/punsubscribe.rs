@@ -0,0 +1,89 @@
+// Library
+use crate::{
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------------
+// PUNSUBSCRIBE
+// ------------
+
+/// Handles the PUNSUBSCRIBE command.
+/// The PUNSUBSCRIBE command unsubscribes the client from the given patterns,
+/// or from all the patterns it is subscribed to if none are given.
+/// The command is in the format `PUNSUBSCRIBE [pattern [pattern ...]]`.
+/// The command replies with a `punsubscribe` message for every pattern,
+/// along with the number of channels and patterns the client is still subscribed to.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Get the patterns, defaulting to all the subscribed patterns
+    let patterns: Vec<String> = if args.is_empty() {
+        let mut patterns: Vec<String> = connection.pattern_subscriptions.iter().cloned().collect();
+        patterns.sort();
+        patterns
+    } else {
+        match args.iter().map(Type::as_str).collect::<Option<Vec<_>>>() {
+            Some(patterns) => patterns.into_iter().map(String::from).collect(),
+            None => {
+                return connection
+                    .write_error("ERR invalid pattern for 'punsubscribe' command")
+                    .await;
+            }
+        }
+    };
+
+    // Without any patterns to unsubscribe from, confirm that the client has no pattern subscriptions
+    if patterns.is_empty() {
+        let count = connection.subscription_count() as i64;
+        return connection
+            .write_push(vec![
+                resp::bulk_string("punsubscribe"),
+                Type::Null,
+                Type::Integer(count),
+            ])
+            .await;
+    }
+
+    for pattern in patterns {
+        // Remove the connection from the subscribers of the pattern
+        server.lock().await.punsubscribe(&pattern, connection.id);
+        connection.pattern_subscriptions.remove(&pattern);
+
+        // Confirm the unsubscription
+        let count = connection.subscription_count() as i64;
+        connection
+            .write_push(vec![
+                resp::bulk_string("punsubscribe"),
+                resp::bulk_string(&pattern),
+                Type::Integer(count),
+            ])
+            .await?;
+    }
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_unsubscribe_from_all_patterns() {
+        let mut client = test_helpers::client().await;
+        client.send(&["PSUBSCRIBE", "a*", "b*"]).await;
+        assert_eq!(
+            client.send(&["PUNSUBSCRIBE"]).await,
+            b"*3\r\n$12\r\npunsubscribe\r\n$2\r\na*\r\n:1\r\n*3\r\n$12\r\npunsubscribe\r\n$2\r\nb*\r\n:0\r\n"
+        );
+        assert!(client.server.lock().await.patterns.is_empty());
+    }
+}
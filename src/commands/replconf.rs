@@ -1,5 +1,6 @@
 // Library
 use crate::{
+    errors::Error,
     parser::resp::Type,
     server::{connection::Connection, Server},
 };
@@ -25,8 +26,8 @@ pub async fn command(
     }
 
     // Extract Sub-Command
-    let subcommand = match args.get(0) {
-        Some(Type::BulkString(subcommand)) => subcommand,
+    let subcommand = match args.first().and_then(Type::as_str) {
+        Some(subcommand) => subcommand,
         x => {
             return connection
                 .write_error(format!("ERR invalid subcommand {:?}", x))
@@ -36,13 +37,13 @@ pub async fn command(
 
     // Handle the REPLCONF subcommands
     match subcommand.to_uppercase().as_str() {
-        "LISTENING-PORT" => connection.write_ok().await?,
+        "LISTENING-PORT" => listening_port(args, connection).await?,
 
         "CAPA" => connection.write_ok().await?,
 
         "GETACK" => get_ack(server, connection).await?,
 
-        "ACK" => ack(args, wait_channel, connection).await?,
+        "ACK" => ack(args, server, wait_channel, connection).await?,
 
         _ => connection.write_ok().await?,
     }
@@ -54,6 +55,29 @@ pub async fn command(
 // SUB-COMMANDS
 // ------------
 
+// LISTENING PORT
+// --------------
+
+/// Handles the REPLCONF LISTENING-PORT subcommand.
+/// A replica sends a `REPLCONF listening-port <port>` during the handshake to advertise the port it listens on.
+/// The port is recorded on the connection, and is reported for the replica once it is registered by PSYNC.
+async fn listening_port(
+    args: &[Type],
+    connection: &mut Connection,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match args
+        .get(1)
+        .and_then(Type::as_str)
+        .and_then(|port| port.parse::<u16>().ok())
+    {
+        Some(port) => {
+            connection.listening_port = Some(port);
+            connection.write_ok().await
+        }
+        None => connection.write_error(Error::NotInteger.to_string()).await,
+    }
+}
+
 // GET ACK
 // -------
 
@@ -89,21 +113,14 @@ pub async fn get_ack(
     let response = Type::Array(vec![
         Type::BulkString("REPLCONF".into()),
         Type::BulkString("ACK".into()),
-        Type::BulkString(offset.to_string()),
+        Type::BulkString(offset.to_string().into()),
     ]);
     let bytes = response.as_bytes();
     connection.write_all(&bytes).await?;
 
     println!("[{}] REPLCONF ACK: Sent ACK", addr);
 
-    // Update the replication offset of the replica
-    {
-        let mut server = server.lock().await;
-        // TODO: Fix this. Hardcoded value for testing purposes (37 bytes for REPLCONF GETACK *)
-        server.repl_offset += 37;
-    }
-
-    return Ok(());
+    Ok(())
 }
 
 // ACK
@@ -116,12 +133,13 @@ pub async fn get_ack(
 /// The master waits for the ACK response from the replica before sending more commands.
 async fn ack(
     args: &[Type],
+    server: &Arc<Mutex<Server>>,
     wait_channel: &Arc<Mutex<(mpsc::Sender<u64>, mpsc::Receiver<u64>)>>,
     connection: &mut Connection,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Extract the offset from the arguments
-    let offset = match args.get(1) {
-        Some(Type::BulkString(offset)) => offset,
+    let offset = match args.get(1).and_then(Type::as_str) {
+        Some(offset) => offset,
         _ => {
             return connection.write_error("ERR invalid offset").await;
         }
@@ -136,6 +154,19 @@ async fn ack(
         }
     };
 
+    // Record the offset acknowledged by the replica
+    connection.ack_offset = offset;
+    {
+        let mut server = server.lock().await;
+        if let Some(replica) = server
+            .replicas
+            .iter_mut()
+            .find(|replica| replica.addr == connection.addr)
+        {
+            replica.offset = offset;
+        }
+    }
+
     // Send the offset to the master
     let wc = wait_channel.lock().await;
     println!("REPLCONF ACK: Received ACK with offset {}", offset);
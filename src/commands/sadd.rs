@@ -0,0 +1,103 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::{collections::HashSet, sync::Arc};
+use tokio::sync::Mutex;
+
+// ----
+// SADD
+// ----
+
+/// Handles the SADD command.
+/// The SADD command adds the specified members to the set stored at the key.
+/// The command is in the format `SADD key member [member ...]`.
+/// Members that are already part of the set are ignored. If the key does not exist, a new set is created.
+/// The command returns the number of members that were actually added to the set.
+/// Returns true if any member was added, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() < 2 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'SADD' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Get the set stored at the key, creating it if it does not exist
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    if shard.get(connection.db, &args[0]).is_none() {
+        shard.set(
+            connection.db,
+            args[0].clone(),
+            Type::Set(HashSet::new()),
+            None,
+        );
+    }
+    let set = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::Set(set)) => set,
+        _ => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Add the members, counting the ones that were not already present
+    let added = args[1..]
+        .iter()
+        .filter(|member| set.insert((*member).clone()))
+        .count();
+
+    // Respond with the number of added members
+    if role.is_master() {
+        let response = Type::Integer(added as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(added > 0)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_ignore_duplicate_members() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SADD", "k", "a", "a", "b"]).await, b":2\r\n");
+        assert_eq!(client.send(&["SCARD", "k"]).await, b":2\r\n");
+        assert_eq!(client.send(&["SADD", "k", "b", "c"]).await, b":1\r\n");
+        assert_eq!(client.send(&["SCARD", "k"]).await, b":3\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_set_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["SADD", "k", "a"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
@@ -0,0 +1,206 @@
+// Library
+use crate::{
+    helpers,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// SCAN
+// ----
+
+/// The number of keys examined by each call when no COUNT is given
+const DEFAULT_COUNT: usize = 10;
+
+/// Handles the SCAN command.
+/// The SCAN command incrementally iterates over the keys of the database.
+/// The command is in the format `SCAN cursor [MATCH pattern] [COUNT count]`.
+/// The iteration starts with the cursor `0`, and each call returns the cursor for the next call
+/// along with a batch of keys. The iteration is complete once the returned cursor is `0`.
+/// The keys are iterated in sorted order, with the cursor as the index of the next key.
+/// `COUNT` is the number of keys examined by each call, and only the examined keys matching
+/// the `MATCH` pattern are returned, so a call may return fewer keys (or none).
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.is_empty() {
+        return connection
+            .write_error("ERR wrong number of arguments for 'SCAN' command")
+            .await;
+    }
+
+    // Parse the cursor and the options
+    let options = match ScanOptions::parse(args) {
+        Ok(options) => options,
+        Err(e) => return connection.write_error(e).await,
+    };
+
+    // Snapshot the keys in sorted order, so that the cursor is a stable position across calls
+    let db = server.lock().await.db.clone();
+    let mut keys: Vec<Vec<u8>> = db
+        .keys(connection.db)
+        .await
+        .into_iter()
+        .filter_map(|key| match key {
+            Type::BulkString(key) => Some(key),
+            _ => None,
+        })
+        .collect();
+    keys.sort();
+
+    // Examine the next batch of keys, and respond with the ones matching the pattern
+    let (next_cursor, batch) = options.page(&keys, |key| String::from_utf8_lossy(key).into());
+    let batch = batch
+        .into_iter()
+        .map(|key| Type::BulkString(key.clone()))
+        .collect();
+    let response = reply(next_cursor, batch);
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+/// The cursor and options shared by the SCAN family of commands
+pub struct ScanOptions {
+    /// The index of the next item to examine
+    cursor: usize,
+    /// The glob-style pattern the returned items must match
+    pattern: Option<String>,
+    /// The number of items examined by each call
+    count: usize,
+}
+
+impl ScanOptions {
+    /// Parses arguments in the format `cursor [MATCH pattern] [COUNT count]`.
+    /// Returns the error message to respond with if the arguments are invalid.
+    pub fn parse(args: &[Type]) -> Result<Self, &'static str> {
+        // Extract the cursor from the arguments
+        let cursor = args
+            .first()
+            .and_then(Type::as_str)
+            .and_then(|cursor| cursor.parse::<usize>().ok())
+            .ok_or("ERR invalid cursor")?;
+
+        // Parse the options
+        let mut options = ScanOptions {
+            cursor,
+            pattern: None,
+            count: DEFAULT_COUNT,
+        };
+        for option in args[1..].chunks(2) {
+            let name = option[0].as_str().map(str::to_uppercase);
+            match (name.as_deref(), option.get(1).and_then(Type::as_str)) {
+                (Some("MATCH"), Some(value)) => options.pattern = Some(value.to_string()),
+                (Some("COUNT"), Some(value)) => match value.parse::<usize>() {
+                    Ok(value) if value > 0 => options.count = value,
+                    _ => return Err("ERR syntax error"),
+                },
+                _ => return Err("ERR syntax error"),
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Examines the next batch of the given items, which must be in the same order on every call.
+    /// Returns the cursor for the next call (`0` once all items are examined)
+    /// and the examined items whose name matches the pattern.
+    pub fn page<'a, T>(&self, items: &'a [T], name: impl Fn(&T) -> String) -> (usize, Vec<&'a T>) {
+        let end = self.cursor.saturating_add(self.count).min(items.len());
+        let batch = items
+            .get(self.cursor..end)
+            .unwrap_or_default()
+            .iter()
+            .filter(|item| match &self.pattern {
+                Some(pattern) => helpers::glob_match(pattern, &name(item)),
+                None => true,
+            })
+            .collect();
+        let next_cursor = if end < items.len() { end } else { 0 };
+        (next_cursor, batch)
+    }
+}
+
+/// Builds the `[next_cursor, [items...]]` reply of the SCAN family of commands
+pub fn reply(next_cursor: usize, items: Vec<Type>) -> Type {
+    resp::array(vec![
+        Type::BulkString(next_cursor.to_string().into()),
+        resp::array(items),
+    ])
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::collections::HashSet;
+
+    /// Sends a SCAN command and returns the next cursor and the returned keys
+    async fn scan(client: &mut test_helpers::Client, args: &[&str]) -> (String, Vec<String>) {
+        let mut cmd = vec!["SCAN"];
+        cmd.extend_from_slice(args);
+        client.send_scan(&cmd).await
+    }
+
+    #[tokio::test]
+    async fn should_cover_all_keys_until_cursor_is_zero() {
+        let mut client = test_helpers::client().await;
+        for i in 0..25 {
+            client.send(&["SET", &format!("key:{}", i), "v"]).await;
+        }
+
+        let mut seen = HashSet::new();
+        let mut cursor = "0".to_string();
+        let mut calls = 0;
+        loop {
+            let (next, keys) = scan(&mut client, &[&cursor, "COUNT", "7"]).await;
+            assert!(keys.len() <= 7);
+            seen.extend(keys);
+            calls += 1;
+            cursor = next;
+            if cursor == "0" {
+                break;
+            }
+        }
+        assert_eq!(calls, 4);
+        assert_eq!(seen.len(), 25);
+    }
+
+    #[tokio::test]
+    async fn should_only_return_keys_matching_pattern() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "user:1", "a"]).await;
+        client.send(&["SET", "user:2", "b"]).await;
+        client.send(&["SET", "session:1", "c"]).await;
+
+        let (cursor, mut keys) = scan(&mut client, &["0", "MATCH", "user:*"]).await;
+        keys.sort();
+        assert_eq!(cursor, "0");
+        assert_eq!(keys, vec!["user:1", "user:2"]);
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_arguments() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["SCAN", "abc"]).await,
+            b"-ERR invalid cursor\r\n"
+        );
+        assert_eq!(
+            client.send(&["SCAN", "0", "COUNT", "0"]).await,
+            b"-ERR syntax error\r\n"
+        );
+        assert_eq!(
+            client.send(&["SCAN", "0", "MATCH"]).await,
+            b"-ERR syntax error\r\n"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// Library
+use crate::{
+    database::DATABASES,
+    errors::Error,
+    parser::resp::Type,
+    server::connection::{Connection, Kind},
+};
+
+// ------
+// SELECT
+// ------
+
+/// Handles the SELECT command.
+/// The SELECT command changes the logical database used by the connection.
+/// The command is in the format `SELECT index`, where the index is between 0 and 15.
+/// Every new connection starts on database 0.
+/// The command returns OK, or an error if the index is not a valid database number.
+/// No reply is sent over the replication connection.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // The master propagates SELECT ahead of the writes it applies to, but does not expect a reply.
+    // Clients of a replica still get one, as SELECT does not write.
+    let reply = connection.kind != Kind::Replication;
+
+    // Check the number of arguments
+    if args.len() != 1 {
+        if reply {
+            connection
+                .write_error("ERR wrong number of arguments for 'SELECT' command")
+                .await?;
+        }
+        return Ok(());
+    }
+
+    // Parse the database index
+    let index = match args[0].as_str() {
+        Some(index) => match index.parse::<i64>() {
+            Ok(index) => index,
+            Err(_) => {
+                if reply {
+                    connection
+                        .write_error(Error::NotInteger.to_string())
+                        .await?;
+                }
+                return Ok(());
+            }
+        },
+        None => {
+            if reply {
+                connection.write_error("ERR invalid database index").await?;
+            }
+            return Ok(());
+        }
+    };
+
+    // Check that the database exists
+    if index < 0 || index as usize >= DATABASES {
+        if reply {
+            connection
+                .write_error("ERR DB index is out of range")
+                .await?;
+        }
+        return Ok(());
+    }
+
+    // Switch the connection to the selected database
+    connection.db = index as usize;
+    if reply {
+        connection.write_ok().await?;
+    }
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::test_helpers,
+        server::{connection::Kind, replication::Role},
+    };
+
+    #[tokio::test]
+    async fn should_isolate_keys_between_databases() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(client.send(&["SELECT", "1"]).await, b"+OK\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$-1\r\n");
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":0\r\n");
+        client.send(&["SET", "k", "other"]).await;
+        assert_eq!(client.send(&["SELECT", "0"]).await, b"+OK\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$1\r\nv\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_out_of_range_index() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["SELECT", "16"]).await,
+            b"-ERR DB index is out of range\r\n"
+        );
+        assert_eq!(
+            client.send(&["SELECT", "-1"]).await,
+            b"-ERR DB index is out of range\r\n"
+        );
+        assert_eq!(
+            client.send(&["SELECT", "one"]).await,
+            b"-ERR value is not an integer or out of range\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reply_to_clients_of_replica_but_not_to_master() {
+        let mut client = test_helpers::client().await;
+        client.server.lock().await.role = Role::Replica("127.0.0.1:6379".into());
+        assert_eq!(client.send(&["SELECT", "1"]).await, b"+OK\r\n");
+        assert_eq!(
+            client.send(&["SELECT", "99"]).await,
+            b"-ERR DB index is out of range\r\n"
+        );
+
+        let mut master = test_helpers::client_for(&client.server).await;
+        master.conn.kind = Kind::Replication;
+        assert_eq!(master.send(&["SELECT", "2"]).await, b"");
+        assert_eq!(master.conn.db, 2);
+    }
+}
@@ -1,6 +1,7 @@
 // Library
 use super::resp::Type;
 use crate::{
+    database::WrongType,
     parser::resp,
     server::{connection::Connection, Server},
 };
@@ -15,15 +16,15 @@ use tokio::sync::Mutex;
 /// The SET command sets the value of a key in the database.
 /// If the key already exists, the value is overwritten.
 /// The command returns OK if the value was set successfully.
+/// The command returns null if the value was not set because the `NX` or `XX` condition was not met.
+/// If the `GET` option is given, the command instead returns the old value (or null if there was none).
 /// The command returns an error if the number of arguments is invalid.
+/// Returns true if the value was written, in which case the command should be propagated to replicas.
 pub async fn command(
-    args: &Vec<resp::Type>,
+    args: &[resp::Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Determine the length of the command bytes
-    let cmd_bytes_len = resp::array(args.clone()).as_bytes().len() as u64;
-
+) -> Result<bool, Box<dyn std::error::Error>> {
     // Get the role of the server
     let role = {
         let server = server.lock().await;
@@ -37,7 +38,7 @@ pub async fn command(
                 .write_error("ERR wrong number of arguments for 'SET' command")
                 .await?;
         }
-        return Ok(());
+        return Ok(false);
     }
 
     // Extract the key and value from the arguments
@@ -47,7 +48,7 @@ pub async fn command(
             if role.is_master() {
                 connection.write_error("ERR invalid key").await?;
             }
-            return Ok(());
+            return Ok(false);
         }
     };
     let value = match args.get(2) {
@@ -56,42 +57,345 @@ pub async fn command(
             if role.is_master() {
                 connection.write_error("ERR invalid value").await?;
             }
-            return Ok(());
+            return Ok(false);
         }
     };
-    // Extract the expiration time from the arguments if it exists, and parse it as a u64, otherwise set it to None
-    let expiry = match args.get(4) {
-        Some(Type::BulkString(expiry)) => match expiry.parse::<usize>() {
-            Ok(expiry) => Some(expiry),
-            Err(x) => {
-                if role.is_master() {
-                    connection
-                        .write_error(format!("ERR invalid expiry {:?}, got {:?}", expiry, x))
-                        .await?;
-                }
-                return Ok(());
+    // Parse the options following the key and value
+    let options = match Options::parse(&args[3..]) {
+        Ok(options) => options,
+        Err(err) => {
+            if role.is_master() {
+                connection.write_error(err).await?;
             }
-        },
-        _ => None,
+            return Ok(false);
+        }
     };
 
-    // Set the value in the database
-    let mut s = server.lock().await;
-    s.db.set(key.clone(), value.clone(), expiry);
+    // Capture the old value, which is returned when the GET option is given
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(key).await;
+    let old = shard.get(connection.db, key).cloned();
+    if options.get && !matches!(old, None | Some(Type::BulkString(_))) {
+        if role.is_master() {
+            connection.write_error(WrongType.to_string()).await?;
+        }
+        return Ok(false);
+    }
+
+    // Set the value in the database, unless the NX/XX condition is not met
+    let written = match options.condition {
+        Some(Condition::NX) => old.is_none(),
+        Some(Condition::XX) => old.is_some(),
+        None => true,
+    };
+    if written {
+        shard.set(connection.db, key.clone(), value.clone(), options.expiry);
+    }
+    drop(shard);
 
     if role.is_master() {
-        // If the server is a master, increment the master replication offset
-        println!(
-            "SET(master) {} + {}",
-            s.master_repl_offset, cmd_bytes_len as u64
+        if options.get {
+            let response = old.unwrap_or(Type::Null);
+            connection.write_all(&response.as_bytes()).await?;
+        } else if written {
+            connection.write_ok().await?;
+        } else {
+            let response = Type::Null;
+            connection.write_all(&response.as_bytes()).await?;
+        }
+    }
+
+    Ok(written)
+}
+
+// -------
+// OPTIONS
+// -------
+
+/// The options that can be passed to the SET command after the key and value.
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    /// The number of milliseconds after which the key expires. `None` means the key never expires.
+    expiry: Option<usize>,
+    /// The condition under which the value is set. `None` means the value is always set.
+    condition: Option<Condition>,
+    /// Whether to return the old value stored at the key
+    get: bool,
+}
+
+/// The conditions under which the SET command writes the value.
+#[derive(Debug, PartialEq)]
+enum Condition {
+    /// Only set the key if it does not already exist
+    NX,
+    /// Only set the key if it already exists
+    XX,
+}
+
+impl Options {
+    /// Parses the SET options from the arguments following the key and value.
+    /// Recognizes `EX seconds`, `PX milliseconds`, `NX`, `XX` and `GET`. Any other option is a syntax error.
+    fn parse(args: &[Type]) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let option = match arg.as_str() {
+                Some(option) => option.to_uppercase(),
+                None => return Err("ERR syntax error".into()),
+            };
+
+            // Determine the number of milliseconds per unit of the expiry
+            let unit = match option.as_str() {
+                "EX" => 1000,
+                "PX" => 1,
+                "NX" | "XX" => {
+                    // Only one condition may be specified
+                    if options.condition.is_some() {
+                        return Err("ERR syntax error".into());
+                    }
+                    options.condition = Some(match option.as_str() {
+                        "NX" => Condition::NX,
+                        _ => Condition::XX,
+                    });
+                    continue;
+                }
+                "GET" => {
+                    options.get = true;
+                    continue;
+                }
+                _ => return Err("ERR syntax error".into()),
+            };
+
+            // Only one expiry option may be specified
+            if options.expiry.is_some() {
+                return Err("ERR syntax error".into());
+            }
+
+            // Parse the expiry and convert it to milliseconds
+            let expiry = match args.next().and_then(Type::as_str) {
+                Some(expiry) => expiry,
+                None => return Err("ERR syntax error".into()),
+            };
+            let expiry = expiry
+                .parse::<usize>()
+                .ok()
+                .filter(|expiry| *expiry > 0)
+                .and_then(|expiry| expiry.checked_mul(unit))
+                .ok_or("ERR invalid expire time in 'set' command")?;
+            options.expiry = Some(expiry);
+        }
+
+        Ok(options)
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::test_helpers,
+        server::{connection::Kind, replication::Role},
+    };
+    use std::time::Duration;
+
+    fn args(args: &[&str]) -> Vec<Type> {
+        args.iter()
+            .map(|arg| Type::BulkString(arg.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_expiry_options() {
+        assert_eq!(Options::parse(&args(&[])), Ok(Options::default()));
+        assert_eq!(
+            Options::parse(&args(&["EX", "2"])),
+            Ok(Options {
+                expiry: Some(2000),
+                condition: None,
+                get: false
+            })
+        );
+        assert_eq!(
+            Options::parse(&args(&["px", "150"])),
+            Ok(Options {
+                expiry: Some(150),
+                condition: None,
+                get: false
+            })
         );
-        connection.write_ok().await?;
-        s.master_repl_offset += cmd_bytes_len;
-    } else {
-        // If the server is a replica, increment the replica replication offset
-        println!("SET(replica) {} + {}", s.repl_offset, cmd_bytes_len as u64);
-        s.repl_offset += cmd_bytes_len;
     }
 
-    Ok(())
+    #[test]
+    fn should_reject_unknown_options() {
+        assert_eq!(
+            Options::parse(&args(&["EXX", "1"])),
+            Err("ERR syntax error".into())
+        );
+        assert_eq!(
+            Options::parse(&args(&["PX"])),
+            Err("ERR syntax error".into())
+        );
+        assert_eq!(
+            Options::parse(&args(&["EX", "1", "PX", "1"])),
+            Err("ERR syntax error".into())
+        );
+        assert_eq!(
+            Options::parse(&args(&["NX", "XX"])),
+            Err("ERR syntax error".into())
+        );
+    }
+
+    #[test]
+    fn should_parse_conditions() {
+        assert_eq!(
+            Options::parse(&args(&["nx", "PX", "10"])),
+            Ok(Options {
+                expiry: Some(10),
+                condition: Some(Condition::NX),
+                get: false
+            })
+        );
+        assert_eq!(
+            Options::parse(&args(&["XX", "get"])),
+            Ok(Options {
+                expiry: None,
+                condition: Some(Condition::XX),
+                get: true
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn should_not_expire_without_expiry_option() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SET", "k", "v"]).await, b"+OK\r\n");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(client.send(&["GET", "k"]).await, b"$1\r\nv\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_expire_after_seconds() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SET", "k", "v", "EX", "1"]).await, b"+OK\r\n");
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(client.send(&["GET", "k"]).await, b"$1\r\nv\r\n");
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert_eq!(client.send(&["GET", "k"]).await, b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reply_syntax_error_for_unknown_option() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["SET", "k", "v", "XY"]).await,
+            b"-ERR syntax error\r\n"
+        );
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_only_set_missing_key_with_nx() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SET", "k", "v1", "NX"]).await, b"+OK\r\n");
+        assert_eq!(client.send(&["SET", "k", "v2", "NX"]).await, b"$-1\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$2\r\nv1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_only_set_existing_key_with_xx() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SET", "k", "v1", "XX"]).await, b"$-1\r\n");
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":0\r\n");
+        client.send(&["SET", "k", "v1"]).await;
+        assert_eq!(client.send(&["SET", "k", "v2", "XX"]).await, b"+OK\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$2\r\nv2\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_treat_expired_key_as_missing_with_nx() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v1", "PX", "1"]).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(
+            client.send(&["SET", "k", "v2", "NX", "EX", "10"]).await,
+            b"+OK\r\n"
+        );
+        assert_eq!(client.send(&["GET", "k"]).await, b"$2\r\nv2\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_return_old_value_with_get() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SET", "k", "v1", "GET"]).await, b"$-1\r\n");
+        assert_eq!(
+            client.send(&["SET", "k", "v2", "GET", "PX", "100"]).await,
+            b"$2\r\nv1\r\n"
+        );
+        assert_eq!(client.send(&["GET", "k"]).await, b"$2\r\nv2\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_compose_get_with_conditions() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v1"]).await;
+        assert_eq!(
+            client.send(&["SET", "k", "v2", "NX", "GET"]).await,
+            b"$2\r\nv1\r\n"
+        );
+        assert_eq!(client.send(&["GET", "k"]).await, b"$2\r\nv1\r\n");
+        assert_eq!(
+            client.send(&["SET", "k", "v2", "XX", "GET"]).await,
+            b"$2\r\nv1\r\n"
+        );
+        assert_eq!(client.send(&["GET", "k"]).await, b"$2\r\nv2\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_get_on_non_string_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        assert_eq!(
+            client.send(&["SET", "s", "v", "GET"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+        assert_eq!(client.send(&["TYPE", "s"]).await, b"+stream\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_client_writes_on_replica() {
+        let mut client = test_helpers::client().await;
+        client.server.lock().await.role = Role::Replica("127.0.0.1:6379".into());
+        assert_eq!(
+            client.send(&["SET", "k", "v"]).await,
+            b"-READONLY You can't write against a read only replica\r\n"
+        );
+        assert_eq!(client.send(&["GET", "k"]).await, b"$-1\r\n");
+
+        // The writes propagated by the master over the replication connection still apply
+        let mut master = test_helpers::client_for(&client.server).await;
+        master.conn.kind = Kind::Replication;
+        assert_eq!(master.send(&["SET", "k", "v"]).await, b"");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$1\r\nv\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_advance_master_offset_once_per_propagated_command() {
+        let mut client = test_helpers::client().await;
+        // Offsets are only counted while there are replicas to propagate to
+        let mut replica = client.server.lock().await.sender.subscribe();
+
+        client.send(&["SET", "key", "value"]).await;
+        let propagated = replica.recv().await.unwrap().as_bytes().len() as u64;
+        assert_eq!(propagated, 33);
+        assert_eq!(client.server.lock().await.master_repl_offset, propagated);
+
+        // Commands that do not write are not propagated
+        client.send(&["SET", "key", "other", "NX"]).await;
+        assert_eq!(client.server.lock().await.master_repl_offset, propagated);
+    }
 }
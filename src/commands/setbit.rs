@@ -0,0 +1,155 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// SETBIT
+// ------
+
+/// The largest bit offset accepted, which limits strings to 512MB like Redis does
+const MAX_BIT_OFFSET: u64 = 512 * 1024 * 1024 * 8 - 1;
+
+/// Handles the SETBIT command.
+/// The SETBIT command sets or clears the bit at the offset in the string stored at the key.
+/// The command is in the format `SETBIT key offset value`, where the value is `0` or `1`.
+/// Bit `0` is the most significant bit of the first byte. The string is grown with zero bytes
+/// as needed to hold the bit, and a missing key is created as an empty string.
+/// The command returns the previous value of the bit.
+/// Returns true if the bit was written, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 3 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'SETBIT' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the offset and the bit from the arguments
+    let Some(offset) = parse_offset(&args[1]) else {
+        if role.is_master() {
+            connection
+                .write_error("ERR bit offset is not an integer or out of range")
+                .await?;
+        }
+        return Ok(false);
+    };
+    let bit = match args[2].as_str() {
+        Some("0") => false,
+        Some("1") => true,
+        _ => {
+            if role.is_master() {
+                connection
+                    .write_error("ERR bit is not an integer or out of range")
+                    .await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Get the string stored at the key, treating a missing key as empty
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let mut value = match shard.expect_string(connection.db, &args[0]) {
+        Ok(value) => value.unwrap_or_default().to_vec(),
+        Err(e) => {
+            if role.is_master() {
+                connection.write_error(e.to_string()).await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Grow the string to hold the bit, and update the bit
+    let (byte, mask) = (offset / 8, 0x80 >> (offset % 8));
+    if value.len() <= byte {
+        value.resize(byte + 1, 0);
+    }
+    let previous = value[byte] & mask != 0;
+    if bit {
+        value[byte] |= mask;
+    } else {
+        value[byte] &= !mask;
+    }
+    shard.update(connection.db, args[0].clone(), Type::BulkString(value));
+
+    // Respond with the previous value of the bit
+    if role.is_master() {
+        let response = Type::Integer(previous as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(true)
+}
+
+/// Parses a bit offset, which must be a non-negative integer no larger than `MAX_BIT_OFFSET`
+pub fn parse_offset(value: &Type) -> Option<usize> {
+    value
+        .as_str()
+        .and_then(|offset| offset.parse::<u64>().ok())
+        .filter(|offset| *offset <= MAX_BIT_OFFSET)
+        .map(|offset| offset as usize)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_set_bit_on_fresh_key() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SETBIT", "k", "7", "1"]).await, b":0\r\n");
+        assert_eq!(client.send(&["BITCOUNT", "k"]).await, b":1\r\n");
+        assert_eq!(client.send(&["STRLEN", "k"]).await, b":1\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$1\r\n\x01\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_grow_string_and_return_previous_bit() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "a"]).await;
+        assert_eq!(client.send(&["SETBIT", "k", "23", "1"]).await, b":0\r\n");
+        assert_eq!(client.send(&["STRLEN", "k"]).await, b":3\r\n");
+        assert_eq!(client.send(&["SETBIT", "k", "23", "0"]).await, b":1\r\n");
+        // `a` is 0b01100001, so bit 1 is already set
+        assert_eq!(client.send(&["SETBIT", "k", "1", "1"]).await, b":1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_arguments() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["SETBIT", "k", "-1", "1"]).await,
+            b"-ERR bit offset is not an integer or out of range\r\n"
+        );
+        assert_eq!(
+            client.send(&["SETBIT", "k", "0", "2"]).await,
+            b"-ERR bit is not an integer or out of range\r\n"
+        );
+        client.send(&["LPUSH", "l", "a"]).await;
+        assert_eq!(
+            client.send(&["SETBIT", "l", "0", "1"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
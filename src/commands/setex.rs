@@ -0,0 +1,114 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------------
+// SETEX/PSETEX
+// ------------
+
+/// Handles the SETEX and PSETEX commands.
+/// The commands set the value of a key along with an expiry, in a single operation.
+/// The commands are in the format `SETEX key seconds value` and `PSETEX key milliseconds value`.
+/// The `unit` is the number of milliseconds per unit of the expiry: 1000 for SETEX and 1 for PSETEX.
+/// The command returns OK if the value was set, and an error if the expiry is not a positive integer.
+/// Returns true if the value was written, in which case the command should be propagated to replicas.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+    unit: usize,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 3 {
+        if role.is_master() {
+            connection
+                .write_error(format!(
+                    "ERR wrong number of arguments for '{}' command",
+                    name
+                ))
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the expiry from the arguments and convert it to milliseconds
+    let expiry = args[1]
+        .as_str()
+        .and_then(|expiry| expiry.parse::<usize>().ok())
+        .filter(|expiry| *expiry > 0)
+        .and_then(|expiry| expiry.checked_mul(unit));
+    let expiry = match expiry {
+        Some(expiry) => expiry,
+        None => {
+            if role.is_master() {
+                connection
+                    .write_error(format!(
+                        "ERR invalid expire time in '{}' command",
+                        name.to_lowercase()
+                    ))
+                    .await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Set the value in the database
+    let db = server.lock().await.db.clone();
+    db.set(
+        connection.db,
+        args[0].clone(),
+        args[2].clone(),
+        Some(expiry),
+    )
+    .await;
+
+    if role.is_master() {
+        connection.write_ok().await?;
+    }
+
+    Ok(true)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_set_value_with_expiry() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["PSETEX", "k", "20", "v"]).await, b"+OK\r\n");
+        assert_eq!(client.send(&["GET", "k"]).await, b"$1\r\nv\r\n");
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(client.send(&["GET", "k"]).await, b"$-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_positive_expiry() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["SETEX", "k", "0", "v"]).await,
+            b"-ERR invalid expire time in 'setex' command\r\n"
+        );
+        assert_eq!(
+            client.send(&["PSETEX", "k", "-5", "v"]).await,
+            b"-ERR invalid expire time in 'psetex' command\r\n"
+        );
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":0\r\n");
+    }
+}
@@ -0,0 +1,172 @@
+// Library
+use crate::{
+    errors::Error,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// --------
+// SETRANGE
+// --------
+
+/// The largest string SETRANGE may produce, which is 512MB like in Redis
+const MAX_STRING_LENGTH: usize = 512 * 1024 * 1024;
+
+/// Handles the SETRANGE command.
+/// The SETRANGE command overwrites part of the string stored at the key, starting at the byte offset.
+/// The command is in the format `SETRANGE key offset value`.
+/// The string is extended as needed, with any gap before the offset padded with zero bytes,
+/// and a missing key is treated as an empty string. Any existing expiry on the key is preserved.
+/// The command returns the length of the string after it was modified.
+/// Returns true if the string was written, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() != 3 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'SETRANGE' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Extract the offset and the value from the arguments
+    let Some(offset) = args[1]
+        .as_str()
+        .and_then(|offset| offset.parse::<i64>().ok())
+    else {
+        if role.is_master() {
+            connection
+                .write_error(Error::NotInteger.to_string())
+                .await?;
+        }
+        return Ok(false);
+    };
+    let Ok(offset) = usize::try_from(offset) else {
+        if role.is_master() {
+            connection.write_error("ERR offset is out of range").await?;
+        }
+        return Ok(false);
+    };
+    let Type::BulkString(patch) = &args[2] else {
+        if role.is_master() {
+            connection.write_error("ERR invalid value").await?;
+        }
+        return Ok(false);
+    };
+    if offset.saturating_add(patch.len()) > MAX_STRING_LENGTH {
+        if role.is_master() {
+            connection
+                .write_error("ERR string exceeds maximum allowed size (proto-max-bulk-len)")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Get the string stored at the key
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let (mut value, exists) = match shard.expect_string(connection.db, &args[0]) {
+        Ok(Some(value)) => (value.to_vec(), true),
+        Ok(None) => (Vec::new(), false),
+        Err(e) => {
+            if role.is_master() {
+                connection.write_error(e.to_string()).await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Overwrite the range, padding the string with zero bytes up to the offset.
+    // An empty value leaves the string (or the missing key) untouched.
+    if !patch.is_empty() {
+        let end = offset + patch.len();
+        if value.len() < end {
+            value.resize(end, 0);
+        }
+        value[offset..end].copy_from_slice(patch);
+        if exists {
+            shard.update(
+                connection.db,
+                args[0].clone(),
+                Type::BulkString(value.clone()),
+            );
+        } else {
+            shard.set(
+                connection.db,
+                args[0].clone(),
+                Type::BulkString(value.clone()),
+                None,
+            );
+        }
+    }
+
+    // Respond with the new length of the string
+    if role.is_master() {
+        let response = Type::Integer(value.len() as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(!patch.is_empty())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_pad_missing_key_with_zero_bytes() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SETRANGE", "k", "3", "abc"]).await, b":6\r\n");
+        assert_eq!(
+            client.send(&["GET", "k"]).await,
+            b"$6\r\n\x00\x00\x00abc\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_overwrite_and_extend_existing_string() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "Hello World"]).await;
+        assert_eq!(
+            client.send(&["SETRANGE", "k", "6", "Redis"]).await,
+            b":11\r\n"
+        );
+        assert_eq!(client.send(&["GET", "k"]).await, b"$11\r\nHello Redis\r\n");
+        assert_eq!(
+            client.send(&["SETRANGE", "k", "6", "Redis!!"]).await,
+            b":13\r\n"
+        );
+        assert_eq!(
+            client.send(&["GET", "k"]).await,
+            b"$13\r\nHello Redis!!\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_not_create_key_for_empty_value() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SETRANGE", "k", "5", ""]).await, b":0\r\n");
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":0\r\n");
+        assert_eq!(
+            client.send(&["SETRANGE", "k", "-1", "a"]).await,
+            b"-ERR offset is out of range\r\n"
+        );
+    }
+}
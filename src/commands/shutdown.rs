@@ -0,0 +1,81 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// --------
+// SHUTDOWN
+// --------
+
+/// Handles the SHUTDOWN command.
+/// The SHUTDOWN command saves the dataset to the RDB file and shuts the server down.
+/// The command is in the format `SHUTDOWN [NOSAVE | SAVE]`.
+/// With `NOSAVE` the dataset is not saved, and with `SAVE` it is saved even if no RDB file is configured.
+/// Without either, the dataset is saved only if an RDB file is configured.
+/// The server stops accepting connections and closes this one, so no reply is sent on success.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let s = server.lock().await;
+
+    // Determine whether to save the dataset before shutting down
+    let save = match args {
+        [] => !s.db.dbfilename.is_empty(),
+        [modifier] => match modifier.as_str().map(str::to_uppercase).as_deref() {
+            Some("NOSAVE") => false,
+            Some("SAVE") => true,
+            _ => return connection.write_error("ERR syntax error").await,
+        },
+        _ => return connection.write_error("ERR syntax error").await,
+    };
+
+    println!("[{}] SHUTDOWN requested (save: {})", s.addr, save);
+    s.request_shutdown(save);
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{commands::test_helpers, helpers, server::shutdown_requested};
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn should_shut_down_without_saving_with_nosave() {
+        let mut client = test_helpers::client().await;
+        let (mut signal, filepath) = {
+            let mut server = client.server.lock().await;
+            server.db.dir = std::env::temp_dir().to_string_lossy().into_owned();
+            server.db.dbfilename = format!("nosave-{}.rdb", helpers::generate_id(8));
+            let filepath = format!("{}/{}", server.db.dir, server.db.dbfilename);
+            (server.shutdown_signal(), filepath)
+        };
+        client.send(&["SET", "k", "v"]).await;
+
+        assert_eq!(client.send(&["SHUTDOWN", "NOSAVE"]).await, b"");
+        let save = timeout(Duration::from_millis(100), shutdown_requested(&mut signal));
+        assert!(!save.await.unwrap());
+        assert!(!std::path::Path::new(&filepath).exists());
+    }
+
+    #[tokio::test]
+    async fn should_reject_unknown_modifier() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["SHUTDOWN", "LATER"]).await,
+            b"-ERR syntax error\r\n"
+        );
+        let signal = client.server.lock().await.shutdown_signal();
+        assert_eq!(*signal.borrow(), None);
+    }
+}
@@ -0,0 +1,161 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::{collections::HashSet, sync::Arc};
+use tokio::sync::Mutex;
+
+// ----------------------------------
+// SINTERSTORE/SUNIONSTORE/SDIFFSTORE
+// ----------------------------------
+
+/// The set operations performed by the storing commands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    /// The members common to all the sets (SINTERSTORE)
+    Intersection,
+    /// The members of any of the sets (SUNIONSTORE)
+    Union,
+    /// The members of the first set that are not in any of the others (SDIFFSTORE)
+    Difference,
+}
+
+/// Handles the SINTERSTORE, SUNIONSTORE and SDIFFSTORE commands.
+/// The commands compute the intersection, union or difference of the sets stored at the keys,
+/// and store the result in the destination key.
+/// The commands are in the format `SINTERSTORE destination key [key ...]`,
+/// `SUNIONSTORE destination key [key ...]` and `SDIFFSTORE destination key [key ...]`.
+/// Missing keys are treated as empty sets. The destination is overwritten,
+/// and removed if the result is empty.
+/// The commands return the number of members in the resulting set.
+/// Returns true if the destination was written, in which case the command should be propagated to replicas.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+    operation: Operation,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() < 2 {
+        if role.is_master() {
+            connection
+                .write_error(format!(
+                    "ERR wrong number of arguments for '{}' command",
+                    name
+                ))
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Collect the source sets, treating missing keys as empty sets
+    let db = server.lock().await.db.clone();
+    let mut sets = Vec::new();
+    for key in &args[1..] {
+        match db.read(key).await.get(connection.db, key) {
+            Some(Type::Set(set)) => sets.push(set.clone()),
+            Some(_) => {
+                if role.is_master() {
+                    connection.write_error(WrongType.to_string()).await?;
+                }
+                return Ok(false);
+            }
+            None => sets.push(HashSet::new()),
+        }
+    }
+
+    // Compute the result and store it in the destination, overwriting its previous value
+    let result = combine(sets, operation);
+    let len = result.len();
+    let mut shard = db.write(&args[0]).await;
+    if result.is_empty() {
+        shard.remove(connection.db, &args[0]);
+    } else {
+        shard.set(connection.db, args[0].clone(), Type::Set(result), None);
+    }
+    drop(shard);
+
+    // Respond with the number of members in the result
+    if role.is_master() {
+        let response = Type::Integer(len as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(true)
+}
+
+/// Combines the sets with the given operation.
+/// The difference is taken between the first set and all the others.
+fn combine(sets: Vec<HashSet<Type>>, operation: Operation) -> HashSet<Type> {
+    let mut sets = sets.into_iter();
+    let first = sets.next().unwrap_or_default();
+    sets.fold(first, |result, set| match operation {
+        Operation::Intersection => result.intersection(&set).cloned().collect(),
+        Operation::Union => result.union(&set).cloned().collect(),
+        Operation::Difference => result.difference(&set).cloned().collect(),
+    })
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_store_common_members() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "a", "x", "y", "z"]).await;
+        client.send(&["SADD", "b", "y", "z", "w"]).await;
+        assert_eq!(
+            client.send(&["SINTERSTORE", "dest", "a", "b"]).await,
+            b":2\r\n"
+        );
+        assert_eq!(client.send(&["SISMEMBER", "dest", "y"]).await, b":1\r\n");
+        assert_eq!(client.send(&["SISMEMBER", "dest", "x"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_store_union_and_difference() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "a", "x", "y"]).await;
+        client.send(&["SADD", "b", "y", "z"]).await;
+        assert_eq!(
+            client
+                .send(&["SUNIONSTORE", "dest", "a", "b", "missing"])
+                .await,
+            b":3\r\n"
+        );
+        assert_eq!(
+            client.send(&["SDIFFSTORE", "dest", "a", "b"]).await,
+            b":1\r\n"
+        );
+        assert_eq!(
+            client.send(&["SMEMBERS", "dest"]).await,
+            b"*1\r\n$1\r\nx\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_remove_destination_when_result_is_empty() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "a", "x"]).await;
+        client.send(&["SET", "dest", "v"]).await;
+        assert_eq!(
+            client.send(&["SINTERSTORE", "dest", "a", "missing"]).await,
+            b":0\r\n"
+        );
+        assert_eq!(client.send(&["EXISTS", "dest"]).await, b":0\r\n");
+    }
+}
@@ -0,0 +1,61 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ---------
+// SISMEMBER
+// ---------
+
+/// Handles the SISMEMBER command.
+/// The SISMEMBER command checks whether the member is part of the set stored at the key.
+/// The command is in the format `SISMEMBER key member`.
+/// The command returns 1 if the member is part of the set, and 0 otherwise.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'SISMEMBER' command")
+            .await;
+    }
+
+    // Check whether the member is part of the set stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::Set(set)) => Type::Integer(set.contains(&args[1]) as i64),
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => Type::Integer(0),
+    };
+
+    // Respond with the result
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_check_membership() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "k", "a"]).await;
+        assert_eq!(client.send(&["SISMEMBER", "k", "a"]).await, b":1\r\n");
+        assert_eq!(client.send(&["SISMEMBER", "k", "b"]).await, b":0\r\n");
+        assert_eq!(client.send(&["SISMEMBER", "missing", "a"]).await, b":0\r\n");
+    }
+}
@@ -0,0 +1,65 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// --------
+// SMEMBERS
+// --------
+
+/// Handles the SMEMBERS command.
+/// The SMEMBERS command returns all members of the set stored at the key.
+/// The command is in the format `SMEMBERS key`.
+/// The members are returned as an array in no particular order,
+/// or an empty array if the key does not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 1 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'SMEMBERS' command")
+            .await;
+    }
+
+    // Collect the members of the set stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::Set(set)) => resp::array(set.iter().cloned().collect()),
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => resp::array(Vec::new()),
+    };
+
+    // Respond with the members
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_all_members() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "k", "a", "b"]).await;
+        let response = client.send(&["SMEMBERS", "k"]).await;
+        assert!(
+            response == b"*2\r\n$1\r\na\r\n$1\r\nb\r\n"
+                || response == b"*2\r\n$1\r\nb\r\n$1\r\na\r\n"
+        );
+        assert_eq!(client.send(&["SMEMBERS", "missing"]).await, b"*0\r\n");
+    }
+}
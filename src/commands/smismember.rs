@@ -0,0 +1,72 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----------
+// SMISMEMBER
+// ----------
+
+/// Handles the SMISMEMBER command.
+/// The SMISMEMBER command checks whether each of the members is part of the set stored at the key.
+/// The command is in the format `SMISMEMBER key member [member ...]`.
+/// The command returns an array with 1 for each member that is part of the set, and 0 otherwise,
+/// in the order of the members. A missing key behaves like an empty set.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() < 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'SMISMEMBER' command")
+            .await;
+    }
+
+    // Check whether each member is part of the set stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::Set(set)) => resp::array(
+            args[1..]
+                .iter()
+                .map(|member| Type::Integer(set.contains(member) as i64))
+                .collect(),
+        ),
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => resp::array(vec![Type::Integer(0); args.len() - 1]),
+    };
+
+    // Respond with the results
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_check_members_in_order() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "k", "a", "c"]).await;
+        assert_eq!(
+            client.send(&["SMISMEMBER", "k", "c", "b", "a"]).await,
+            b"*3\r\n:1\r\n:0\r\n:1\r\n"
+        );
+        assert_eq!(
+            client.send(&["SMISMEMBER", "missing", "a", "b"]).await,
+            b"*2\r\n:0\r\n:0\r\n"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+// Library
+use crate::{
+    database::WrongType,
+    errors::Error,
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use rand::seq::{IteratorRandom, SliceRandom};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -----------
+// SRANDMEMBER
+// -----------
+
+/// The maximum number of members returned for a negative count, as each member is allocated up front
+const MAX_REPEATED_COUNT: u64 = 1024 * 1024;
+
+/// Handles the SRANDMEMBER command.
+/// The SRANDMEMBER command returns random members of the set stored at the key, without removing them.
+/// The command is in the format `SRANDMEMBER key [count]`.
+/// Without a count, the command returns a single member, or null if the key does not exist.
+/// With a positive count, the command returns up to `count` distinct members.
+/// With a negative count, the command returns exactly `-count` members, which may repeat.
+/// Negative counts beyond `-MAX_REPEATED_COUNT` are rejected as out of range.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.is_empty() || args.len() > 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'SRANDMEMBER' command")
+            .await;
+    }
+
+    // Extract the optional count from the arguments
+    let count = match args.get(1) {
+        Some(count) => match count.as_str().and_then(|count| count.parse::<i64>().ok()) {
+            Some(count) => Some(count),
+            None => {
+                return connection.write_error(Error::NotInteger.to_string()).await;
+            }
+        },
+        None => None,
+    };
+    if count.is_some_and(|count| count < 0 && count.unsigned_abs() > MAX_REPEATED_COUNT) {
+        return connection.write_error("ERR value is out of range").await;
+    }
+
+    // Pick the random members from the set stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::Set(set)) => {
+            let mut rng = rand::thread_rng();
+            match count {
+                None => set.iter().choose(&mut rng).cloned().unwrap_or(Type::Null),
+                Some(count) if count >= 0 => resp::array(
+                    set.iter()
+                        .cloned()
+                        .choose_multiple(&mut rng, count as usize),
+                ),
+                Some(count) => {
+                    let members: Vec<&Type> = set.iter().collect();
+                    resp::array(
+                        (0..count.unsigned_abs())
+                            .filter_map(|_| members.choose(&mut rng).map(|m| (*m).clone()))
+                            .collect(),
+                    )
+                }
+            }
+        }
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => match count {
+            Some(_) => resp::array(Vec::new()),
+            None => Type::Null,
+        },
+    };
+    drop(shard);
+
+    // Respond with the members
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_distinct_members_for_positive_count() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "k", "a", "b", "c"]).await;
+        let response = client.send(&["SRANDMEMBER", "k", "10"]).await;
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("*3\r\n"));
+        for member in ["a", "b", "c"] {
+            assert!(response.contains(&format!("$1\r\n{}\r\n", member)));
+        }
+    }
+
+    #[tokio::test]
+    async fn should_allow_duplicates_for_negative_count() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "k", "a"]).await;
+        assert_eq!(
+            client.send(&["SRANDMEMBER", "k", "-3"]).await,
+            b"*3\r\n$1\r\na\r\n$1\r\na\r\n$1\r\na\r\n"
+        );
+        assert_eq!(client.send(&["SRANDMEMBER", "k"]).await, b"$1\r\na\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_out_of_range_negative_count() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "k", "a"]).await;
+        assert_eq!(
+            client
+                .send(&["SRANDMEMBER", "k", "-9223372036854775807"])
+                .await,
+            b"-ERR value is out of range\r\n"
+        );
+        assert_eq!(client.send(&["PING"]).await, b"+PONG\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_handle_missing_key() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["SRANDMEMBER", "missing"]).await, b"$-1\r\n");
+        assert_eq!(
+            client.send(&["SRANDMEMBER", "missing", "5"]).await,
+            b"*0\r\n"
+        );
+    }
+}
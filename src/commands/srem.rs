@@ -0,0 +1,86 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// SREM
+// ----
+
+/// Handles the SREM command.
+/// The SREM command removes the specified members from the set stored at the key.
+/// The command is in the format `SREM key member [member ...]`.
+/// Members that are not part of the set are ignored, and the key is removed once the set is empty.
+/// The command returns the number of members that were removed from the set.
+/// Returns true if any member was removed, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() < 2 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'SREM' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Remove the members from the set stored at the key
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let removed = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::Set(set)) => {
+            let removed = args[1..].iter().filter(|member| set.remove(member)).count();
+            if set.is_empty() {
+                shard.remove(connection.db, &args[0]);
+            }
+            removed
+        }
+        Some(_) => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+        None => 0,
+    };
+
+    // Respond with the number of removed members
+    if role.is_master() {
+        let response = Type::Integer(removed as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(removed > 0)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_remove_members_and_empty_set() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "k", "a", "b"]).await;
+        assert_eq!(client.send(&["SREM", "k", "a", "z"]).await, b":1\r\n");
+        assert_eq!(client.send(&["SREM", "k", "b"]).await, b":1\r\n");
+        assert_eq!(client.send(&["EXISTS", "k"]).await, b":0\r\n");
+    }
+}
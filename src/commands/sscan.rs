@@ -0,0 +1,107 @@
+// Library
+use crate::{
+    commands::scan::{self, ScanOptions},
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -----
+// SSCAN
+// -----
+
+/// Handles the SSCAN command.
+/// The SSCAN command incrementally iterates over the members of the set stored at the key.
+/// The command is in the format `SSCAN key cursor [MATCH pattern] [COUNT count]`.
+/// The cursor works as in SCAN, with the members iterated in sorted order and matched against the pattern.
+/// The members are returned as an array, or an empty array if the key does not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() < 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'SSCAN' command")
+            .await;
+    }
+
+    // Parse the cursor and the options
+    let options = match ScanOptions::parse(&args[1..]) {
+        Ok(options) => options,
+        Err(e) => return connection.write_error(e).await,
+    };
+
+    // Snapshot the members of the set in sorted order
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let mut members: Vec<Type> = match shard.get(connection.db, &args[0]) {
+        Some(Type::Set(set)) => set.iter().cloned().collect(),
+        Some(_) => {
+            drop(shard);
+            return connection.write_error(WrongType.to_string()).await;
+        }
+        None => Vec::new(),
+    };
+    drop(shard);
+    members.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+
+    // Examine the next batch of members, and respond with the ones matching the pattern
+    let (next_cursor, batch) = options.page(&members, |member| {
+        member.as_str().unwrap_or_default().to_string()
+    });
+    let response = scan::reply(next_cursor, batch.into_iter().cloned().collect());
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_iterate_members_with_cursor() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SADD", "s", "c", "a", "d", "b"]).await;
+
+        let (cursor, members) = client.send_scan(&["SSCAN", "s", "0", "COUNT", "3"]).await;
+        assert_eq!(cursor, "3");
+        assert_eq!(members, vec!["a", "b", "c"]);
+        let (cursor, members) = client.send_scan(&["SSCAN", "s", "3", "COUNT", "3"]).await;
+        assert_eq!(cursor, "0");
+        assert_eq!(members, vec!["d"]);
+    }
+
+    #[tokio::test]
+    async fn should_only_return_members_matching_pattern() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["SADD", "s", "apple", "avocado", "banana"])
+            .await;
+        let (cursor, members) = client.send_scan(&["SSCAN", "s", "0", "MATCH", "a*"]).await;
+        assert_eq!(cursor, "0");
+        assert_eq!(members, vec!["apple", "avocado"]);
+    }
+
+    #[tokio::test]
+    async fn should_handle_missing_keys_and_wrong_types() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["SSCAN", "missing", "0"]).await,
+            b"*2\r\n$1\r\n0\r\n*0\r\n"
+        );
+        assert_eq!(
+            client.send(&["SSCAN", "k", "0"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// STRLEN
+// ------
+
+/// Handles the STRLEN command.
+/// The STRLEN command returns the length of the string stored at the key.
+/// The command is in the format `STRLEN key`.
+/// The command returns 0 if the key does not exist.
+/// The command returns an error if the key holds a value that is not a string.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 1 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'STRLEN' command")
+            .await;
+    }
+
+    // Get the length of the stored string
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.expect_string(connection.db, &args[0]) {
+        Ok(Some(value)) => Type::Integer(value.len() as i64),
+        Ok(None) => Type::Integer(0),
+        Err(e) => Type::SimpleError(e.to_string()),
+    };
+
+    // Respond with the length
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_string_length() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "Hello"]).await;
+        assert_eq!(client.send(&["STRLEN", "k"]).await, b":5\r\n");
+        assert_eq!(client.send(&["STRLEN", "missing"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_string_value() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        assert_eq!(
+            client.send(&["STRLEN", "s"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
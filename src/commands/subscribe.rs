@@ -0,0 +1,80 @@
+// Library
+use crate::{
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ---------
+// SUBSCRIBE
+// ---------
+
+/// Handles the SUBSCRIBE command.
+/// The SUBSCRIBE command subscribes the client to the given channels.
+/// Messages published to the channels (with the PUBLISH command) are then pushed to the client.
+/// The command is in the format `SUBSCRIBE channel [channel ...]`.
+/// The command replies with a `subscribe` message for every channel,
+/// along with the number of channels and patterns the client is subscribed to.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.is_empty() {
+        return connection
+            .write_error("ERR wrong number of arguments for 'subscribe' command")
+            .await;
+    }
+
+    // Get the channel names
+    let Some(channels) = args.iter().map(Type::as_str).collect::<Option<Vec<_>>>() else {
+        return connection
+            .write_error("ERR invalid channel name for 'subscribe' command")
+            .await;
+    };
+
+    for channel in channels {
+        // Register the connection as a subscriber of the channel
+        server
+            .lock()
+            .await
+            .channels
+            .entry(channel.to_string())
+            .or_default()
+            .insert(connection.id, connection.messages_sender.clone());
+        connection.subscriptions.insert(channel.to_string());
+
+        // Confirm the subscription
+        let count = connection.subscription_count() as i64;
+        connection
+            .write_push(vec![
+                resp::bulk_string("subscribe"),
+                resp::bulk_string(channel),
+                Type::Integer(count),
+            ])
+            .await?;
+    }
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_confirm_each_subscription() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["SUBSCRIBE", "a", "b"]).await,
+            b"*3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n:1\r\n*3\r\n$9\r\nsubscribe\r\n$1\r\nb\r\n:2\r\n"
+        );
+        assert_eq!(client.server.lock().await.channels.len(), 2);
+    }
+}
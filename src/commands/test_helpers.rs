@@ -0,0 +1,113 @@
+// Library
+use crate::{
+    config::DEFAULT_BUFFER_SIZE,
+    parser::{self, resp},
+    server::{
+        self,
+        connection::{self, Connection, Kind},
+        Server,
+    },
+};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    io::AsyncReadExt,
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, Mutex},
+    time::timeout,
+};
+
+// ------------
+// TEST HELPERS
+// ------------
+
+/// How long to wait for a response before assuming the command wrote nothing
+const RESPONSE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// A test client wired to a server-side `Connection` over a loopback socket.
+/// Commands are dispatched through `commands::handle` exactly as the server would,
+/// and the raw response bytes are read back from the client end of the socket.
+pub struct Client {
+    /// The shared server instance
+    pub server: Arc<Mutex<Server>>,
+    /// The server-side end of the connection
+    pub conn: Connection,
+    /// The client-side end of the connection
+    stream: TcpStream,
+    /// The channel used by WAIT and REPLCONF ACK
+    wait_channel: Arc<Mutex<(mpsc::Sender<u64>, mpsc::Receiver<u64>)>>,
+}
+
+/// Creates a new test client connected to a fresh master server
+pub async fn client() -> Client {
+    let server = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+    client_for(&server).await
+}
+
+/// Creates a new test client connected to the given server
+pub async fn client_for(server: &Arc<Mutex<Server>>) -> Client {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (accepted, addr) = listener.accept().await.unwrap();
+    let conn = connection::new(accepted, addr, Kind::Main, DEFAULT_BUFFER_SIZE);
+    server.lock().await.register_client(&conn);
+    Client {
+        server: Arc::clone(server),
+        conn,
+        stream,
+        wait_channel: Arc::new(Mutex::new(mpsc::channel::<u64>(64))),
+    }
+}
+
+impl Client {
+    /// Sends the command to the server and returns the raw response bytes
+    pub async fn send(&mut self, args: &[&str]) -> Vec<u8> {
+        let args: Vec<&[u8]> = args.iter().map(|arg| arg.as_bytes()).collect();
+        self.send_bytes(&args).await
+    }
+
+    /// Sends the command, given as raw (possibly binary) arguments, and returns the raw response bytes
+    pub async fn send_bytes(&mut self, args: &[&[u8]]) -> Vec<u8> {
+        let cmd: Vec<resp::Type> = args
+            .iter()
+            .map(|arg| resp::Type::BulkString(arg.to_vec()))
+            .collect();
+        super::handle(&cmd, &mut self.conn, &self.server, &self.wait_channel)
+            .await
+            .unwrap();
+        self.read().await
+    }
+
+    /// Sends a command of the SCAN family and returns the next cursor and the returned items
+    pub async fn send_scan(&mut self, args: &[&str]) -> (String, Vec<String>) {
+        let response = parser::parse(&self.send(args).await).unwrap();
+        let [resp::Type::Array(reply)] = response.as_slice() else {
+            panic!("unexpected reply {:?}", response);
+        };
+        let [cursor, resp::Type::Array(items)] = reply.as_slice() else {
+            panic!("unexpected reply {:?}", reply);
+        };
+        let items = items
+            .iter()
+            .map(|item| item.as_str().unwrap().to_string())
+            .collect();
+        (cursor.as_str().unwrap().to_string(), items)
+    }
+
+    /// Reads whatever response bytes are available on the client end of the socket
+    pub async fn read(&mut self) -> Vec<u8> {
+        let mut response = Vec::new();
+        let mut buf = [0; 1024];
+        if let Ok(Ok(n)) = timeout(RESPONSE_TIMEOUT, self.stream.read(&mut buf)).await {
+            response.extend_from_slice(&buf[..n]);
+            while let Ok(n) = self.stream.try_read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                response.extend_from_slice(&buf[..n]);
+            }
+        }
+        response
+    }
+}
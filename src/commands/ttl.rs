@@ -0,0 +1,75 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// --------
+// TTL/PTTL
+// --------
+
+/// Handles the TTL and PTTL commands.
+/// The commands return the remaining time to live of a key that has an expiry.
+/// The commands are in the format `TTL key` and `PTTL key`.
+/// The `unit` is the number of milliseconds per unit of the reply: 1000 for TTL and 1 for PTTL.
+/// The command returns -2 if the key does not exist, and -1 if the key exists but has no expiry.
+pub async fn command(
+    name: &str,
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+    unit: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 1 {
+        return connection
+            .write_error(format!(
+                "ERR wrong number of arguments for '{}' command",
+                name
+            ))
+            .await;
+    }
+
+    // Get the remaining time to live, rounded to the nearest unit
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let ttl = match shard.get_item(connection.db, &args[0]) {
+        Some(item) => match item.ttl() {
+            Some(ttl) => ((ttl + unit / 2) / unit) as i64,
+            None => -1,
+        },
+        None => -2,
+    };
+
+    // Respond with the time to live
+    let response = Type::Integer(ttl);
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_report_missing_and_persistent_keys() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(client.send(&["TTL", "missing"]).await, b":-2\r\n");
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(client.send(&["TTL", "k"]).await, b":-1\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_report_remaining_time() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v", "EX", "100"]).await;
+        assert_eq!(client.send(&["TTL", "k"]).await, b":100\r\n");
+    }
+}
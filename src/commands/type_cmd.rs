@@ -18,10 +18,12 @@ use crate::{
 /// - "set" for a set value
 /// - "zset" for a sorted set value
 /// - "hash" for a hash value
-/// - "none" if the key does not exist
+/// - "stream" for a stream value
+/// - "none" if the key does not exist (or has expired)
+///
 /// The command is used to determine the type of a value before performing operations on it.
 pub async fn command(
-    cmd: &Vec<resp::Type>,
+    cmd: &[resp::Type],
     conn: &mut crate::server::connection::Connection,
     server: &std::sync::Arc<tokio::sync::Mutex<Server>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -36,27 +38,103 @@ pub async fn command(
     };
     let key = &Type::BulkString(key.clone());
 
-    // Get the value from the server
-    let s = server.lock().await;
-    let value_type = s.db.get(key);
+    // Get the value from the server and determine its type
+    let db = server.lock().await.db.clone();
+    let value_type_str = type_name(db.get(conn.db, key).await.as_ref());
 
-    // Convert the value type to a string
-    let value_type_str = match value_type {
+    // Create the response
+    let response = resp::Type::SimpleString(value_type_str.into());
+    conn.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+/// Returns the name of the type of the given value, as reported by the TYPE command.
+/// A missing value is reported as "none".
+fn type_name(value: Option<&Type>) -> &'static str {
+    match value {
         Some(value) => match value {
             Type::SimpleString(_) => "string",
             Type::BulkString(_) => "string",
             Type::Stream(_) => "stream",
-            // Type::Set(_) => "set",
-            // Type::ZSet(_) => "zset",
-            // Type::Hash(_) => "hash",
+            Type::List(_) => "list",
+            Type::Set(_) => "set",
+            Type::SortedSet(_) => "zset",
+            Type::Hash(_) => "hash",
             _ => "none",
         },
         None => "none",
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        database,
+        parser::resp::stream::{Stream, StreamID},
+    };
+    use std::{
+        collections::{HashMap, HashSet},
+        time::Duration,
     };
 
-    // Create the response
-    let response = resp::Type::SimpleString(value_type_str.into());
-    conn.write_all(&response.as_bytes()).await?;
+    fn key(key: &str) -> Type {
+        Type::BulkString(key.into())
+    }
 
-    Ok(())
+    #[tokio::test]
+    async fn should_report_string() {
+        let db = database::new();
+        db.set(0, key("k"), Type::BulkString("v".into()), None)
+            .await;
+        assert_eq!(type_name(db.get(0, &key("k")).await.as_ref()), "string");
+    }
+
+    #[tokio::test]
+    async fn should_report_stream() {
+        let db = database::new();
+        let entries = vec![(StreamID::from_parts(1, 1), HashMap::new())];
+        db.set(0, key("s"), Type::Stream(Stream::new(entries)), None)
+            .await;
+        assert_eq!(type_name(db.get(0, &key("s")).await.as_ref()), "stream");
+    }
+
+    #[tokio::test]
+    async fn should_report_list() {
+        let db = database::new();
+        db.set(0, key("l"), Type::List(vec!["a".into()]), None)
+            .await;
+        assert_eq!(type_name(db.get(0, &key("l")).await.as_ref()), "list");
+    }
+
+    #[tokio::test]
+    async fn should_report_hash() {
+        let db = database::new();
+        let hash = HashMap::from([("f".to_string(), "v".to_string())]);
+        db.set(0, key("h"), Type::Hash(hash), None).await;
+        assert_eq!(type_name(db.get(0, &key("h")).await.as_ref()), "hash");
+    }
+
+    #[tokio::test]
+    async fn should_report_set() {
+        let db = database::new();
+        let set = HashSet::from([Type::BulkString("a".into())]);
+        db.set(0, key("s"), Type::Set(set), None).await;
+        assert_eq!(type_name(db.get(0, &key("s")).await.as_ref()), "set");
+    }
+
+    #[tokio::test]
+    async fn should_report_none_for_missing_and_expired_keys() {
+        let db = database::new();
+        db.set(0, key("expired"), Type::BulkString("v".into()), Some(1))
+            .await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(type_name(db.get(0, &key("missing")).await.as_ref()), "none");
+        assert_eq!(type_name(db.get(0, &key("expired")).await.as_ref()), "none");
+    }
 }
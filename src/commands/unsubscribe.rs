@@ -0,0 +1,93 @@
+// Library
+use crate::{
+    parser::resp::{self, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -----------
+// UNSUBSCRIBE
+// -----------
+
+/// Handles the UNSUBSCRIBE command.
+/// The UNSUBSCRIBE command unsubscribes the client from the given channels,
+/// or from all the channels it is subscribed to if none are given.
+/// The command is in the format `UNSUBSCRIBE [channel [channel ...]]`.
+/// The command replies with an `unsubscribe` message for every channel,
+/// along with the number of channels and patterns the client is still subscribed to.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Get the channel names, defaulting to all the subscribed channels
+    let channels: Vec<String> = if args.is_empty() {
+        let mut channels: Vec<String> = connection.subscriptions.iter().cloned().collect();
+        channels.sort();
+        channels
+    } else {
+        match args.iter().map(Type::as_str).collect::<Option<Vec<_>>>() {
+            Some(channels) => channels.into_iter().map(String::from).collect(),
+            None => {
+                return connection
+                    .write_error("ERR invalid channel name for 'unsubscribe' command")
+                    .await;
+            }
+        }
+    };
+
+    // Without any channels to unsubscribe from, confirm that the client has no channel subscriptions
+    if channels.is_empty() {
+        let count = connection.subscription_count() as i64;
+        return connection
+            .write_push(vec![
+                resp::bulk_string("unsubscribe"),
+                Type::Null,
+                Type::Integer(count),
+            ])
+            .await;
+    }
+
+    for channel in channels {
+        // Remove the connection from the subscribers of the channel
+        server.lock().await.unsubscribe(&channel, connection.id);
+        connection.subscriptions.remove(&channel);
+
+        // Confirm the unsubscription
+        let count = connection.subscription_count() as i64;
+        connection
+            .write_push(vec![
+                resp::bulk_string("unsubscribe"),
+                resp::bulk_string(&channel),
+                Type::Integer(count),
+            ])
+            .await?;
+    }
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_unsubscribe_from_all_channels() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SUBSCRIBE", "a", "b"]).await;
+        assert_eq!(
+            client.send(&["UNSUBSCRIBE"]).await,
+            b"*3\r\n$11\r\nunsubscribe\r\n$1\r\na\r\n:1\r\n*3\r\n$11\r\nunsubscribe\r\n$1\r\nb\r\n:0\r\n"
+        );
+        assert!(client.server.lock().await.channels.is_empty());
+        assert_eq!(
+            client.send(&["UNSUBSCRIBE"]).await,
+            b"*3\r\n$11\r\nunsubscribe\r\n$-1\r\n:0\r\n"
+        );
+    }
+}
@@ -16,19 +16,28 @@ use tokio::{
 /// Handle the WAIT command.
 /// The WAIT command blocks the client until the specified number of replicas for the specified key is reached,
 /// or the timeout is reached. The command is used to wait for the completion of a write operation on a replica.
+/// The command is in the format `WAIT numreplicas timeout`.
+/// If enough replicas have already acknowledged every write, the command returns immediately without asking for acks.
 pub async fn command(
     args: &[resp::Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
     wait_channel: &Arc<Mutex<(mpsc::Sender<u64>, mpsc::Receiver<u64>)>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Get the role, master_repl_offset, and number of replicas from the server
-    let (role, master_repl_offset, addresses) = {
+    // Get the role, master_repl_offset, and number of replicas from the server,
+    // along with the number of replicas that have already acknowledged every write
+    let (role, master_repl_offset, addresses, acked_replicas) = {
         let server = server.lock().await;
+        let acked_replicas = server
+            .replicas
+            .iter()
+            .filter(|replica| replica.offset >= server.master_repl_offset)
+            .count();
         (
             server.role.clone(),
-            server.master_repl_offset.clone(),
+            server.master_repl_offset,
             server.replicas.len(),
+            acked_replicas,
         )
     };
 
@@ -47,11 +56,11 @@ pub async fn command(
     }
 
     // Extract the desired number of replicas from the arguments
-    let desired_replicas = match &args[0] {
-        resp::Type::BulkString(replicas) => replicas.parse::<usize>()?,
-        x => {
+    let desired_replicas = match args[0].as_str() {
+        Some(replicas) => replicas.parse::<usize>()?,
+        None => {
             return connection
-                .write_error(format!("ERR invalid number of replicas {:?}", x))
+                .write_error(format!("ERR invalid number of replicas {:?}", args[0]))
                 .await;
         }
     };
@@ -63,17 +72,23 @@ pub async fn command(
     };
 
     // Extract the timeout from the arguments
-    let timeout = match &args[1] {
-        resp::Type::BulkString(timeout) => timeout.parse::<u64>()?,
-        x => {
+    let timeout = match args[1].as_str() {
+        Some(timeout) => timeout.parse::<u64>()?,
+        None => {
             return connection
-                .write_error(format!("ERR invalid timeout {:?}", x))
+                .write_error(format!("ERR invalid timeout {:?}", args[1]))
                 .await;
         }
     };
     // Calculate the timeout
     let timeout = Instant::now() + Duration::from_millis(timeout);
 
+    // Return immediately if there are no replicas to wait for, or enough of them are already in sync
+    if addresses == 0 || acked_replicas >= desired_replicas {
+        let response = resp::Type::Integer(acked_replicas as i64);
+        return connection.write_all(&response.as_bytes()).await;
+    }
+
     let mut synced_replicas = 0;
 
     // Counter to keep track of the number of bytes to send later
@@ -100,9 +115,9 @@ pub async fn command(
             // If this is the first iteration, send the REPLCONF GETACK command
             if first_iteration {
                 let command = Type::Array(vec![
-                    Type::BulkString("REPLCONF".to_string()),
-                    Type::BulkString("GETACK".to_string()),
-                    Type::BulkString("*".to_string()),
+                    resp::bulk_string("REPLCONF"),
+                    resp::bulk_string("GETACK"),
+                    resp::bulk_string("*"),
                 ]);
                 later_bytes += command.as_bytes().len();
                 println!("Sending REPLCONF GETACK * command");
@@ -165,3 +180,57 @@ pub async fn command(
 
     Ok(())
 }
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{commands::test_helpers, server::replication::Replica};
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn should_return_immediately_without_replicas() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        let wait = timeout(
+            Duration::from_millis(50),
+            client.send(&["WAIT", "1", "5000"]),
+        );
+        assert_eq!(wait.await.unwrap(), b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_return_immediately_when_replicas_already_acked() {
+        let mut client = test_helpers::client().await;
+        let mut stream = {
+            let mut server = client.server.lock().await;
+            server.master_repl_offset = 100;
+            for (port, offset) in [(6380, 100), (6381, 40)] {
+                server.replicas.push(Replica {
+                    addr: format!("127.0.0.1:{}", port).parse().unwrap(),
+                    port,
+                    offset,
+                });
+            }
+            server.sender.subscribe()
+        };
+
+        let wait = timeout(
+            Duration::from_millis(50),
+            client.send(&["WAIT", "1", "5000"]),
+        );
+        assert_eq!(wait.await.unwrap(), b":1\r\n");
+        let wait = timeout(
+            Duration::from_millis(50),
+            client.send(&["WAIT", "0", "5000"]),
+        );
+        assert_eq!(wait.await.unwrap(), b":1\r\n");
+
+        // No acknowledgements were requested from the replicas
+        assert!(stream.try_recv().is_err());
+        assert_eq!(client.server.lock().await.master_repl_offset, 100);
+    }
+}
@@ -1,7 +1,10 @@
 // Library
 use crate::{
-    parser::resp::{stream::StreamID, Type},
-    server::{connection::Connection, Server},
+    parser::resp::{
+        stream::{Stream, StreamID},
+        Type,
+    },
+    server::{connection::Connection, replication::Role, Server},
 };
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
@@ -13,105 +16,283 @@ use tokio::sync::Mutex;
 /// Handles the XADD command.
 /// The XADD command is used to append a new entry to a stream.
 /// The command is in the format `XADD 'stream' 'id' 'field1' 'value1' 'field2' 'value2' ...`.
+/// The ID can be explicit (`5-3`), have an auto-generated sequence number (`5-*`), or be fully auto-generated (`*`).
 /// The command returns the ID of the new entry.
-/// If the stream does not exist, it is created.
+/// Returns the ID of the new entry, so that the command can be propagated with the generated ID.
+/// If the stream does not exist, it is created, and an error is returned if the key holds another type of value.
 pub async fn command(
-    args: &Vec<Type>,
+    args: &[Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<Option<StreamID>, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
     // Check the number of arguments
-    if args.len() < 3 || args.len() % 2 == 0 {
-        return connection
-            .write_error("ERR wrong number of arguments for 'XADD' command")
-            .await;
+    if args.len() < 3 || args.len().is_multiple_of(2) {
+        return write_error(
+            connection,
+            &role,
+            "ERR wrong number of arguments for 'XADD' command",
+        )
+        .await;
     }
 
     // Extract the stream name and ID from the arguments
     let name = match args.get(1) {
         Some(stream) => stream,
         _ => {
-            return connection.write_error("ERR invalid stream name").await;
+            return write_error(connection, &role, "ERR invalid stream name").await;
         }
     };
-    let id = match args.get(2) {
-        Some(Type::BulkString(id)) => id,
+    let id = match args.get(2).and_then(Type::as_str) {
+        Some(id) => id,
         _ => {
-            return connection.write_error("ERR invalid ID").await;
+            return write_error(connection, &role, "ERR invalid ID").await;
         }
     };
 
     // Extract the field-value pairs from the arguments
     let mut fields = HashMap::new();
     for i in (3..args.len()).step_by(2) {
-        let field = match args.get(i) {
-            Some(Type::BulkString(field)) => field,
+        let field = match args.get(i).and_then(Type::as_str) {
+            Some(field) => field,
             _ => {
-                return connection.write_error("ERR invalid field").await;
+                return write_error(connection, &role, "ERR invalid field").await;
             }
         };
-        let value = match args.get(i + 1) {
-            Some(Type::BulkString(value)) => value,
+        let value = match args.get(i + 1).and_then(Type::as_str) {
+            Some(value) => value,
             _ => {
-                return connection.write_error("ERR invalid value").await;
+                return write_error(connection, &role, "ERR invalid value").await;
             }
         };
         fields.insert(field.to_string(), value.to_string());
     }
 
-    // Get the current stream
-    let mut s = server.lock().await;
-    let item = s.db.get(name);
-    let mut stream = match item {
-        Some(Type::Stream(stream)) => stream.clone(),
-        _ => Vec::new(), // Create a new stream
+    // Get the last ID of the current stream, which the new ID must be larger than.
+    // Deleted entries still count, so that IDs never go backwards.
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(name).await;
+    let last_id = match shard.expect_stream(connection.db, name) {
+        Ok(stream) => stream.map(|stream| stream.last_id.clone()),
+        Err(e) => return write_error(connection, &role, e.to_string()).await,
     };
 
-    // Check that the ID is larger than the last entry
-    let last_entry = match stream.last() {
-        Some(entry) => Some(entry.clone()),
-        None => None,
+    // Parse the ID, generating the parts given as `*`
+    let id = match StreamID::parse(id, last_id.clone()) {
+        Some(id) => id,
+        None => {
+            return write_error(
+                connection,
+                &role,
+                "ERR Invalid stream ID specified as stream command argument",
+            )
+            .await;
+        }
     };
 
-    // Split the id into its parts
-    let old_id = id.clone();
-    let id = StreamID::parse(&id, last_entry.clone());
-    println!(
-        "Stream ID ({}): {}-{}",
-        old_id, id.milliseconds, id.sequence
-    );
-
     // Check if the ID is valid
-    if id.milliseconds == 0 && id.sequence == 0 {
-        return connection
-            .write_error("ERR The ID specified in XADD must be greater than 0-0")
-            .await;
+    if id == StreamID::from_parts(0, 0) {
+        return write_error(
+            connection,
+            &role,
+            "ERR The ID specified in XADD must be greater than 0-0",
+        )
+        .await;
     }
 
-    // Check if the ID is greater than the last entry
-    if let Some((last, _)) = last_entry {
-        if id.milliseconds < last.milliseconds
-            || (id.milliseconds == last.milliseconds && id.sequence <= last.sequence)
-        {
-            return connection
-                .write_error(
-                    "ERR The ID specified in XADD is equal or smaller than the target stream top item",
-                )
-                .await;
+    // Check if the ID is greater than the last ID
+    if let Some(last) = last_id {
+        if id <= last {
+            return write_error(
+                connection,
+                &role,
+                "ERR The ID specified in XADD is equal or smaller than the target stream top item",
+            )
+            .await;
         }
     }
 
     // Update the ID format
-    let response = Type::BulkString(id.to_string());
+    let response = Type::BulkString(id.to_string().into());
 
-    // Append the entry to the stream
-    stream.push((id, fields));
+    // Append the entry to the stream in place, so that the key keeps its expiry,
+    // or create the stream if it does not exist
+    let entry = (id.clone(), fields);
+    match shard.get_mut(connection.db, name) {
+        Some(Type::Stream(stream)) => stream.push(entry),
+        _ => shard.set(
+            connection.db,
+            name.clone(),
+            Type::Stream(Stream::new(vec![entry])),
+            None,
+        ),
+    }
+    drop(shard);
 
-    // Update the database
-    s.db.set(name.clone(), Type::Stream(stream), None);
+    // Wake up the clients blocked on the stream. Sending only fails if no client is blocked
+    let _ = server
+        .lock()
+        .await
+        .stream_events
+        .send((connection.db, name.clone()));
 
     // Write the ID of the new entry
-    connection.write_all(&response.as_bytes()).await?;
+    if role.is_master() {
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(Some(id))
+}
+
+/// Responds with the error, unless the command was propagated from the master
+async fn write_error(
+    connection: &mut Connection,
+    role: &Role,
+    message: impl Into<String>,
+) -> Result<Option<StreamID>, Box<dyn std::error::Error>> {
+    if role.is_master() {
+        connection.write_error(message).await?;
+    }
+    Ok(None)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::test_helpers,
+        parser::resp::{
+            stream::{Stream, StreamID},
+            Type,
+        },
+    };
+    use std::{
+        collections::HashMap,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[tokio::test]
+    async fn should_generate_full_id() {
+        let mut client = test_helpers::client().await;
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let response = client.send(&["XADD", "s", "*", "f", "v"]).await;
+        let response = String::from_utf8(response).unwrap();
+        let id = response.trim_end().lines().last().unwrap();
+        let (milliseconds, sequence) = id.split_once('-').unwrap();
+        assert!(milliseconds.parse::<u64>().unwrap() >= before);
+        assert_eq!(sequence, "0");
+    }
+
+    #[tokio::test]
+    async fn should_generate_sequence_number() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["XADD", "s", "5-*", "f", "v"]).await,
+            b"$3\r\n5-0\r\n"
+        );
+        assert_eq!(
+            client.send(&["XADD", "s", "5-*", "f", "v"]).await,
+            b"$3\r\n5-1\r\n"
+        );
+        assert_eq!(
+            client.send(&["XADD", "t", "0-*", "f", "v"]).await,
+            b"$3\r\n0-1\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_ids_that_are_not_increasing() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "5-1", "f", "v"]).await;
+        let error =
+            b"-ERR The ID specified in XADD is equal or smaller than the target stream top item\r\n";
+        assert_eq!(client.send(&["XADD", "s", "5-1", "f", "v"]).await, error);
+        assert_eq!(client.send(&["XADD", "s", "4-9", "f", "v"]).await, error);
+        assert_eq!(
+            client.send(&["XADD", "s", "0-0", "f", "v"]).await,
+            b"-ERR The ID specified in XADD must be greater than 0-0\r\n"
+        );
+        assert_eq!(
+            client.send(&["XADD", "s", "abc", "f", "v"]).await,
+            b"-ERR Invalid stream ID specified as stream command argument\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_key_holding_another_type() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["XADD", "k", "1-1", "f", "v"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+        assert_eq!(client.send(&["GET", "k"]).await, b"$1\r\nv\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_keep_expiry_when_appending() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        client.send(&["EXPIRE", "s", "100"]).await;
+        client.send(&["XADD", "s", "1-2", "f", "v"]).await;
+        assert_eq!(client.send(&["TTL", "s"]).await, b":100\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_propagate_entry_with_generated_id() {
+        let mut client = test_helpers::client().await;
+        let mut replica = client.server.lock().await.sender.subscribe();
+        client.send(&["XADD", "s", "5-*", "f", "v"]).await;
+        assert_eq!(
+            replica.recv().await.unwrap().as_bytes(),
+            b"*5\r\n$4\r\nXADD\r\n$1\r\ns\r\n$3\r\n5-0\r\n$1\r\nf\r\n$1\r\nv\r\n"
+        );
+
+        // Rejected entries are not propagated
+        client.send(&["XADD", "s", "5-0", "f", "v"]).await;
+        assert!(replica.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn should_store_entries_readable_by_xrange_and_xread() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        client.send(&["XADD", "s", "2-*", "f", "v"]).await;
 
-    Ok(())
+        // The entries are stored with typed IDs
+        {
+            let db = client.server.lock().await.db.clone();
+            let fields = HashMap::from([("f".to_string(), "v".to_string())]);
+            assert_eq!(
+                db.get(0, &Type::BulkString("s".into())).await,
+                Some(Type::Stream(Stream::new(vec![
+                    (StreamID::from_parts(1, 1), fields.clone()),
+                    (StreamID::from_parts(2, 0), fields),
+                ])))
+            );
+        }
+
+        let entry = b"*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n";
+        let mut expected = b"*1\r\n".to_vec();
+        expected.extend_from_slice(entry);
+        assert_eq!(client.send(&["XRANGE", "s", "2", "+"]).await, expected);
+
+        let mut expected = b"*1\r\n*2\r\n$1\r\ns\r\n*1\r\n".to_vec();
+        expected.extend_from_slice(entry);
+        assert_eq!(
+            client.send(&["XREAD", "STREAMS", "s", "1-1"]).await,
+            expected
+        );
+    }
 }
@@ -0,0 +1,142 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::{stream::StreamID, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// XDEL
+// ----
+
+/// Handles the XDEL command.
+/// The XDEL command removes the entries with the given IDs from the stream stored at the key.
+/// The command is in the format `XDEL key id [id ...]`.
+/// IDs that are not in the stream are ignored, and the stream is kept even once it is empty.
+/// The command returns the number of entries that were removed.
+/// Returns true if any entry was removed, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() < 2 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'XDEL' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Parse the IDs of the entries to remove
+    let ids = match args[1..]
+        .iter()
+        .map(|id| id.as_str().and_then(parse_id))
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(ids) => ids,
+        None => {
+            if role.is_master() {
+                connection
+                    .write_error("ERR Invalid stream ID specified as stream command argument")
+                    .await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Remove the entries from the stream stored at the key
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let removed = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::Stream(stream)) => {
+            let len = stream.entries.len();
+            stream.entries.retain(|(id, _)| !ids.contains(id));
+            len - stream.entries.len()
+        }
+        Some(_) => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+        None => 0,
+    };
+
+    // Respond with the number of removed entries
+    if role.is_master() {
+        let response = Type::Integer(removed as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(removed > 0)
+}
+
+/// Parses an entry ID. The sequence number defaults to 0 if it is omitted.
+fn parse_id(id: &str) -> Option<StreamID> {
+    let (milliseconds, sequence) = id.split_once('-').unwrap_or((id, "0"));
+    Some(StreamID::from_parts(
+        milliseconds.parse().ok()?,
+        sequence.parse().ok()?,
+    ))
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_remove_entries_by_id() {
+        let mut client = test_helpers::client().await;
+        for id in ["1-1", "1-2", "2-1"] {
+            client.send(&["XADD", "s", id, "f", "v"]).await;
+        }
+        assert_eq!(client.send(&["XDEL", "s", "1-2", "9-9"]).await, b":1\r\n");
+        assert_eq!(client.send(&["XLEN", "s"]).await, b":2\r\n");
+        assert_eq!(client.send(&["XDEL", "s", "1-2"]).await, b":0\r\n");
+        assert_eq!(client.send(&["XDEL", "missing", "1-1"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_not_let_ids_go_backwards_after_deleting_newest_entry() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "5-0", "f", "v"]).await;
+        assert_eq!(client.send(&["XDEL", "s", "5-0"]).await, b":1\r\n");
+        assert_eq!(
+            client.send(&["XADD", "s", "3-0", "f", "v"]).await,
+            b"-ERR The ID specified in XADD is equal or smaller than the target stream top item\r\n"
+        );
+        assert_eq!(
+            client.send(&["XADD", "s", "5-*", "f", "v"]).await,
+            b"$3\r\n5-1\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_arguments() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["XDEL", "k", "1-1"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+        assert_eq!(
+            client.send(&["XDEL", "k", "abc"]).await,
+            b"-ERR Invalid stream ID specified as stream command argument\r\n"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+// Library
+use crate::{
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// XLEN
+// ----
+
+/// Handles the XLEN command.
+/// The XLEN command returns the number of entries in the stream stored at the key.
+/// The command is in the format `XLEN key`.
+/// The command returns 0 if the key does not exist.
+/// The command returns an error if the key holds a value that is not a stream.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 1 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'XLEN' command")
+            .await;
+    }
+
+    // Get the number of entries in the stream
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.expect_stream(connection.db, &args[0]) {
+        Ok(Some(stream)) => Type::Integer(stream.entries.len() as i64),
+        Ok(None) => Type::Integer(0),
+        Err(e) => Type::SimpleError(e.to_string()),
+    };
+
+    // Respond with the length
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_stream_length() {
+        let mut client = test_helpers::client().await;
+        for id in ["1-1", "1-2", "2-1"] {
+            client.send(&["XADD", "s", id, "field", "value"]).await;
+        }
+        assert_eq!(client.send(&["XLEN", "s"]).await, b":3\r\n");
+        assert_eq!(client.send(&["XLEN", "missing"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_stream_key() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["XLEN", "k"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
@@ -14,9 +14,10 @@ use tokio::sync::Mutex;
 /// The XRANGE command is used to get a range of entries from a stream.
 /// The command is in the format `XRANGE 'stream' 'start' 'end'`.
 /// Both the start and end values are inclusive.
+/// `-` and `+` stand for the smallest and largest IDs, and the sequence number of an ID may be omitted.
 /// The command returns an array of entries.
 pub async fn command(
-    args: &Vec<Type>,
+    args: &[Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -34,59 +35,63 @@ pub async fn command(
             return connection.write_error("ERR invalid stream name").await;
         }
     };
-    let start = match args.get(2) {
-        Some(Type::BulkString(id)) => match id.as_str() {
-            "-" => StreamID::from_parts(0, 0),
-            _ => StreamID::from_id(&id),
-        },
+    // A start without a sequence number begins at the first sequence of that millisecond
+    let start = match args
+        .get(2)
+        .and_then(Type::as_str)
+        .and_then(|id| parse_bound(id, 0))
+    {
+        Some(id) => id,
         _ => {
             return connection.write_error("ERR invalid start").await;
         }
     };
-    let end = match args.get(3) {
-        Some(Type::BulkString(id)) => match id.as_str() {
-            "+" => StreamID::from_parts(u64::MAX, u64::MAX),
-            _ => StreamID::from_id(&id),
-        },
+    // An end without a sequence number includes every sequence of that millisecond
+    let end = match args
+        .get(3)
+        .and_then(Type::as_str)
+        .and_then(|id| parse_bound(id, u64::MAX))
+    {
+        Some(id) => id,
         _ => {
             return connection.write_error("ERR invalid end").await;
         }
     };
 
-    // Lock the server
-    let s = server.lock().await;
-
     // Get the stream
-    let stream = match s.db.get(name) {
-        Some(Type::Stream(stream)) => stream,
-        _ => {
+    let db = server.lock().await.db.clone();
+    let shard = db.read(name).await;
+    let stream = match shard.expect_stream(connection.db, name) {
+        Ok(Some(stream)) => stream,
+        Ok(None) => {
             return connection.write_error("ERR no such stream").await;
         }
+        Err(e) => return connection.write_error(e.to_string()).await,
     };
 
+    // Stream IDs are compared by their milliseconds first, and then by their sequence number
+    let range = start..=end;
+
     let res: Vec<Type> = stream
+        .entries
         .iter()
         .filter_map(|entry| {
             let id = entry.0.clone();
 
-            println!("{} {} {}", id, start, end);
-
-            if (id.milliseconds >= start.milliseconds && id.sequence >= start.sequence)
-                && (id.milliseconds <= end.milliseconds && id.sequence <= end.sequence)
-            {
+            if range.contains(&id) {
                 let fields = entry
                     .1
                     .iter()
                     .flat_map(|(k, v)| {
                         vec![
-                            Type::BulkString(k.to_string()),
-                            Type::BulkString(v.to_string()),
+                            Type::BulkString(k.to_string().into()),
+                            Type::BulkString(v.to_string().into()),
                         ]
                     })
                     .collect();
 
                 Some(Type::Array(vec![
-                    Type::BulkString(id.to_string()),
+                    Type::BulkString(id.to_string().into()),
                     Type::Array(fields),
                 ]))
             } else {
@@ -103,3 +108,118 @@ pub async fn command(
 
     Ok(())
 }
+
+/// Parses a range bound of the XRANGE command.
+/// `-` and `+` are the smallest and largest possible IDs.
+/// An ID without a sequence number (e.g. `5`) uses the given default sequence number.
+/// Returns `None` if the bound is not a valid ID.
+fn parse_bound(id: &str, default_sequence: u64) -> Option<StreamID> {
+    match id {
+        "-" => Some(StreamID::from_parts(0, 0)),
+        "+" => Some(StreamID::from_parts(u64::MAX, u64::MAX)),
+        _ => match id.split_once('-') {
+            Some((milliseconds, sequence)) => Some(StreamID::from_parts(
+                milliseconds.parse().ok()?,
+                sequence.parse().ok()?,
+            )),
+            None => Some(StreamID::from_parts(id.parse().ok()?, default_sequence)),
+        },
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    /// Adds entries with the given IDs to the stream
+    async fn add(client: &mut test_helpers::Client, ids: &[&str]) {
+        for id in ids {
+            client.send(&["XADD", "s", id, "f", "v"]).await;
+        }
+    }
+
+    /// Returns the expected response for the entries with the given IDs
+    fn entries(ids: &[&str]) -> Vec<u8> {
+        let mut expected = format!("*{}\r\n", ids.len());
+        for id in ids {
+            expected += &format!(
+                "*2\r\n${}\r\n{}\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n",
+                id.len(),
+                id
+            );
+        }
+        expected.into_bytes()
+    }
+
+    #[tokio::test]
+    async fn should_include_entries_after_start_with_smaller_sequence() {
+        let mut client = test_helpers::client().await;
+        add(&mut client, &["4-9", "5-0", "6-3"]).await;
+        assert_eq!(
+            client.send(&["XRANGE", "s", "4-9", "+"]).await,
+            entries(&["4-9", "5-0", "6-3"])
+        );
+        assert_eq!(
+            client.send(&["XRANGE", "s", "4-10", "+"]).await,
+            entries(&["5-0", "6-3"])
+        );
+    }
+
+    #[tokio::test]
+    async fn should_include_entries_before_end_with_larger_sequence() {
+        let mut client = test_helpers::client().await;
+        add(&mut client, &["4-9", "5-0", "6-3"]).await;
+        assert_eq!(
+            client.send(&["XRANGE", "s", "-", "6-2"]).await,
+            entries(&["4-9", "5-0"])
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_full_stream_for_min_and_max_bounds() {
+        let mut client = test_helpers::client().await;
+        add(&mut client, &["1-1", "5-0", "5-7", "9-2"]).await;
+        assert_eq!(
+            client.send(&["XRANGE", "s", "-", "+"]).await,
+            entries(&["1-1", "5-0", "5-7", "9-2"])
+        );
+    }
+
+    #[tokio::test]
+    async fn should_expand_ids_without_sequence() {
+        let mut client = test_helpers::client().await;
+        add(&mut client, &["1-1", "5-0", "5-7", "9-2"]).await;
+        assert_eq!(
+            client.send(&["XRANGE", "s", "5", "+"]).await,
+            entries(&["5-0", "5-7", "9-2"])
+        );
+        assert_eq!(
+            client.send(&["XRANGE", "s", "-", "5"]).await,
+            entries(&["1-1", "5-0", "5-7"])
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_ids() {
+        let mut client = test_helpers::client().await;
+        add(&mut client, &["1-1"]).await;
+        assert_eq!(
+            client.send(&["XRANGE", "s", "abc", "+"]).await,
+            b"-ERR invalid start\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_non_stream_key() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["XRANGE", "k", "-", "+"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
@@ -1,12 +1,16 @@
-use std::sync::Arc;
-
-use tokio::sync::Mutex;
-
 // Library
 use crate::{
+    database::Database,
+    errors::Error,
+    helpers,
     parser::resp::{stream::StreamID, Type},
     server::{connection::Connection, Server},
 };
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::{broadcast::error::RecvError, Mutex},
+    time::timeout_at,
+};
 
 // -----
 // XREAD
@@ -14,8 +18,14 @@ use crate::{
 
 /// Handles the XREAD command.
 /// The XREAD command is used to read data from one or more streams.
+/// The command is in the format `XREAD [BLOCK milliseconds] STREAMS key [key ...] id [id ...]`.
+/// Only the entries with an ID greater than the given ID are returned for each stream,
+/// and the ID `$` stands for the last ID in the stream.
+/// Streams without new entries are left out, and null is returned if there are no new entries at all.
+/// With `BLOCK`, the command waits until an entry is added to one of the streams,
+/// or until the timeout elapses. A timeout of 0 waits forever.
 pub async fn command(
-    args: &Vec<Type>,
+    args: &[Type],
     connection: &mut Connection,
     server: &Arc<Mutex<Server>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -29,127 +39,299 @@ pub async fn command(
 
     let _ = args.next(); // Skip the first argument (XREAD)
 
-    // Error if the first argument is not `streams`
-    let subcommand = match args.next() {
-        Some(Type::BulkString(subcommand)) => subcommand,
-        _ => {
-            return connection.write_error("ERR invalid subcommand").await;
-        }
-    };
-    println!("Subcommand: {:?}", subcommand);
-
-    let blocking_duration = if subcommand.to_uppercase() == "BLOCK" {
-        match args.next() {
-            Some(Type::BulkString(duration)) => duration.parse::<u64>().ok(),
-            _ => {
-                return connection.write_error("ERR invalid duration").await;
-            }
+    // Parse the options up to the `STREAMS` keyword
+    let mut blocking_duration = None;
+    loop {
+        let option = match args.next().and_then(Type::as_str) {
+            Some(option) => option.to_uppercase(),
+            _ => return connection.write_error("ERR syntax error").await,
+        };
+        match option.as_str() {
+            "BLOCK" => match args.next().and_then(Type::as_str) {
+                Some(duration) => match duration.parse::<u64>() {
+                    Ok(duration) => blocking_duration = Some(duration),
+                    Err(_) => {
+                        return connection
+                            .write_error("ERR timeout is not an integer or out of range")
+                            .await;
+                    }
+                },
+                _ => return connection.write_error("ERR syntax error").await,
+            },
+            "STREAMS" => break,
+            _ => return connection.write_error("ERR syntax error").await,
         }
-    } else {
-        None
-    };
-    println!("Blocking duration: {:?}", blocking_duration);
-
-    // If blocking, wait for the specified duration
-    if let Some(duration) = blocking_duration {
-        tokio::time::sleep(tokio::time::Duration::from_millis(duration)).await;
     }
 
-    // Calculate remaining arguments
-    let length_of_remaining_args = args.len();
+    // Every stream must be followed by an ID
+    let remaining: Vec<&Type> = args.collect();
+    if remaining.is_empty() || !remaining.len().is_multiple_of(2) {
+        return connection
+            .write_error("ERR Unbalanced 'xread' list of streams: for each stream key an ID or '$' must be specified.")
+            .await;
+    }
+    let (keys, ids) = remaining.split_at(remaining.len() / 2);
 
-    // Note: Assume the happy path and ignore the case where the number of streams is not even
+    // Subscribe to the stream events before reading, so that no entry added in between is missed
+    let (db, mut events) = {
+        let s = server.lock().await;
+        (s.db.clone(), s.stream_events.subscribe())
+    };
 
-    // Extract the streams and IDs from the arguments
+    // Pair each stream with the ID to read after
     let mut streams = Vec::new();
-    for _ in 0..length_of_remaining_args / 2 {
-        let stream = args.next().unwrap();
-        streams.push(stream);
-    }
-    let mut ids = Vec::new();
-    for _ in 0..length_of_remaining_args / 2 {
-        let id = args.next().unwrap();
-        ids.push(id);
+    for (key, id) in keys.iter().zip(ids) {
+        // Only explicit IDs can be read after, as there is no new entry to generate an ID for
+        let id = match id.as_str() {
+            Some("$") => Some(last_id(&db, connection.db, key).await),
+            Some(id) if !id.contains('*') => StreamID::parse(id, None),
+            _ => None,
+        };
+        let Some(id) = id else {
+            return connection
+                .write_error("ERR Invalid stream ID specified as stream command argument")
+                .await;
+        };
+        streams.push(((*key).clone(), id));
     }
 
-    println!("Streams: {:?}", streams);
-    println!("IDs: {:?}", ids);
+    let entries = match read_streams(&db, connection.db, &streams).await {
+        Ok(entries) => entries,
+        Err(err) => return connection.write_error(err.to_string()).await,
+    };
 
-    // The collection of entries of all the streams
-    let mut entries_of_entries = Vec::new();
+    // Respond right away if there are new entries, or if the command does not block
+    let duration = match blocking_duration {
+        Some(duration) if entries.is_empty() => duration,
+        _ => return write_entries(connection, entries).await,
+    };
 
-    for (stream, id) in streams.iter().zip(ids.iter()) {
-        println!("Stream: {:?}, ID: {:?}", stream, id);
-        let stream = match stream {
-            Type::BulkString(stream) => stream,
-            _ => {
-                return connection.write_error("ERR invalid stream name").await;
-            }
+    // Wait for entries to be added to one of the streams
+    // A timeout too large to represent as a deadline is as good as waiting forever
+    let deadline = match duration {
+        0 => None,
+        duration => helpers::deadline(Duration::from_millis(duration)),
+    };
+    loop {
+        let event = match deadline {
+            Some(deadline) => match timeout_at(deadline, events.recv()).await {
+                Ok(event) => event,
+                Err(_) => break, // Timed out
+            },
+            None => events.recv().await,
         };
-
-        let id = match id {
-            Type::BulkString(id) => StreamID::from_id(&id),
-            _ => {
-                return connection.write_error("ERR invalid ID").await;
+        match event {
+            // Ignore entries added to other streams
+            Ok((db, key)) if db != connection.db || !streams.iter().any(|(k, _)| *k == key) => {
+                continue
             }
-        };
-
-        let key = Type::BulkString(stream.clone());
-        let entries = match xread(server, &key, connection, id).await {
-            Ok(value) => value,
-            Err(value) => return value,
-        };
+            Err(RecvError::Closed) => break,
+            // Either an entry was added to one of the streams, or some events were missed
+            _ => {}
+        }
 
-        entries_of_entries.push(entries);
+        match read_streams(&db, connection.db, &streams).await {
+            Ok(entries) if entries.is_empty() => continue,
+            Ok(entries) => return write_entries(connection, entries).await,
+            Err(err) => return connection.write_error(err.to_string()).await,
+        }
     }
 
-    // Write the entries to the client
-    let response = Type::Array(entries_of_entries);
+    // Respond with null as no entries were added in time
+    connection.write_all(&Type::Null.as_bytes()).await
+}
+
+/// Returns the last ID of the stream, or `0-0` if the stream does not exist
+async fn last_id(database: &Database, db: usize, key: &Type) -> StreamID {
+    match database.read(key).await.get(db, key) {
+        Some(Type::Stream(stream)) => stream.last_id.clone(),
+        _ => StreamID::from_parts(0, 0),
+    }
+}
 
-    // println!("Response: {:?}", response);
+/// Collects the entries after the given ID for each of the streams.
+/// Streams without new entries (including streams that do not exist) are left out.
+async fn read_streams(
+    database: &Database,
+    db: usize,
+    streams: &[(Type, StreamID)],
+) -> Result<Vec<Type>, Error> {
+    let mut result = Vec::new();
+    for (key, id) in streams {
+        let shard = database.read(key).await;
+        let Some(stream) = shard.expect_stream(db, key)? else {
+            continue;
+        };
 
-    connection.write_all(&response.as_bytes()).await?;
+        let entries = stream
+            .entries
+            .iter()
+            // Only the entries strictly after the given ID are returned
+            .filter(|entry| entry.0 > *id)
+            .map(|(id, fields)| {
+                let fields = fields
+                    .iter()
+                    .flat_map(|(k, v)| {
+                        vec![
+                            Type::BulkString(k.clone().into()),
+                            Type::BulkString(v.clone().into()),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                Type::Array(vec![
+                    Type::BulkString(id.to_string().into()),
+                    Type::Array(fields),
+                ])
+            })
+            .collect::<Vec<_>>();
 
-    Ok(())
+        if !entries.is_empty() {
+            result.push(Type::Array(vec![key.clone(), Type::Array(entries)]));
+        }
+    }
+    Ok(result)
 }
 
-async fn xread(
-    server: &Arc<Mutex<Server>>,
-    key: &Type,
+/// Writes the entries of the streams to the client, or null if there are none
+async fn write_entries(
     connection: &mut Connection,
-    id: StreamID,
-) -> Result<Type, Result<(), Box<dyn std::error::Error>>> {
-    let s = server.lock().await;
-
-    let stream = match s.db.get(key) {
-        Some(Type::Stream(stream)) => stream,
-        _ => {
-            return Err(connection.write_error("ERR no such stream").await);
-        }
+    entries: Vec<Type>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let response = match entries.is_empty() {
+        true => Type::Null,
+        false => Type::Array(entries),
     };
+    connection.write_all(&response.as_bytes()).await
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+    use std::time::{Duration, Instant};
+
+    /// Returns the expected response for the stream `s` with the entries with the given IDs
+    fn response(ids: &[&str]) -> Vec<u8> {
+        let mut expected = format!("*1\r\n*2\r\n$1\r\ns\r\n*{}\r\n", ids.len());
+        for id in ids {
+            expected += &format!(
+                "*2\r\n${}\r\n{}\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n",
+                id.len(),
+                id
+            );
+        }
+        expected.into_bytes()
+    }
 
-    let entries = stream
-        .iter()
-        .filter_map(|entry| {
-            if entry.0.milliseconds >= id.milliseconds && entry.0.sequence >= id.sequence {
-                Some(entry)
-            } else {
-                None
+    #[tokio::test]
+    async fn should_only_return_entries_after_id() {
+        let mut client = test_helpers::client().await;
+        for id in ["4-9", "5-0", "5-1"] {
+            client.send(&["XADD", "s", id, "f", "v"]).await;
+        }
+        assert_eq!(
+            client.send(&["XREAD", "STREAMS", "s", "0-0"]).await,
+            response(&["4-9", "5-0", "5-1"])
+        );
+        assert_eq!(
+            client.send(&["XREAD", "STREAMS", "s", "4-9"]).await,
+            response(&["5-0", "5-1"])
+        );
+        assert_eq!(
+            client.send(&["XREAD", "STREAMS", "s", "5-1"]).await,
+            b"$-1\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_id() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        for id in ["garbage", "1-x", "1-*", "*"] {
+            assert_eq!(
+                client.send(&["XREAD", "STREAMS", "s", id]).await,
+                b"-ERR Invalid stream ID specified as stream command argument\r\n"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn should_wake_blocked_read_when_entry_is_added() {
+        let mut reader = test_helpers::client().await;
+        let mut writer = test_helpers::client_for(&reader.server).await;
+        let (read, _) = tokio::join!(
+            reader.send(&["XREAD", "BLOCK", "0", "STREAMS", "s", "$"]),
+            async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                writer.send(&["XADD", "other", "1-1", "f", "v"]).await;
+                writer.send(&["XADD", "s", "2-1", "f", "v"]).await
             }
-        })
-        .flat_map(|entry| {
-            let id = entry.0.clone();
-            let fields = entry
-                .1
-                .iter()
-                .flat_map(|(k, v)| vec![Type::BulkString(k.clone()), Type::BulkString(v.clone())])
-                .collect::<Vec<_>>();
-            vec![Type::Array(vec![
-                Type::BulkString(id.to_string()),
-                Type::Array(fields),
-            ])]
-        })
-        .collect::<Vec<_>>();
-
-    Ok(Type::Array(vec![key.clone(), Type::Array(entries)]))
+        );
+        assert_eq!(read, response(&["2-1"]));
+    }
+
+    #[tokio::test]
+    async fn should_block_with_huge_timeout_until_entry_is_added() {
+        let mut reader = test_helpers::client().await;
+        let mut writer = test_helpers::client_for(&reader.server).await;
+        let (read, _) = tokio::join!(
+            reader.send(&[
+                "XREAD",
+                "BLOCK",
+                "18446744073709551615",
+                "STREAMS",
+                "s",
+                "$"
+            ]),
+            async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                writer.send(&["XADD", "s", "2-1", "f", "v"]).await
+            }
+        );
+        assert_eq!(read, response(&["2-1"]));
+    }
+
+    #[tokio::test]
+    async fn should_ignore_existing_entries_for_dollar_id() {
+        let mut reader = test_helpers::client().await;
+        let mut writer = test_helpers::client_for(&reader.server).await;
+        reader.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        let (read, _) = tokio::join!(
+            reader.send(&["XREAD", "BLOCK", "0", "STREAMS", "s", "$"]),
+            async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                writer.send(&["XADD", "s", "1-2", "f", "v"]).await
+            }
+        );
+        assert_eq!(read, response(&["1-2"]));
+    }
+
+    #[tokio::test]
+    async fn should_return_null_when_block_times_out() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        let started = Instant::now();
+        assert_eq!(
+            client
+                .send(&["XREAD", "BLOCK", "50", "STREAMS", "s", "1-1"])
+                .await,
+            b"$-1\r\n"
+        );
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn should_not_block_when_entries_are_available() {
+        let mut client = test_helpers::client().await;
+        client.send(&["XADD", "s", "1-1", "f", "v"]).await;
+        assert_eq!(
+            client
+                .send(&["XREAD", "BLOCK", "0", "STREAMS", "s", "0-0"])
+                .await,
+            response(&["1-1"])
+        );
+    }
 }
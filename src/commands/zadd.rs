@@ -0,0 +1,167 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// ZADD
+// ----
+
+/// Handles the ZADD command.
+/// The ZADD command adds the specified members, with their scores, to the sorted set stored at the key.
+/// The command is in the format `ZADD key score member [score member ...]`.
+/// The score of a member that is already part of the sorted set is updated, moving the member to its new position.
+/// If the key does not exist, a new sorted set is created.
+/// The command returns the number of members that were newly added to the sorted set.
+/// Returns true if the members were added or updated, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() < 3 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'ZADD' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+    if args.len().is_multiple_of(2) {
+        if role.is_master() {
+            connection.write_error("ERR syntax error").await?;
+        }
+        return Ok(false);
+    }
+
+    // Validate all the scores before changing the sorted set
+    let mut members = Vec::new();
+    for pair in args[1..].chunks(2) {
+        let (Some(score), Some(member)) = (parse_score(&pair[0]), pair[1].as_str()) else {
+            if role.is_master() {
+                connection
+                    .write_error("ERR value is not a valid float")
+                    .await?;
+            }
+            return Ok(false);
+        };
+        members.push((member.to_string(), score));
+    }
+
+    // Get the sorted set stored at the key, creating it if it does not exist
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    if shard.get(connection.db, &args[0]).is_none() {
+        shard.set(
+            connection.db,
+            args[0].clone(),
+            Type::SortedSet(Vec::new()),
+            None,
+        );
+    }
+    let set = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::SortedSet(set)) => set,
+        _ => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+    };
+
+    // Add the members, counting the ones that were not already present
+    let added = members
+        .into_iter()
+        .filter(|(member, score)| insert(set, member, *score))
+        .count();
+
+    // Respond with the number of added members
+    if role.is_master() {
+        let response = Type::Integer(added as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(true)
+}
+
+/// Parses a score, accepting `inf` and `-inf` but rejecting NaN
+pub fn parse_score(value: &Type) -> Option<f64> {
+    value
+        .as_str()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|score| !score.is_nan())
+}
+
+/// Inserts the member into the sorted set at the position given by its score, replacing its old score.
+/// Returns true if the member was not already part of the sorted set.
+fn insert(set: &mut Vec<(String, f64)>, member: &str, score: f64) -> bool {
+    let existing = set.iter().position(|(m, _)| m == member);
+    if let Some(index) = existing {
+        set.remove(index);
+    }
+    let index = set.partition_point(|(m, s)| (*s, m.as_str()) < (score, member));
+    set.insert(index, (member.to_string(), score));
+    existing.is_none()
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_count_new_members_only() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["ZADD", "z", "1", "a", "2", "b"]).await,
+            b":2\r\n"
+        );
+        assert_eq!(
+            client.send(&["ZADD", "z", "5", "a", "3", "c"]).await,
+            b":1\r\n"
+        );
+        assert_eq!(client.send(&["ZSCORE", "z", "a"]).await, b"$1\r\n5\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_scores() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["ZADD", "z", "abc", "a"]).await,
+            b"-ERR value is not a valid float\r\n"
+        );
+        assert_eq!(
+            client.send(&["ZADD", "z", "nan", "a"]).await,
+            b"-ERR value is not a valid float\r\n"
+        );
+        assert_eq!(
+            client.send(&["ZADD", "z", "1", "a", "2"]).await,
+            b"-ERR syntax error\r\n"
+        );
+        assert_eq!(client.send(&["EXISTS", "z"]).await, b":0\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_reject_wrong_type() {
+        let mut client = test_helpers::client().await;
+        client.send(&["SET", "k", "v"]).await;
+        assert_eq!(
+            client.send(&["ZADD", "k", "1", "a"]).await,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+}
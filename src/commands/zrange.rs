@@ -0,0 +1,125 @@
+// Library
+use crate::{
+    commands::lrange,
+    database::WrongType,
+    errors::Error,
+    parser::resp::{self, types::format_double, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// ZRANGE
+// ------
+
+/// Handles the ZRANGE command.
+/// The ZRANGE command returns the members of the sorted set stored at the key within the given range of ranks.
+/// The command is in the format `ZRANGE key start stop [WITHSCORES]`.
+/// The members are ordered by score, with members of equal score ordered lexically.
+/// The indices work as in LRANGE: they are inclusive, and negative indices count from the end.
+/// With `WITHSCORES`, every member is followed by its score.
+/// The command returns an empty array if the key does not exist or the range is empty.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() < 3 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'ZRANGE' command")
+            .await;
+    }
+
+    // Extract the start and stop indices from the arguments
+    let (start, stop) = match (
+        args[1].as_str().and_then(|start| start.parse::<i64>().ok()),
+        args[2].as_str().and_then(|stop| stop.parse::<i64>().ok()),
+    ) {
+        (Some(start), Some(stop)) => (start, stop),
+        _ => {
+            return connection.write_error(Error::NotInteger.to_string()).await;
+        }
+    };
+
+    // Parse the options
+    let with_scores = match &args[3..] {
+        [] => false,
+        [option] if option.as_str().map(str::to_uppercase).as_deref() == Some("WITHSCORES") => true,
+        _ => return connection.write_error("ERR syntax error").await,
+    };
+
+    // Get the sorted set stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let set = match shard.get(connection.db, &args[0]) {
+        Some(Type::SortedSet(set)) => set.as_slice(),
+        Some(_) => {
+            return connection.write_error(WrongType.to_string()).await;
+        }
+        None => &[],
+    };
+
+    // Respond with the members in the range
+    let members = match lrange::range(set.len(), start, stop) {
+        Some((start, stop)) => set[start..=stop]
+            .iter()
+            .flat_map(|(member, score)| {
+                let mut items = vec![resp::bulk_string(member)];
+                if with_scores {
+                    items.push(resp::bulk_string(&format_double(*score)));
+                }
+                items
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    let response = resp::array(members);
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_order_tied_scores_lexically() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["ZADD", "z", "2", "c", "1", "b", "1", "a", "0", "d"])
+            .await;
+        assert_eq!(
+            client.send(&["ZRANGE", "z", "0", "-1"]).await,
+            b"*4\r\n$1\r\nd\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nc\r\n"
+        );
+        assert_eq!(
+            client.send(&["ZRANGE", "z", "-2", "-1"]).await,
+            b"*2\r\n$1\r\nb\r\n$1\r\nc\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_return_scores_with_withscores() {
+        let mut client = test_helpers::client().await;
+        client.send(&["ZADD", "z", "1.5", "a", "2", "b"]).await;
+        assert_eq!(
+            client.send(&["ZRANGE", "z", "0", "-1", "withscores"]).await,
+            b"*4\r\n$1\r\na\r\n$3\r\n1.5\r\n$1\r\nb\r\n$1\r\n2\r\n"
+        );
+        assert_eq!(
+            client.send(&["ZRANGE", "z", "0", "-1", "BYSCORE"]).await,
+            b"-ERR syntax error\r\n"
+        );
+        assert_eq!(
+            client.send(&["ZRANGE", "missing", "0", "-1"]).await,
+            b"*0\r\n"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+// Library
+use crate::{
+    database::WrongType,
+    errors::Error,
+    parser::resp::{self, types::format_double, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -------------
+// ZRANGEBYSCORE
+// -------------
+
+/// Handles the ZRANGEBYSCORE command.
+/// The ZRANGEBYSCORE command returns the members of the sorted set stored at the key with a score between `min` and `max`.
+/// The command is in the format `ZRANGEBYSCORE key min max [WITHSCORES] [LIMIT offset count]`.
+/// The bounds are inclusive, unless prefixed with `(`, and may be `-inf` or `+inf`.
+/// The members are returned in ascending score order. With `WITHSCORES`, every member is followed by its score.
+/// `LIMIT` skips the first `offset` matching members and returns at most `count` of them
+/// (a negative `count` returns all the remaining members).
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() < 3 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'ZRANGEBYSCORE' command")
+            .await;
+    }
+
+    // Extract the bounds from the arguments
+    let (min, max) = match (
+        args[1].as_str().and_then(parse_bound),
+        args[2].as_str().and_then(parse_bound),
+    ) {
+        (Some(min), Some(max)) => (min, max),
+        _ => {
+            return connection
+                .write_error("ERR min or max is not a float")
+                .await;
+        }
+    };
+
+    // Parse the options
+    let mut with_scores = false;
+    let mut limit = None;
+    let mut options = args[3..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str().map(str::to_uppercase).as_deref() {
+            Some("WITHSCORES") => with_scores = true,
+            Some("LIMIT") => {
+                let (Some(offset), Some(count)) = (options.next(), options.next()) else {
+                    return connection.write_error("ERR syntax error").await;
+                };
+                match (
+                    offset
+                        .as_str()
+                        .and_then(|offset| offset.parse::<i64>().ok()),
+                    count.as_str().and_then(|count| count.parse::<i64>().ok()),
+                ) {
+                    (Some(offset), Some(count)) => limit = Some((offset, count)),
+                    _ => {
+                        return connection.write_error(Error::NotInteger.to_string()).await;
+                    }
+                }
+            }
+            _ => return connection.write_error("ERR syntax error").await,
+        }
+    }
+
+    // Get the sorted set stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let set = match shard.get(connection.db, &args[0]) {
+        Some(Type::SortedSet(set)) => set.as_slice(),
+        Some(_) => {
+            return connection.write_error(WrongType.to_string()).await;
+        }
+        None => &[],
+    };
+
+    // Collect the members within the bounds, applying the limit
+    let (offset, count) = match limit {
+        Some((offset, _)) if offset < 0 => (0, 0),
+        Some((offset, count)) if count >= 0 => (offset as usize, count as usize),
+        Some((offset, _)) => (offset as usize, usize::MAX),
+        None => (0, usize::MAX),
+    };
+    let members = set
+        .iter()
+        .filter(|(_, score)| above(*score, min) && below(*score, max))
+        .skip(offset)
+        .take(count)
+        .flat_map(|(member, score)| {
+            let mut items = vec![resp::bulk_string(member)];
+            if with_scores {
+                items.push(resp::bulk_string(&format_double(*score)));
+            }
+            items
+        })
+        .collect();
+
+    // Respond with the members
+    let response = resp::array(members);
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+/// Parses a score bound, which is exclusive if prefixed with `(`.
+/// Returns the score and whether the bound is exclusive.
+fn parse_bound(bound: &str) -> Option<(f64, bool)> {
+    let (bound, exclusive) = match bound.strip_prefix('(') {
+        Some(bound) => (bound, true),
+        None => (bound, false),
+    };
+    let score = bound.parse::<f64>().ok().filter(|score| !score.is_nan())?;
+    Some((score, exclusive))
+}
+
+/// Returns true if the score is above the lower bound
+fn above(score: f64, (min, exclusive): (f64, bool)) -> bool {
+    if exclusive {
+        score > min
+    } else {
+        score >= min
+    }
+}
+
+/// Returns true if the score is below the upper bound
+fn below(score: f64, (max, exclusive): (f64, bool)) -> bool {
+    if exclusive {
+        score < max
+    } else {
+        score <= max
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_members_within_bounds() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["ZADD", "z", "1", "a", "5", "b", "7", "c", "10", "d"])
+            .await;
+        assert_eq!(
+            client.send(&["ZRANGEBYSCORE", "z", "-inf", "+inf"]).await,
+            b"*4\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nc\r\n$1\r\nd\r\n"
+        );
+        assert_eq!(
+            client
+                .send(&["ZRANGEBYSCORE", "z", "5", "7", "WITHSCORES"])
+                .await,
+            b"*4\r\n$1\r\nb\r\n$1\r\n5\r\n$1\r\nc\r\n$1\r\n7\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_exclude_exact_match_with_exclusive_bound() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["ZADD", "z", "1", "a", "5", "b", "7", "c", "10", "d"])
+            .await;
+        assert_eq!(
+            client.send(&["ZRANGEBYSCORE", "z", "(5", "10"]).await,
+            b"*2\r\n$1\r\nc\r\n$1\r\nd\r\n"
+        );
+        assert_eq!(
+            client.send(&["ZRANGEBYSCORE", "z", "(1", "(10"]).await,
+            b"*2\r\n$1\r\nb\r\n$1\r\nc\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_page_results_with_limit() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["ZADD", "z", "1", "a", "2", "b", "3", "c", "4", "d"])
+            .await;
+        assert_eq!(
+            client
+                .send(&["ZRANGEBYSCORE", "z", "-inf", "+inf", "LIMIT", "0", "2"])
+                .await,
+            b"*2\r\n$1\r\na\r\n$1\r\nb\r\n"
+        );
+        assert_eq!(
+            client
+                .send(&["ZRANGEBYSCORE", "z", "-inf", "+inf", "LIMIT", "2", "2"])
+                .await,
+            b"*2\r\n$1\r\nc\r\n$1\r\nd\r\n"
+        );
+        assert_eq!(
+            client
+                .send(&["ZRANGEBYSCORE", "z", "2", "+inf", "LIMIT", "1", "-1"])
+                .await,
+            b"*2\r\n$1\r\nc\r\n$1\r\nd\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_bounds() {
+        let mut client = test_helpers::client().await;
+        assert_eq!(
+            client.send(&["ZRANGEBYSCORE", "z", "abc", "1"]).await,
+            b"-ERR min or max is not a float\r\n"
+        );
+        assert_eq!(
+            client
+                .send(&["ZRANGEBYSCORE", "z", "0", "1", "LIMIT", "0"])
+                .await,
+            b"-ERR syntax error\r\n"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// -----
+// ZRANK
+// -----
+
+/// Handles the ZRANK command.
+/// The ZRANK command returns the rank of the member in the sorted set stored at the key.
+/// The command is in the format `ZRANK key member`.
+/// The rank is the zero-based position of the member when ordered by score, from lowest to highest.
+/// The command returns a null if the member or the key does not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'ZRANK' command")
+            .await;
+    }
+
+    // Find the position of the member in the sorted set stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::SortedSet(set)) => set
+            .iter()
+            .position(|(member, _)| Some(member.as_str()) == args[1].as_str())
+            .map(|rank| Type::Integer(rank as i64))
+            .unwrap_or(Type::Null),
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => Type::Null,
+    };
+
+    // Respond with the rank
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_rank_of_member() {
+        let mut client = test_helpers::client().await;
+        client
+            .send(&["ZADD", "z", "3", "c", "1", "a", "2", "b"])
+            .await;
+        assert_eq!(client.send(&["ZRANK", "z", "a"]).await, b":0\r\n");
+        assert_eq!(client.send(&["ZRANK", "z", "c"]).await, b":2\r\n");
+        assert_eq!(client.send(&["ZRANK", "z", "x"]).await, b"$-1\r\n");
+        assert_eq!(client.send(&["ZRANK", "missing", "a"]).await, b"$-1\r\n");
+    }
+}
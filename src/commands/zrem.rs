@@ -0,0 +1,91 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::Type,
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ----
+// ZREM
+// ----
+
+/// Handles the ZREM command.
+/// The ZREM command removes the specified members from the sorted set stored at the key.
+/// The command is in the format `ZREM key member [member ...]`.
+/// Members that are not part of the sorted set are ignored, and the key is deleted once the sorted set is empty.
+/// The command returns the number of members that were actually removed.
+/// Returns true if any member was removed, in which case the command should be propagated to replicas.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Get the role of the server
+    let role = {
+        let server = server.lock().await;
+        server.role.clone()
+    };
+
+    // Check the number of arguments
+    if args.len() < 2 {
+        if role.is_master() {
+            connection
+                .write_error("ERR wrong number of arguments for 'ZREM' command")
+                .await?;
+        }
+        return Ok(false);
+    }
+
+    // Remove the members from the sorted set stored at the key
+    let db = server.lock().await.db.clone();
+    let mut shard = db.write(&args[0]).await;
+    let removed = match shard.get_mut(connection.db, &args[0]) {
+        Some(Type::SortedSet(set)) => {
+            let members: Vec<&str> = args[1..].iter().filter_map(Type::as_str).collect();
+            let len = set.len();
+            set.retain(|(member, _)| !members.contains(&member.as_str()));
+            let removed = len - set.len();
+            if set.is_empty() {
+                shard.remove(connection.db, &args[0]);
+            }
+            removed
+        }
+        Some(_) => {
+            if role.is_master() {
+                connection.write_error(WrongType.to_string()).await?;
+            }
+            return Ok(false);
+        }
+        None => 0,
+    };
+
+    // Respond with the number of removed members
+    if role.is_master() {
+        let response = Type::Integer(removed as i64);
+        connection.write_all(&response.as_bytes()).await?;
+    }
+
+    Ok(removed > 0)
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_remove_members_and_delete_empty_key() {
+        let mut client = test_helpers::client().await;
+        client.send(&["ZADD", "z", "1", "a", "2", "b"]).await;
+        assert_eq!(client.send(&["ZREM", "z", "a", "x"]).await, b":1\r\n");
+        assert_eq!(client.send(&["ZRANK", "z", "b"]).await, b":0\r\n");
+        assert_eq!(client.send(&["ZREM", "z", "b"]).await, b":1\r\n");
+        assert_eq!(client.send(&["EXISTS", "z"]).await, b":0\r\n");
+        assert_eq!(client.send(&["ZREM", "missing", "a"]).await, b":0\r\n");
+    }
+}
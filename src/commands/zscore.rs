@@ -0,0 +1,66 @@
+// Library
+use crate::{
+    database::WrongType,
+    parser::resp::{self, types::format_double, Type},
+    server::{connection::Connection, Server},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// ------
+// ZSCORE
+// ------
+
+/// Handles the ZSCORE command.
+/// The ZSCORE command returns the score of the member in the sorted set stored at the key.
+/// The command is in the format `ZSCORE key member`.
+/// The score is returned as a bulk string, or a null if the member or the key does not exist.
+pub async fn command(
+    args: &[Type],
+    connection: &mut Connection,
+    server: &Arc<Mutex<Server>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check the number of arguments
+    if args.len() != 2 {
+        return connection
+            .write_error("ERR wrong number of arguments for 'ZSCORE' command")
+            .await;
+    }
+
+    // Look up the score of the member in the sorted set stored at the key
+    let db = server.lock().await.db.clone();
+    let shard = db.read(&args[0]).await;
+    let response = match shard.get(connection.db, &args[0]) {
+        Some(Type::SortedSet(set)) => set
+            .iter()
+            .find(|(member, _)| Some(member.as_str()) == args[1].as_str())
+            .map(|(_, score)| resp::bulk_string(&format_double(*score)))
+            .unwrap_or(Type::Null),
+        Some(_) => Type::SimpleError(WrongType.to_string()),
+        None => Type::Null,
+    };
+
+    // Respond with the score
+    connection.write_all(&response.as_bytes()).await?;
+
+    Ok(())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_return_score_of_member() {
+        let mut client = test_helpers::client().await;
+        client.send(&["ZADD", "z", "1.5", "a", "-inf", "b"]).await;
+        assert_eq!(client.send(&["ZSCORE", "z", "a"]).await, b"$3\r\n1.5\r\n");
+        assert_eq!(client.send(&["ZSCORE", "z", "b"]).await, b"$4\r\n-inf\r\n");
+        assert_eq!(client.send(&["ZSCORE", "z", "x"]).await, b"$-1\r\n");
+        assert_eq!(client.send(&["ZSCORE", "missing", "a"]).await, b"$-1\r\n");
+    }
+}
@@ -1,22 +1,22 @@
-/// Configuration module for the application.
-/// The configuration can be parsed from the command-line arguments.
-/// The configuration includes the port the server will listen on and the replica-of address.
-/// If the replica-of address is set, the server will act as a replica of the given address.
-///
-/// To parse the configuration from the command-line arguments, use the `from_command_line` function.
-/// The function returns a `Result` with the `Config` struct or an error message.
-/// ```rs
-/// use config::{Config, from_command_line};
-///
-/// let args: Vec<String> = std::env::args().collect(); // Get the command-line arguments
-/// let config = from_command_line(args).expect("Failed to parse command-line arguments"); // Parse the configuration
-///
-/// // Print the configuration values
-/// println!("Port: {}", config.port);
-/// if let Some(replicaof) = &config.replicaof {
-///    println!("Replica-of: {}", replicaof);
-/// }
-/// ```
+//! Configuration module for the application.
+//! The configuration can be parsed from the command-line arguments.
+//! The configuration includes the port the server will listen on and the replica-of address.
+//! If the replica-of address is set, the server will act as a replica of the given address.
+//!
+//! To parse the configuration from the command-line arguments, use the `from_command_line` function.
+//! The function returns a `Result` with the `Config` struct or an error message.
+//! ```rs
+//! use config::{Config, from_command_line};
+//!
+//! let args: Vec<String> = std::env::args().collect(); // Get the command-line arguments
+//! let config = from_command_line(args).expect("Failed to parse command-line arguments"); // Parse the configuration
+//!
+//! // Print the configuration values
+//! println!("Port: {}", config.port);
+//! if let Some(replicaof) = &config.replicaof {
+//!    println!("Replica-of: {}", replicaof);
+//! }
+//! ```
 // -------------
 // CONFIGURATION
 // -------------
@@ -24,6 +24,12 @@
 /// The default port the server will listen on.
 const DEFAULT_PORT: u16 = 6379;
 
+/// The default number of bytes read from a connection at a time.
+pub const DEFAULT_BUFFER_SIZE: usize = 16 * 1024;
+
+/// The default number of milliseconds between the sweeps for expired keys.
+pub const DEFAULT_EXPIRY_INTERVAL: u64 = 100;
+
 /// Configuration for the application.
 pub struct Config {
     /// The port the server will listen on. (Defaults to 6379)
@@ -38,6 +44,17 @@ pub struct Config {
 
     /// The filename of the database file.
     pub dbfilename: Option<String>,
+
+    /// The initial size (in bytes) of the buffer used to read from each connection.
+    /// Commands larger than the buffer are still accepted, as they are accumulated across reads.
+    pub buffer_size: usize,
+
+    /// The number of milliseconds between the background sweeps that evict expired keys.
+    pub expiry_interval: u64,
+
+    /// The number of seconds after which an idle client connection is closed.
+    /// A timeout of 0 (the default) keeps idle connections open forever.
+    pub timeout: u64,
 }
 
 /// Default implementation for the Config struct.
@@ -54,6 +71,9 @@ impl Default for Config {
                     .into_owned(),
             ), // Set the current directory as the default directory for the database files.
             dbfilename: Some("rdb.dump".into()), // Default filename for the database file.
+            buffer_size: DEFAULT_BUFFER_SIZE, // Default size of the connection read buffer.
+            expiry_interval: DEFAULT_EXPIRY_INTERVAL, // Default interval between expiry sweeps.
+            timeout: 0,      // Idle connections are never closed by default. Same as Redis.
         }
     }
 }
@@ -61,13 +81,13 @@ impl Default for Config {
 /// Parses the Configuration from the command-line arguments.
 pub fn from_command_line(args: Vec<String>) -> Result<Config, Box<dyn std::error::Error>> {
     let mut config = Config::default(); // Initialize the configuration with the default values
-    config.from_command_line(args)?; // Parse the configuration from the command-line arguments
+    config.parse_command_line(args)?; // Parse the configuration from the command-line arguments
     Ok(config) // Return the configuration
 }
 
 impl Config {
     /// Parses the Configuration from the command-line arguments.
-    fn from_command_line(&mut self, args: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+    fn parse_command_line(&mut self, args: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
         // Iterate over the arguments...
         for i in 0..args.len() {
             match args[i].as_str() {
@@ -83,6 +103,15 @@ impl Config {
                 // If the argument is a dbfilename flag, parse the dbfilename
                 "--dbfilename" => self.parse_dbfilename(&args, i)?,
 
+                // If the argument is a buffer-size flag, parse the buffer size
+                "--buffer-size" => self.parse_buffer_size(&args, i)?,
+
+                // If the argument is an expiry-interval flag, parse the expiry interval
+                "--expiry-interval" => self.parse_expiry_interval(&args, i)?,
+
+                // If the argument is a timeout flag, parse the idle timeout
+                "--timeout" => self.parse_timeout(&args, i)?,
+
                 _ => {} // Ignore any other arguments
             }
         }
@@ -147,11 +176,7 @@ impl Config {
     /// Parses the directory from the command-line arguments.
     /// The directory must be specified in the format `--dir 'path'`.
     /// The directory is where the server will store the rdb database files.
-    fn parse_dir(
-        &mut self,
-        args: &Vec<String>,
-        i: usize,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    fn parse_dir(&mut self, args: &[String], i: usize) -> Result<(), Box<dyn std::error::Error>> {
         // Check if there is a value after the flag...
         if i + 1 < args.len() {
             // ...and if there is, set it as the directory
@@ -172,7 +197,7 @@ impl Config {
     /// The dbfilename is the name of the rdb database file.
     pub fn parse_dbfilename(
         &mut self,
-        args: &Vec<String>,
+        args: &[String],
         i: usize,
     ) -> Result<(), Box<dyn std::error::Error>> {
         // Check if there is a value after the flag...
@@ -186,6 +211,78 @@ impl Config {
         }
         Ok(())
     }
+
+    // BUFFER SIZE
+    // -----------
+
+    /// Parses the connection buffer size from the command-line arguments.
+    /// The buffer size must be specified in the format `--buffer-size 4096`.
+    fn parse_buffer_size(
+        &mut self,
+        args: &[String],
+        i: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Check if there is a value after the flag...
+        if i + 1 < args.len() {
+            // ...and if there is, parse it as a positive number of bytes
+            self.buffer_size = match args[i + 1].parse::<usize>() {
+                Ok(size) if size > 0 => size,
+                _ => Err("Invalid buffer size value")?,
+            }
+        } else {
+            // ...otherwise, print an error message
+            Err("No buffer size provided after the flag")?;
+        }
+        Ok(())
+    }
+
+    // EXPIRY INTERVAL
+    // ---------------
+
+    /// Parses the interval between the expiry sweeps from the command-line arguments.
+    /// The interval must be specified in milliseconds, in the format `--expiry-interval 100`.
+    fn parse_expiry_interval(
+        &mut self,
+        args: &[String],
+        i: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Check if there is a value after the flag...
+        if i + 1 < args.len() {
+            // ...and if there is, parse it as a positive number of milliseconds
+            self.expiry_interval = match args[i + 1].parse::<u64>() {
+                Ok(interval) if interval > 0 => interval,
+                _ => Err("Invalid expiry interval value")?,
+            }
+        } else {
+            // ...otherwise, print an error message
+            Err("No expiry interval provided after the flag")?;
+        }
+        Ok(())
+    }
+
+    // TIMEOUT
+    // -------
+
+    /// Parses the idle timeout of client connections from the command-line arguments.
+    /// The timeout must be specified in seconds, in the format `--timeout 300` (0 disables it).
+    fn parse_timeout(
+        &mut self,
+        args: &[String],
+        i: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Check if there is a value after the flag...
+        if i + 1 < args.len() {
+            // ...and if there is, parse it as a number of seconds
+            self.timeout = match args[i + 1].parse::<u64>() {
+                Ok(timeout) => timeout,
+                _ => Err("Invalid timeout value")?,
+            }
+        } else {
+            // ...otherwise, print an error message
+            Err("No timeout provided after the flag")?;
+        }
+        Ok(())
+    }
 }
 
 // -----
@@ -252,6 +349,42 @@ mod tests {
         assert_eq!(cli.dbfilename, Some("dump.rdb".into()));
     }
 
+    #[test]
+    fn should_parse_buffer_size() {
+        let cli = from_command_line(vec![]).unwrap();
+        assert_eq!(cli.buffer_size, DEFAULT_BUFFER_SIZE);
+        let args: Vec<String> = vec!["--buffer-size".into(), "4096".into()];
+        let cli = from_command_line(args).unwrap();
+        assert_eq!(cli.buffer_size, 4096);
+    }
+
+    #[test]
+    fn should_error_if_invalid_buffer_size() {
+        let args: Vec<String> = vec!["--buffer-size".into(), "0".into()];
+        match from_command_line(args) {
+            Ok(_) => panic!("Should have errored"),
+            Err(e) => assert_eq!(e.to_string(), "Invalid buffer size value"),
+        }
+    }
+
+    #[test]
+    fn should_parse_expiry_interval() {
+        let cli = from_command_line(vec![]).unwrap();
+        assert_eq!(cli.expiry_interval, DEFAULT_EXPIRY_INTERVAL);
+        let args: Vec<String> = vec!["--expiry-interval".into(), "250".into()];
+        let cli = from_command_line(args).unwrap();
+        assert_eq!(cli.expiry_interval, 250);
+    }
+
+    #[test]
+    fn should_parse_timeout() {
+        let cli = from_command_line(vec![]).unwrap();
+        assert_eq!(cli.timeout, 0);
+        let args: Vec<String> = vec!["--timeout".into(), "300".into()];
+        let cli = from_command_line(args).unwrap();
+        assert_eq!(cli.timeout, 300);
+    }
+
     #[test]
     fn should_ignore_any_other_arguments() {
         let args: Vec<String> = vec!["--port".into(), "2142".into(), "--foo".into(), "bar".into()];
@@ -0,0 +1,58 @@
+// ------
+// CRC-64
+// ------
+
+/// The reflected form of the Jones polynomial (`0xad93d23594c935a9`), as used by Redis
+/// to checksum RDB files.
+const POLY: u64 = 0x95ac_9329_ac4b_c9b5;
+
+/// Lookup table for the byte-wise CRC-64 computation
+const TABLE: [u64; 256] = build_table();
+
+/// Builds the lookup table for the reflected Jones polynomial
+const fn build_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Computes the CRC-64 (Jones) checksum of the given data.
+/// This matches the checksum Redis appends to the end of RDB files.
+pub fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0, |crc, byte| {
+        TABLE[((crc ^ *byte as u64) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_match_reference_check_value() {
+        assert_eq!(checksum(b"123456789"), 0xe9c6d914c4b8d9ca);
+    }
+
+    #[test]
+    fn should_return_zero_for_empty_data() {
+        assert_eq!(checksum(b""), 0);
+    }
+}
@@ -0,0 +1,180 @@
+// --------
+// LISTPACK
+// --------
+
+// A listpack is a compact sequence of strings and integers, stored in RDB files as a single string.
+// It starts with a header holding its total size in bytes (32 bits) and its number of elements (16 bits),
+// followed by the elements and a terminating 0xFF byte. Each element is made of its encoding, its data,
+// and a "backlen" holding the size of the encoding and data, so that the listpack can be walked backwards.
+
+/// The byte that terminates a listpack
+const EOF: u8 = 0xFF;
+
+/// The number of elements stored in the header when there are too many to count in 16 bits
+const UNKNOWN_COUNT: usize = u16::MAX as usize;
+
+/// Encodes the elements as a listpack.
+/// Elements are written as strings, as numbers encoded as strings are read back as numbers.
+pub fn encode<T: AsRef<[u8]>>(elements: &[T]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for element in elements {
+        let element = element.as_ref();
+        let start = body.len();
+        match element.len() {
+            len @ 0..=63 => body.push(0x80 | len as u8),
+            len @ 64..=4095 => body.extend([0xE0 | (len >> 8) as u8, len as u8]),
+            len => {
+                body.push(0xF0);
+                body.extend((len as u32).to_le_bytes());
+            }
+        }
+        body.extend(element);
+        let backlen = encode_backlen(body.len() - start);
+        body.extend(backlen);
+    }
+
+    let mut bytes = Vec::with_capacity(body.len() + 7);
+    bytes.extend((body.len() as u32 + 7).to_le_bytes());
+    bytes.extend((elements.len().min(UNKNOWN_COUNT) as u16).to_le_bytes());
+    bytes.extend(body);
+    bytes.push(EOF);
+    bytes
+}
+
+/// Decodes the elements of a listpack. Integers are returned as their decimal representation.
+pub fn decode(bytes: &[u8]) -> Result<Vec<Vec<u8>>, Box<dyn std::error::Error>> {
+    let mut elements = Vec::new();
+    let mut pos = 6; // Skip the header
+    loop {
+        let encoding = *bytes
+            .get(pos)
+            .ok_or("Invalid listpack: missing terminator")?;
+        if encoding == EOF {
+            return Ok(elements);
+        }
+
+        // Determine the size of the encoding and the element it holds
+        let (header_len, element) = match encoding {
+            // 7 bit unsigned integer
+            0x00..=0x7F => (1, Element::Int(encoding as i64)),
+            // String with a 6 bit length
+            0x80..=0xBF => (1, Element::Str((encoding & 0x3F) as usize)),
+            // 13 bit signed integer
+            0xC0..=0xDF => {
+                let int = ((encoding as i64 & 0x1F) << 8) | read(bytes, pos + 1, 1)?[0] as i64;
+                (2, Element::Int(int << 51 >> 51))
+            }
+            // String with a 12 bit length
+            0xE0..=0xEF => {
+                let len = ((encoding as usize & 0x0F) << 8) | read(bytes, pos + 1, 1)?[0] as usize;
+                (2, Element::Str(len))
+            }
+            // String with a 32 bit length
+            0xF0 => {
+                let len = u32::from_le_bytes(read(bytes, pos + 1, 4)?.try_into()?);
+                (5, Element::Str(len as usize))
+            }
+            // 16, 24, 32 and 64 bit signed integers
+            0xF1..=0xF4 => {
+                let width = match encoding {
+                    0xF1 => 2,
+                    0xF2 => 3,
+                    0xF3 => 4,
+                    _ => 8,
+                };
+                let mut int = [0u8; 8];
+                int[..width].copy_from_slice(read(bytes, pos + 1, width)?);
+                let shift = 64 - 8 * width as u32;
+                let int = (i64::from_le_bytes(int) << shift) >> shift;
+                (1 + width, Element::Int(int))
+            }
+            _ => return Err(format!("Invalid listpack encoding: {:#04x}", encoding).into()),
+        };
+
+        let (len, element) = match element {
+            Element::Int(int) => (header_len, int.to_string().into_bytes()),
+            Element::Str(len) => (
+                header_len + len,
+                read(bytes, pos + header_len, len)?.to_vec(),
+            ),
+        };
+        elements.push(element);
+        pos += len + backlen_size(len);
+    }
+}
+
+/// An element of a listpack: an integer, or the length of a string
+enum Element {
+    Int(i64),
+    Str(usize),
+}
+
+/// Returns the `len` bytes at the given position, or an error if the listpack is too short
+fn read(bytes: &[u8], pos: usize, len: usize) -> Result<&[u8], Box<dyn std::error::Error>> {
+    bytes
+        .get(pos..pos + len)
+        .ok_or_else(|| "Invalid listpack: truncated element".into())
+}
+
+/// Encodes the size of an element, using 7 bits per byte with the most significant bits first.
+/// Every byte but the first has its high bit set, so that the size can be read backwards.
+fn encode_backlen(len: usize) -> Vec<u8> {
+    let size = backlen_size(len);
+    (0..size)
+        .map(|i| {
+            let byte = (len >> (7 * (size - 1 - i))) as u8 & 0x7F;
+            if i == 0 {
+                byte
+            } else {
+                byte | 0x80
+            }
+        })
+        .collect()
+}
+
+/// Returns the number of bytes taken up by the backlen of an element of the given size
+fn backlen_size(len: usize) -> usize {
+    match len {
+        0..=127 => 1,
+        128..=16382 => 2,
+        16383..=2097150 => 3,
+        2097151..=268435454 => 4,
+        _ => 5,
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_round_trip_strings_of_every_length_encoding() {
+        let elements = vec![
+            b"".to_vec(),
+            b"a".repeat(63),
+            b"b".repeat(200),
+            b"c".repeat(5000),
+        ];
+        assert_eq!(decode(&encode(&elements)).unwrap(), elements);
+    }
+
+    #[test]
+    fn should_decode_integer_encodings() {
+        // 7 bit 5, 13 bit -2, 16 bit 1000 and 64 bit -1, each followed by its backlen
+        let mut bytes = vec![0, 0, 0, 0, 4, 0];
+        bytes.extend([0x05, 1, 0xDF, 0xFE, 2, 0xF1, 0xE8, 0x03, 3]);
+        bytes.extend([0xF4, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 9, EOF]);
+        let expected: Vec<Vec<u8>> = ["5", "-2", "1000", "-1"].map(|n| n.into()).to_vec();
+        assert_eq!(decode(&bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn should_reject_truncated_listpack() {
+        let bytes = encode(&[b"hello"]);
+        assert!(decode(&bytes[..bytes.len() - 3]).is_err());
+    }
+}
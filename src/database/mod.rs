@@ -1,10 +1,22 @@
 use tokio::fs;
 
 // Library
-use crate::parser::resp::Type;
-use std::{collections::HashMap, time::Instant};
+use crate::{
+    errors::Error,
+    parser::resp::{stream::Stream, Type},
+};
+use rand::seq::index;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::{Instant, SystemTime},
+};
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 // Modules
+mod crc64;
+mod listpack;
 mod opcode;
 pub mod rdb;
 
@@ -19,11 +31,257 @@ pub struct Item {
     expires_at: Option<usize>,
 }
 
+impl Item {
+    /// Returns true if the item has an expiry and it has elapsed
+    pub fn is_expired(&self) -> bool {
+        match self.expires_at {
+            Some(expires_at) => self.created_at.elapsed().as_millis() as usize >= expires_at,
+            None => false,
+        }
+    }
+
+    /// Returns true if the item has an expiry set
+    pub fn has_expiry(&self) -> bool {
+        self.expires_at.is_some()
+    }
+
+    /// Returns the number of milliseconds remaining until the item expires,
+    /// or `None` if the item has no expiry
+    pub fn ttl(&self) -> Option<usize> {
+        let elapsed = self.created_at.elapsed().as_millis() as usize;
+        self.expires_at
+            .map(|expires_at| expires_at.saturating_sub(elapsed))
+    }
+}
+
+/// The error for an operation against a key holding a value of the wrong type.
+/// Its message is the `WRONGTYPE` error that is sent to the client, and it converts into `Error::WrongType`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WrongType;
+
+impl std::fmt::Display for WrongType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Error::WrongType)
+    }
+}
+
+impl std::error::Error for WrongType {}
+
+/// The number of logical databases, selectable with the SELECT command
+pub const DATABASES: usize = 16;
+
+/// The number of keys with an expiry sampled at a time when evicting expired keys
+const EXPIRY_SAMPLE_SIZE: usize = 20;
+
+/// The maximum number of samples taken from each database in a single eviction,
+/// so that the shard is not locked for long even if most of its keys have expired
+const EXPIRY_MAX_ROUNDS: usize = 16;
+
+/// The number of shards the keys are spread across.
+/// Each shard is locked independently, so commands on keys in different shards run concurrently.
+const SHARDS: usize = 16;
+
+/// A shard of the database, holding the keys whose hash maps to it.
+/// The keys are split across the logical databases, addressed by index,
+/// and every operation on keys takes the index of the database it applies to.
+pub struct Shard {
+    /// The actual data store, one map per logical database
+    data: Vec<HashMap<Type, Item>>,
+    /// The keys with an expiry, one index per logical database
+    expiring: Vec<ExpiryIndex>,
+}
+
+impl Shard {
+    /// Sets the value of a key in the database.
+    pub fn set(&mut self, db: usize, key: Type, value: Type, expires_at: Option<usize>) {
+        let item = Item {
+            value,
+            created_at: Instant::now(),
+            expires_at,
+        };
+        self.insert_item(db, key, item);
+    }
+
+    /// Inserts the item, keeping track of whether the key has an expiry
+    fn insert_item(&mut self, db: usize, key: Type, item: Item) {
+        match item.has_expiry() {
+            true => self.expiring[db].insert(&key),
+            false => self.expiring[db].remove(&key),
+        }
+        self.data[db].insert(key, item);
+    }
+
+    /// Removes the item, whether or not it has expired
+    fn remove_item(&mut self, db: usize, key: &Type) -> Option<Item> {
+        let item = self.data[db].remove(key)?;
+        self.expiring[db].remove(key);
+        Some(item)
+    }
+
+    /// Removes all keys from the database
+    fn clear(&mut self, db: usize) {
+        self.data[db].clear();
+        self.expiring[db] = ExpiryIndex::default();
+    }
+
+    /// Updates the value of a key in the database, preserving any existing expiry.
+    /// If the key does not exist (or has expired), it is set without an expiry.
+    pub fn update(&mut self, db: usize, key: Type, value: Type) {
+        match self.data[db].get_mut(&key) {
+            Some(item) if !item.is_expired() => item.value = value,
+            _ => self.set(db, key, value, None),
+        }
+    }
+
+    /// Gets the value of a key in the database.
+    pub fn get(&self, db: usize, key: &Type) -> Option<&Type> {
+        let item = self.data[db].get(key)?;
+        println!("Getting {:?}", item);
+        if item.is_expired() {
+            return None;
+        }
+        Some(&item.value)
+    }
+
+    /// Gets the string value of a key in the database.
+    /// Returns `Ok(None)` if the key does not exist, and `Err(Error::WrongType)` if it holds another type of value.
+    pub fn expect_string(&self, db: usize, key: &Type) -> Result<Option<&[u8]>, Error> {
+        match self.get(db, key) {
+            Some(Type::BulkString(value)) => Ok(Some(value)),
+            Some(_) => Err(Error::WrongType),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets the stream stored at a key in the database.
+    /// Returns `Ok(None)` if the key does not exist, and `Err(Error::WrongType)` if it holds another type of value.
+    pub fn expect_stream(&self, db: usize, key: &Type) -> Result<Option<&Stream>, Error> {
+        match self.get(db, key) {
+            Some(Type::Stream(stream)) => Ok(Some(stream)),
+            Some(_) => Err(Error::WrongType),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets a mutable reference to the value of a key in the database.
+    /// Returns `None` if the key does not exist or has expired.
+    pub fn get_mut(&mut self, db: usize, key: &Type) -> Option<&mut Type> {
+        self.data[db]
+            .get_mut(key)
+            .filter(|item| !item.is_expired())
+            .map(|item| &mut item.value)
+    }
+
+    /// Gets the item (value and metadata) of a key in the database.
+    /// Returns `None` if the key does not exist or has expired.
+    pub fn get_item(&self, db: usize, key: &Type) -> Option<&Item> {
+        self.data[db].get(key).filter(|item| !item.is_expired())
+    }
+
+    /// Sets the expiry of an existing key in the database, restarting the expiry clock.
+    /// Passing `None` clears the expiry.
+    /// Returns true if the key existed (and had not expired).
+    pub fn set_expiry(&mut self, db: usize, key: &Type, expires_at: Option<usize>) -> bool {
+        match self.data[db].get_mut(key) {
+            Some(item) if !item.is_expired() => {
+                item.created_at = Instant::now();
+                item.expires_at = expires_at;
+            }
+            _ => return false,
+        }
+        match expires_at {
+            Some(_) => self.expiring[db].insert(key),
+            None => self.expiring[db].remove(key),
+        }
+        true
+    }
+
+    /// Removes a key from the database.
+    /// Returns the removed item, or `None` if the key did not exist (or had already expired).
+    pub fn remove(&mut self, db: usize, key: &Type) -> Option<Item> {
+        let item = self.remove_item(db, key)?;
+        if item.is_expired() {
+            return None;
+        }
+        Some(item)
+    }
+
+    /// Evicts the expired keys among a random sample of the keys with an expiry in every database.
+    /// The sampling is repeated (a bounded number of times) while more than a quarter of the sample had expired.
+    /// Returns the number of evicted keys.
+    fn evict_expired(&mut self) -> usize {
+        let mut rng = rand::thread_rng();
+        let mut evicted = 0;
+        for db in 0..self.data.len() {
+            for _ in 0..EXPIRY_MAX_ROUNDS {
+                let sample = self.expiring[db].sample(&mut rng, EXPIRY_SAMPLE_SIZE);
+
+                // Evict the expired keys in the sample
+                let mut expired = 0;
+                for key in &sample {
+                    if self.data[db].get(key).is_some_and(Item::is_expired) {
+                        self.remove_item(db, key);
+                        expired += 1;
+                    }
+                }
+                evicted += expired;
+
+                // Stop once only a few of the sampled keys had expired
+                if expired * 4 <= sample.len() {
+                    break;
+                }
+            }
+        }
+        evicted
+    }
+}
+
+/// The keys of a database that have an expiry.
+/// The keys are kept in a vector (along with the position of each key) so that they can be sampled
+/// at random without going through every key in the database.
+#[derive(Default)]
+struct ExpiryIndex {
+    keys: Vec<Type>,
+    positions: HashMap<Type, usize>,
+}
+
+impl ExpiryIndex {
+    /// Adds the key to the index, if it is not already in it
+    fn insert(&mut self, key: &Type) {
+        if !self.positions.contains_key(key) {
+            self.positions.insert(key.clone(), self.keys.len());
+            self.keys.push(key.clone());
+        }
+    }
+
+    /// Removes the key from the index, moving the last key into its place
+    fn remove(&mut self, key: &Type) {
+        let Some(position) = self.positions.remove(key) else {
+            return;
+        };
+        self.keys.swap_remove(position);
+        if let Some(moved) = self.keys.get(position) {
+            self.positions.insert(moved.clone(), position);
+        }
+    }
+
+    /// Returns up to `amount` distinct keys picked at random
+    fn sample(&self, rng: &mut impl rand::Rng, amount: usize) -> Vec<Type> {
+        index::sample(rng, self.keys.len(), amount.min(self.keys.len()))
+            .into_iter()
+            .map(|i| self.keys[i].clone())
+            .collect()
+    }
+}
+
 /// Database struct to store key-value pairs.
+/// The keys are spread across shards by their hash, and each shard is behind its own lock,
+/// so that commands on independent keys do not block each other.
+/// Cloning the database is cheap, as the clones share the same shards.
 #[derive(Clone)]
 pub struct Database {
-    /// The actual data store
-    data: HashMap<Type, Item>,
+    /// The shards holding the data
+    shards: Arc<Vec<RwLock<Shard>>>,
 
     /// The directory where the database is stored
     pub dir: String,
@@ -34,67 +292,183 @@ pub struct Database {
 
 /// Creates a new instance of the database.
 pub fn new() -> Database {
+    let shards = (0..SHARDS)
+        .map(|_| {
+            RwLock::new(Shard {
+                data: (0..DATABASES).map(|_| HashMap::new()).collect(),
+                expiring: (0..DATABASES).map(|_| ExpiryIndex::default()).collect(),
+            })
+        })
+        .collect();
     Database {
-        data: HashMap::new(),
+        shards: Arc::new(shards),
         dir: String::from(""),
         dbfilename: String::from(""),
     }
 }
 
 impl Database {
+    /// Returns the index of the shard holding the given key.
+    fn shard_index(key: &Type) -> usize {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() % SHARDS as u64) as usize
+    }
+
+    /// Locks the shard holding the given key for reading.
+    pub async fn read(&self, key: &Type) -> RwLockReadGuard<'_, Shard> {
+        self.shards[Self::shard_index(key)].read().await
+    }
+
+    /// Locks the shard holding the given key for writing.
+    /// Commands that read and then modify a key hold the lock throughout, so the change is atomic.
+    pub async fn write(&self, key: &Type) -> RwLockWriteGuard<'_, Shard> {
+        self.shards[Self::shard_index(key)].write().await
+    }
+
+    /// Gets (a copy of) the value of a key in the database.
+    pub async fn get(&self, db: usize, key: &Type) -> Option<Type> {
+        self.read(key).await.get(db, key).cloned()
+    }
+
     /// Sets the value of a key in the database.
-    pub fn set(&mut self, key: Type, value: Type, expires_at: Option<usize>) {
-        self.data.insert(
-            key,
-            Item {
-                value,
-                created_at: Instant::now(),
-                expires_at,
-            },
-        );
+    pub async fn set(&self, db: usize, key: Type, value: Type, expires_at: Option<usize>) {
+        self.write(&key).await.set(db, key, value, expires_at);
     }
 
-    /// Gets the value of a key in the database.
-    pub fn get(&self, key: &Type) -> Option<&Type> {
-        let item = self.data.get(key)?;
-        println!("Getting {:?}", item);
-        if item.expires_at.is_some() {
-            if item.created_at.elapsed().as_millis() as usize >= item.expires_at? {
-                return None;
+    /// Removes a key from the database.
+    /// Returns the removed item, or `None` if the key did not exist (or had already expired).
+    pub async fn remove(&self, db: usize, key: &Type) -> Option<Item> {
+        self.write(key).await.remove(db, key)
+    }
+
+    /// Copies the value of the source key to the destination key.
+    /// The remaining time-to-live of the source is carried over to the destination.
+    /// If the destination already exists, it is only overwritten when `replace` is true.
+    /// Returns true if the value was copied.
+    pub async fn copy(&self, db: usize, src: &Type, dst: &Type, replace: bool) -> bool {
+        let (src_index, dst_index) = (Self::shard_index(src), Self::shard_index(dst));
+        if src_index == dst_index {
+            let mut shard = self.shards[src_index].write().await;
+            let Some(item) = shard.get_item(db, src).cloned() else {
+                return false;
+            };
+            return copy_item(item, &mut shard, db, dst, replace);
+        }
+
+        // Lock the shards in index order, so that concurrent copies cannot deadlock
+        let mut low = self.shards[src_index.min(dst_index)].write().await;
+        let mut high = self.shards[src_index.max(dst_index)].write().await;
+        let (src_shard, dst_shard) = match src_index < dst_index {
+            true => (&*low, &mut *high),
+            false => (&*high, &mut *low),
+        };
+        let Some(item) = src_shard.get_item(db, src).cloned() else {
+            return false;
+        };
+        copy_item(item, dst_shard, db, dst, replace)
+    }
+
+    /// Pops an element from the head (or tail) of the list stored at the key,
+    /// removing the key once its list is empty.
+    /// Returns the popped element, `Ok(None)` if the key does not exist,
+    /// and `Err(WrongType)` if the key holds another type of value.
+    pub async fn pop(
+        &self,
+        db: usize,
+        key: &Type,
+        left: bool,
+    ) -> Result<Option<String>, WrongType> {
+        let mut shard = self.write(key).await;
+        expect_list(&shard, db, key)?;
+        let element = pop_element(&mut shard, db, key, left);
+        remove_if_empty(&mut shard, db, key);
+        Ok(element)
+    }
+
+    /// Pops an element from one end of the source list, and pushes it onto one end of the destination list.
+    /// The destination list is created if it does not exist, and the source key is removed once its list is empty.
+    /// A list may be both the source and the destination, which rotates the list.
+    /// Returns the moved element, `Ok(None)` if the source does not exist,
+    /// and `Err(WrongType)` (without moving anything) if either key holds another type of value.
+    pub async fn lmove(
+        &self,
+        db: usize,
+        src: &Type,
+        dst: &Type,
+        from_left: bool,
+        to_left: bool,
+    ) -> Result<Option<String>, WrongType> {
+        let (src_index, dst_index) = (Self::shard_index(src), Self::shard_index(dst));
+        if src_index == dst_index {
+            let mut shard = self.shards[src_index].write().await;
+            expect_list(&shard, db, src)?;
+            expect_list(&shard, db, dst)?;
+            let Some(element) = pop_element(&mut shard, db, src, from_left) else {
+                return Ok(None);
+            };
+            push_element(&mut shard, db, dst, element.clone(), to_left);
+            remove_if_empty(&mut shard, db, src);
+            return Ok(Some(element));
+        }
+
+        // Lock the shards in index order, so that concurrent moves cannot deadlock
+        let mut low = self.shards[src_index.min(dst_index)].write().await;
+        let mut high = self.shards[src_index.max(dst_index)].write().await;
+        let (src_shard, dst_shard) = match src_index < dst_index {
+            true => (&mut *low, &mut *high),
+            false => (&mut *high, &mut *low),
+        };
+        expect_list(src_shard, db, src)?;
+        expect_list(dst_shard, db, dst)?;
+        let Some(element) = pop_element(src_shard, db, src, from_left) else {
+            return Ok(None);
+        };
+        push_element(dst_shard, db, dst, element.clone(), to_left);
+        remove_if_empty(src_shard, db, src);
+        Ok(Some(element))
+    }
+
+    /// Moves a key (along with its expiry) from the source database to the destination database.
+    /// The key is not moved if it does not exist in the source, or already exists in the destination.
+    /// Returns true if the key was moved.
+    pub async fn move_key(&self, src: usize, dst: usize, key: &Type) -> bool {
+        let mut shard = self.write(key).await;
+        if shard.get_item(src, key).is_none() || shard.get_item(dst, key).is_some() {
+            return false;
+        }
+        match shard.remove_item(src, key) {
+            Some(item) => {
+                shard.insert_item(dst, key.clone(), item);
+                true
             }
+            None => false,
         }
-        Some(&item.value)
     }
 
-    // /// Removes a key from the database.
-    // pub fn remove(&mut self, key: &Type) {
-    //     self.data.remove(key);
-    // }
+    /// Removes all keys from the given database.
+    pub async fn clear(&self, db: usize) {
+        for shard in self.shards.iter() {
+            shard.write().await.clear(db);
+        }
+    }
+
+    /// Removes all keys from every database.
+    pub async fn clear_all(&self) {
+        for shard in self.shards.iter() {
+            let mut shard = shard.write().await;
+            for db in 0..DATABASES {
+                shard.clear(db);
+            }
+        }
+    }
 
-    pub async fn load(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    /// Loads the database from the RDB file in the configured directory.
+    /// A missing or corrupt RDB file is logged and the database is left empty.
+    pub async fn load(&self) -> Result<(), Box<dyn std::error::Error>> {
         let filepath = format!("{}/{}", self.dir, self.dbfilename);
         match fs::read(filepath).await {
-            Ok(contents) => {
-                // println!("{:?}", contents);
-                let rdb = rdb::parse(contents)
-                    .await
-                    .expect("Failed to parse RDB file.");
-                println!("{:?}", rdb.data.len());
-                for ele in rdb.data {
-                    println!(
-                        "Key - {}, Value - {}, Expiry - {:?}",
-                        ele.0, ele.1 .0, ele.1 .1
-                    );
-                    let value = match ele.1 .0 {
-                        x => Type::BulkString(x),
-                    };
-                    let expiry = match ele.1 .1 {
-                        Some(x) => Some(x as usize),
-                        None => None,
-                    };
-                    self.set(Type::BulkString(ele.0), value, expiry);
-                }
-            }
+            Ok(contents) => self.restore(contents).await,
             Err(_) => {
                 println!("No RDB file found.");
             }
@@ -102,7 +476,372 @@ impl Database {
         Ok(())
     }
 
-    pub fn keys(&self) -> Vec<Type> {
-        self.data.keys().cloned().collect()
+    /// Saves the database as an RDB file in the configured directory.
+    pub async fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let filepath = format!("{}/{}", self.dir, self.dbfilename);
+        fs::write(filepath, self.dump().await).await?;
+        Ok(())
+    }
+
+    /// Adds the keys in the RDB file contents to the database.
+    /// A corrupt RDB file should not bring down the server, so it is logged and nothing is added.
+    pub async fn restore(&self, contents: Vec<u8>) {
+        let rdb = match rdb::parse(contents, false).await {
+            Ok(rdb) => rdb,
+            Err(e) => {
+                println!("[WARNING] Failed to parse RDB file, starting empty: {}", e);
+                return;
+            }
+        };
+        println!("{:?}", rdb.data.len());
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Failed to get time")
+            .as_millis();
+        for (db, data) in rdb.data {
+            if db >= DATABASES {
+                println!("[WARNING] Skipping out of range database {}", db);
+                continue;
+            }
+            for ele in data {
+                println!(
+                    "DB - {}, Key - {}, Value - {:?}, Expiry - {:?}",
+                    db,
+                    String::from_utf8_lossy(&ele.0),
+                    ele.1 .0,
+                    ele.1 .1
+                );
+                let value = ele.1 .0;
+                // The RDB expiry is an absolute Unix timestamp, while the database keeps the remaining time
+                let expiry = match ele.1 .1 {
+                    Some(at) if at <= now => continue, // Already expired
+                    Some(at) => Some((at - now) as usize),
+                    None => None,
+                };
+                self.set(db, Type::BulkString(ele.0), value, expiry).await;
+            }
+        }
+    }
+
+    /// Serializes the keys that have not expired in every database as an RDB file.
+    /// The remaining time-to-live of each key is stored as an absolute Unix timestamp.
+    pub async fn dump(&self) -> Vec<u8> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Failed to get time")
+            .as_millis();
+        let mut data = rdb::Data::new();
+        for shard in self.shards.iter() {
+            let shard = shard.read().await;
+            for (db, items) in shard.data.iter().enumerate() {
+                for (key, item) in items.iter().filter(|(_, item)| !item.is_expired()) {
+                    // Keys are binary-safe, so their raw bytes are written as is
+                    let Type::BulkString(key) = key else {
+                        continue;
+                    };
+                    let expiry = item.ttl().map(|ttl| now + ttl as u128);
+                    data.entry(db)
+                        .or_default()
+                        .insert(key.clone(), (item.value.clone(), expiry));
+                }
+            }
+        }
+        rdb::encode(&data)
+    }
+
+    /// Evicts expired keys from every database, so keys that are never read again do not leak memory.
+    /// Rather than scanning every key, a random sample of the keys with an expiry is checked in each shard.
+    /// Returns the number of evicted keys.
+    pub async fn evict_expired(&self) -> usize {
+        let mut evicted = 0;
+        for shard in self.shards.iter() {
+            evicted += shard.write().await.evict_expired();
+        }
+        evicted
+    }
+
+    /// Returns all the keys that have not expired in the given database.
+    pub async fn keys(&self, db: usize) -> Vec<Type> {
+        let mut keys = Vec::new();
+        for shard in self.shards.iter() {
+            let shard = shard.read().await;
+            let items = shard.data[db].iter();
+            keys.extend(
+                items
+                    .filter(|(_, item)| !item.is_expired())
+                    .map(|(key, _)| key.clone()),
+            );
+        }
+        keys
+    }
+}
+
+/// Copies the item to the destination key of the shard, restarting the clock on the copy
+/// so that only the remaining TTL applies.
+/// The destination is only overwritten when `replace` is true.
+fn copy_item(item: Item, shard: &mut Shard, db: usize, dst: &Type, replace: bool) -> bool {
+    if !replace && shard.get_item(db, dst).is_some() {
+        return false;
+    }
+    let item = Item {
+        expires_at: item.ttl(),
+        created_at: Instant::now(),
+        value: item.value,
+    };
+    shard.insert_item(db, dst.clone(), item);
+    true
+}
+
+/// Checks that the key is either missing or holds a list
+fn expect_list(shard: &Shard, db: usize, key: &Type) -> Result<(), WrongType> {
+    match shard.get(db, key) {
+        Some(Type::List(_)) | None => Ok(()),
+        Some(_) => Err(WrongType),
+    }
+}
+
+/// Pops an element from the head (or tail) of the list stored at the key.
+/// The list is left in place even if it becomes empty (see `remove_if_empty`).
+fn pop_element(shard: &mut Shard, db: usize, key: &Type, left: bool) -> Option<String> {
+    match shard.get_mut(db, key) {
+        Some(Type::List(list)) if left && !list.is_empty() => Some(list.remove(0)),
+        Some(Type::List(list)) => list.pop(),
+        _ => None,
+    }
+}
+
+/// Pushes an element onto the head (or tail) of the list stored at the key, creating the list if needed
+fn push_element(shard: &mut Shard, db: usize, key: &Type, element: String, left: bool) {
+    if shard.get(db, key).is_none() {
+        shard.set(db, key.clone(), Type::List(Vec::new()), None);
+    }
+    if let Some(Type::List(list)) = shard.get_mut(db, key) {
+        match left {
+            true => list.insert(0, element),
+            false => list.push(element),
+        }
+    }
+}
+
+/// Removes the key if it holds an empty list
+fn remove_if_empty(shard: &mut Shard, db: usize, key: &Type) {
+    if matches!(shard.get(db, key), Some(Type::List(list)) if list.is_empty()) {
+        shard.remove(db, key);
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers;
+    use std::time::Duration;
+
+    /// Writes the contents to a uniquely named RDB file in the temp directory,
+    /// and returns a database configured to load it
+    async fn database_with_file(name: &str, contents: &[u8]) -> Database {
+        let dir = std::env::temp_dir().to_string_lossy().into_owned();
+        let dbfilename = format!("{}-{}.rdb", name, helpers::generate_id(8));
+        fs::write(format!("{}/{}", dir, dbfilename), contents)
+            .await
+            .unwrap();
+        Database {
+            dir,
+            dbfilename,
+            ..new()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_load_nothing_from_truncated_rdb() {
+        let bytes = helpers::base64_to_bytes(rdb::EMPTY_RDB);
+        let db = database_with_file("truncated", &bytes[..20]).await;
+        assert!(db.load().await.is_ok());
+        assert!(db.keys(0).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_load_nothing_from_garbage() {
+        let db = database_with_file("garbage", b"REDIS0011\x42\x42").await;
+        assert!(db.load().await.is_ok());
+        assert!(db.keys(0).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_keep_databases_separate() {
+        let db = new();
+        let key = Type::BulkString("k".into());
+        db.set(0, key.clone(), Type::BulkString("v".into()), None)
+            .await;
+        assert!(db.get(0, &key).await.is_some());
+        assert!(db.get(1, &key).await.is_none());
+
+        db.clear(1).await;
+        assert!(db.get(0, &key).await.is_some());
+        db.clear_all().await;
+        assert!(db.get(0, &key).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn should_move_key_between_databases() {
+        let db = new();
+        let key = Type::BulkString("k".into());
+        db.set(0, key.clone(), Type::BulkString("v".into()), Some(10_000))
+            .await;
+        assert!(db.move_key(0, 1, &key).await);
+        assert!(db.get(0, &key).await.is_none());
+        let shard = db.read(&key).await;
+        assert!(shard
+            .get_item(1, &key)
+            .is_some_and(|item| item.has_expiry()));
+        drop(shard);
+        assert!(!db.move_key(0, 1, &key).await);
+    }
+
+    #[tokio::test]
+    async fn should_evict_expired_keys() {
+        let db = new();
+        for i in 0..100 {
+            let key = Type::BulkString(format!("expired{}", i).into());
+            db.set(2, key, Type::BulkString("v".into()), Some(0)).await;
+        }
+        let key = Type::BulkString("k".into());
+        db.set(2, key.clone(), Type::BulkString("v".into()), Some(10_000))
+            .await;
+        assert_eq!(db.evict_expired().await, 100);
+        assert_eq!(db.keys(2).await, vec![key]);
+    }
+
+    #[tokio::test]
+    async fn should_not_list_expired_keys_before_eviction() {
+        let db = new();
+        let key = Type::BulkString("k".into());
+        let value = Type::BulkString("v".into());
+        db.set(
+            0,
+            Type::BulkString("expired".into()),
+            value.clone(),
+            Some(0),
+        )
+        .await;
+        db.set(0, key.clone(), value, None).await;
+        assert_eq!(db.keys(0).await, vec![key]);
+    }
+
+    #[tokio::test]
+    async fn should_only_index_keys_with_expiry() {
+        let db = new();
+        let key = Type::BulkString("k".into());
+        let indexed = |shard: &Shard, i: usize| shard.expiring[i].keys.clone();
+
+        db.set(0, key.clone(), Type::BulkString("v".into()), Some(10_000))
+            .await;
+        assert_eq!(indexed(&*db.read(&key).await, 0), vec![key.clone()]);
+
+        // Overwriting the key without an expiry, or clearing the expiry, removes it from the index
+        db.set(0, key.clone(), Type::BulkString("v".into()), None)
+            .await;
+        assert!(indexed(&*db.read(&key).await, 0).is_empty());
+        db.write(&key).await.set_expiry(0, &key, Some(10_000));
+        db.write(&key).await.set_expiry(0, &key, None);
+        assert!(indexed(&*db.read(&key).await, 0).is_empty());
+
+        // Moving and removing the key update the index of each database
+        db.write(&key).await.set_expiry(0, &key, Some(10_000));
+        assert!(db.move_key(0, 1, &key).await);
+        assert!(indexed(&*db.read(&key).await, 0).is_empty());
+        assert_eq!(indexed(&*db.read(&key).await, 1), vec![key.clone()]);
+        db.remove(1, &key).await;
+        assert!(indexed(&*db.read(&key).await, 1).is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_copy_across_shards() {
+        let db = new();
+        let src = Type::BulkString("src".into());
+        db.set(0, src.clone(), Type::BulkString("v".into()), None)
+            .await;
+        for i in 0..SHARDS * 4 {
+            let dst = Type::BulkString(format!("dst{}", i).into());
+            assert!(db.copy(0, &src, &dst, false).await);
+            assert_eq!(db.get(0, &dst).await, Some(Type::BulkString("v".into())));
+        }
+    }
+
+    #[tokio::test]
+    async fn should_not_block_keys_in_other_shards() {
+        let db = new();
+        let key = Type::BulkString("locked".into());
+        let other = (0..)
+            .map(|i| Type::BulkString(format!("other{}", i).into()))
+            .find(|other| Database::shard_index(other) != Database::shard_index(&key))
+            .unwrap();
+        db.set(0, other.clone(), Type::BulkString("v".into()), None)
+            .await;
+
+        // Keys in other shards can be read while a shard is locked for writing
+        let _guard = db.write(&key).await;
+        let read = tokio::time::timeout(Duration::from_millis(100), db.get(0, &other));
+        assert_eq!(read.await.unwrap(), Some(Type::BulkString("v".into())));
+
+        // ... but keys in the locked shard have to wait
+        let read = tokio::time::timeout(Duration::from_millis(100), db.get(0, &key));
+        assert!(read.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_load_expiry_as_remaining_time() {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let value = Type::BulkString("v".into());
+        let keys = HashMap::from([
+            (b"future".to_vec(), (value.clone(), Some(now + 60_000))),
+            (b"past".to_vec(), (value.clone(), Some(now - 1_000))),
+            (b"persistent".to_vec(), (value, None)),
+        ]);
+        let bytes = rdb::encode(&HashMap::from([(0, keys)]));
+        let db = database_with_file("expiry", &bytes).await;
+        db.load().await.unwrap();
+
+        let key = Type::BulkString("future".into());
+        let ttl = db.read(&key).await.get_item(0, &key).unwrap().ttl();
+        assert!(matches!(ttl, Some(ttl) if ttl > 50_000 && ttl <= 60_000));
+        assert!(db.get(0, &Type::BulkString("past".into())).await.is_none());
+        assert!(db
+            .get(0, &Type::BulkString("persistent".into()))
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn should_load_keys_into_selected_database() {
+        // An RDB file with the key `k` set to `v` in database 3
+        let mut bytes = b"REDIS0003".to_vec();
+        bytes.extend([0xFE, 3, 0xFB, 1, 0, 0, 1, b'k', 1, b'v', 0xFF]);
+        let db = database_with_file("select", &bytes).await;
+        db.load().await.unwrap();
+        assert!(db.keys(0).await.is_empty());
+        assert_eq!(db.keys(3).await, vec![Type::BulkString("k".into())]);
+    }
+
+    #[tokio::test]
+    async fn should_dump_and_restore_binary_keys() {
+        let db = new();
+        let key = Type::BulkString(vec![0xff, 0x00, 0xfe]);
+        db.set(2, key.clone(), Type::BulkString("v".into()), None)
+            .await;
+
+        let restored = new();
+        restored.restore(db.dump().await).await;
+        assert_eq!(restored.keys(2).await, vec![key.clone()]);
+        assert_eq!(
+            restored.get(2, &key).await,
+            Some(Type::BulkString("v".into()))
+        );
     }
 }
@@ -1,5 +1,6 @@
 // OPCODES
 
+#[allow(dead_code)]
 pub enum OPCode {
     Aux = 0xfa,
     ResizeDB = 0xfb,
@@ -1,6 +1,11 @@
 // Library
-use byteorder::{ByteOrder, LittleEndian};
-use std::collections::HashMap;
+use super::{crc64, listpack};
+use crate::parser::resp::{
+    stream::{Stream, StreamEntry, StreamID},
+    Type,
+};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use std::collections::{HashMap, HashSet};
 use std::io::Cursor;
 use std::time::SystemTime;
 use tokio::io::AsyncReadExt;
@@ -9,28 +14,38 @@ use tokio::io::AsyncReadExt;
 pub const MAGIC_BYTES: &[u8; 5] = b"REDIS";
 
 /// Contents of an empty RDB file in base64 encoding
+#[allow(dead_code)]
 pub const EMPTY_RDB: &str = "UkVESVMwMDEx+glyZWRpcy12ZXIFNy4yLjD6CnJlZGlzLWJpdHPAQPoFY3RpbWXCbQi8ZfoIdXNlZC1tZW3CsMQQAPoIYW9mLWJhc2XAAP/wbjv+wP9aog==";
 
+/// The key-value pairs (with their expiry), grouped by database number.
+/// Keys are kept as raw bytes, as they are binary-safe.
+pub type Data = HashMap<usize, HashMap<Vec<u8>, (Type, Option<u128>)>>;
+
 /// Represents the contents of an RDB file
+#[derive(Default)]
+#[allow(clippy::upper_case_acronyms)]
 pub struct RDB {
     pub magic_string: String,
     pub version: String,
-    pub data: HashMap<String, (String, Option<u128>)>,
-}
-
-impl Default for RDB {
-    fn default() -> Self {
-        RDB {
-            magic_string: String::new(),
-            version: String::new(),
-            data: HashMap::new(),
-        }
-    }
+    /// The key-value pairs (with their expiry), grouped by database number
+    pub data: Data,
+    /// The database number selected by the last SELECTDB opcode
+    db: usize,
+    /// Whether a checksum mismatch is an error (strict) or only logged as a warning (lenient)
+    pub verify_checksum: bool,
 }
 
-/// Parses the given RDB file data and returns the corresponding `RDB` struct
-pub async fn parse(data: Vec<u8>) -> Result<RDB, Box<dyn std::error::Error>> {
-    let mut rdb = RDB::default();
+/// Parses the given RDB file data and returns the corresponding `RDB` struct.
+/// If `verify_checksum` is true, a mismatch between the CRC64 checksum at the end of the file
+/// and the checksum computed over its contents is returned as an error. Otherwise it is only logged.
+pub async fn parse(
+    data: Vec<u8>,
+    verify_checksum: bool,
+) -> Result<RDB, Box<dyn std::error::Error>> {
+    let mut rdb = RDB {
+        verify_checksum,
+        ..RDB::default()
+    };
     rdb.parse(data).await?;
     Ok(rdb)
 }
@@ -50,7 +65,7 @@ impl RDB {
         cursor
             .read_exact(&mut buf)
             .await
-            .expect("Failed to read magic string");
+            .map_err(|e| format!("Failed to read magic string: {}", e))?;
         self.magic_string = String::from_utf8(buf.to_vec())?;
 
         // Read the next four bytes for the version
@@ -58,31 +73,36 @@ impl RDB {
         cursor
             .read_exact(&mut buf)
             .await
-            .expect("Failed to read version");
+            .map_err(|e| format!("Failed to read version: {}", e))?;
         self.version = String::from_utf8(buf.to_vec())?;
 
         // Read the rest of the data
         loop {
-            let next_byte = cursor.read_u8().await.expect("Failed to read opcode byte");
+            let next_byte = cursor
+                .read_u8()
+                .await
+                .map_err(|e| format!("Failed to read opcode byte: {}", e))?;
             // println!("Opcode Byte: {}", next_byte);
             match next_byte {
                 0xFA => self
                     .parse_aux(&mut cursor)
                     .await
-                    .expect("Failed to parse aux"),
-                0xFB => {
-                    self.parse_resize_db(&mut cursor)
-                        .await
-                        .expect("Failed to parse resize db");
-                    break;
-                }
+                    .map_err(|e| format!("Failed to parse aux: {}", e))?,
+                0xFB => self
+                    .parse_resize_db(&mut cursor)
+                    .await
+                    .map_err(|e| format!("Failed to parse resize db: {}", e))?,
                 0xFE => self
                     .parse_select_db(&mut cursor)
                     .await
-                    .expect("Failed to parse select db"),
-                0xFF => break, // End of the RDB file
+                    .map_err(|e| format!("Failed to parse select db: {}", e))?,
+                0xFF => {
+                    // End of the RDB file, followed by the checksum
+                    self.parse_checksum(&cursor)?;
+                    break;
+                }
                 _ => {
-                    panic!("Invalid opcode: {}", next_byte);
+                    return Err(format!("Invalid opcode: {}", next_byte).into());
                 }
             }
         }
@@ -97,11 +117,11 @@ impl RDB {
         // Key
         read_encoded_string(cursor)
             .await
-            .expect("Failed to read aux key");
+            .map_err(|e| format!("Failed to read aux key: {}", e))?;
         // Value
         read_encoded_string(cursor)
             .await
-            .expect("Failed to read aux value");
+            .map_err(|e| format!("Failed to read aux value: {}", e))?;
         Ok(())
     }
 
@@ -119,58 +139,94 @@ impl RDB {
 
     async fn parse_hash_table(
         &mut self,
-        size: u32,
+        size: u64,
         cursor: &mut Cursor<&Vec<u8>>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         // Iterate over the hash table for the given size
         for _ in 0..size {
-            let value_type = cursor.read_u8().await?;
+            let mut value_type = cursor.read_u8().await?;
 
-            let expiry: Option<u128>;
+            // The entry may start with an expiry, followed by the actual value type
+            let mut expiry: Option<u128> = None;
             // println!("ValueType {:b}", value_type);
             match value_type {
                 0xFC => {
                     let val = cursor.read_u64_le().await? as u128;
                     expiry = Some(val);
-                    cursor.read_u8().await?;
+                    value_type = cursor.read_u8().await?;
                 }
                 0xFD => {
                     let val = cursor.read_u32_le().await? as u128;
                     expiry = Some(val * 1000);
-                    cursor.read_u8().await?;
+                    value_type = cursor.read_u8().await?;
+                }
+                0xFF => {
+                    // End of the RDB file. Step back so that the caller handles the end opcode
+                    cursor.set_position(cursor.position() - 1);
+                    break;
                 }
-                0xFF => break,
-                _ => expiry = None,
+                _ => {}
             }
 
-            let key = read_encoded_string(cursor).await?;
-            let value = read_encoded_string(cursor).await?;
+            let key = read_encoded_bytes(cursor).await?;
+            let value = read_value(value_type, cursor).await?;
 
             println!(
                 "\u{001b}[31mKey: {:?}, Value: {:?}, Expiry: {:?} (vs {})\u{001b}[0m",
-                key,
+                String::from_utf8_lossy(&key),
                 value,
                 expiry,
                 get_time()
             );
 
             // If the key is already expired, skip it
-            if !expiry.is_none() && expiry.unwrap() < get_time() {
+            if expiry.is_some() && expiry.unwrap() < get_time() {
                 continue;
             }
 
-            // Insert the key-value pair into the data
-            self.data.insert(key, (value, expiry));
+            // Insert the key-value pair into the data of the selected database
+            self.data
+                .entry(self.db)
+                .or_default()
+                .insert(key, (value, expiry));
         }
 
         Ok(())
     }
+
+    /// Verifies the 8-byte little-endian CRC64 checksum that follows the end-of-file opcode.
+    /// The checksum covers every byte of the file up to and including the end-of-file opcode.
+    /// Files without a checksum, or with a zero checksum (checksums disabled), are accepted as is.
+    fn parse_checksum(&self, cursor: &Cursor<&Vec<u8>>) -> Result<(), Box<dyn std::error::Error>> {
+        let data = cursor.get_ref();
+        let end = cursor.position() as usize;
+        let Some(bytes) = data.get(end..end + 8) else {
+            return Ok(());
+        };
+
+        let expected = LittleEndian::read_u64(bytes);
+        let actual = crc64::checksum(&data[..end]);
+        if expected == 0 || expected == actual {
+            return Ok(());
+        }
+
+        let message = format!(
+            "RDB checksum mismatch: expected {:#018x}, computed {:#018x}",
+            expected, actual
+        );
+        if self.verify_checksum {
+            return Err(message.into());
+        }
+        println!("[WARNING] {}", message);
+        Ok(())
+    }
+
     async fn parse_select_db(
-        &self,
+        &mut self,
         cursor: &mut Cursor<&Vec<u8>>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         // DB NUMBER
-        cursor.read_u8().await?; // We essentially skip over this
+        self.db = read_int(cursor).await? as usize;
         Ok(())
     }
 }
@@ -186,29 +242,32 @@ fn get_time() -> u128 {
 // HELPERS
 // -------
 
-async fn read_int(cursor: &mut Cursor<&Vec<u8>>) -> Result<u32, Box<dyn std::error::Error>> {
+async fn read_int(cursor: &mut Cursor<&Vec<u8>>) -> Result<u64, Box<dyn std::error::Error>> {
     let n = read_length_encoding(cursor).await?;
-    return Ok(n.0);
+    Ok(n.0)
 }
 
 async fn read_length_encoding(
     cursor: &mut Cursor<&Vec<u8>>,
-) -> Result<(u32, bool), Box<dyn std::error::Error>> {
+) -> Result<(u64, bool), Box<dyn std::error::Error>> {
     let byte = cursor.read_u8().await?; // Read the first byte
     let two_most_significant_bits = (byte & 0xC0) >> 6; // Get the two most significant bits of the byte
 
     let mut is_encoded = false;
-    let length: u32;
+    let length: u64;
     match two_most_significant_bits {
-        0x0 => length = (byte & 0x3F) as u32, // The next 6 bits are the length
-        0x02 => {
-            // Discard the 6 bits, the next 32 bits (4 bytes) are the length
-            length = cursor.read_u32_le().await?;
-        }
+        0x0 => length = (byte & 0x3F) as u64, // The next 6 bits are the length
+        0x02 => match byte {
+            // The next 32 bits (4 bytes) are the big-endian length
+            0x80 => length = cursor.read_u32().await? as u64,
+            // The next 64 bits (8 bytes) are the big-endian length
+            0x81 => length = cursor.read_u64().await?,
+            _ => return Err(format!("not supported length encoding: {:#04x}", byte).into()),
+        },
         0x01 => {
             // Read one additional byte, the combined 14 bits are the length
             let next_byte = cursor.read_u8().await?;
-            let other_len = (((byte & 0x3F) << 8) | next_byte) as u32;
+            let other_len = (((byte & 0x3F) as u64) << 8) | next_byte as u64;
             length = other_len;
         }
         _ => {
@@ -218,11 +277,12 @@ async fn read_length_encoding(
                 0x01 => length = 2,
                 0x02 => length = 4,
                 _ => {
-                    panic!(
+                    return Err(format!(
                         "not supported special length encoding {}: {}",
                         (byte & 0xC0) >> 6,
                         byte & 0x3F
                     )
+                    .into())
                 }
             }
         }
@@ -231,12 +291,407 @@ async fn read_length_encoding(
     Ok((length, is_encoded))
 }
 
+/// The value type bytes that precede each key-value pair in the RDB file
+mod value_type {
+    pub const STRING: u8 = 0;
+    pub const LIST: u8 = 1;
+    pub const SET: u8 = 2;
+    pub const HASH: u8 = 4;
+    pub const ZSET_2: u8 = 5;
+    pub const INTSET: u8 = 11;
+    pub const STREAM_LISTPACKS: u8 = 15;
+    pub const STREAM_LISTPACKS_2: u8 = 19;
+    pub const STREAM_LISTPACKS_3: u8 = 21;
+}
+
+/// Reads a value of the given value type and converts it to the corresponding `Type`
+async fn read_value(
+    value_type: u8,
+    cursor: &mut Cursor<&Vec<u8>>,
+) -> Result<Type, Box<dyn std::error::Error>> {
+    let value = match value_type {
+        value_type::STRING => Type::BulkString(read_encoded_bytes(cursor).await?),
+        value_type::LIST => {
+            // The length of the list, followed by each element as a string
+            let len = read_int(cursor).await?;
+            let mut list = Vec::new();
+            for _ in 0..len {
+                list.push(read_encoded_string(cursor).await?);
+            }
+            Type::List(list)
+        }
+        value_type::SET => {
+            // The size of the set, followed by each member as a string
+            let len = read_int(cursor).await?;
+            let mut set = HashSet::new();
+            for _ in 0..len {
+                set.insert(Type::BulkString(read_encoded_bytes(cursor).await?));
+            }
+            Type::Set(set)
+        }
+        value_type::HASH => {
+            // The size of the hash, followed by each field and value as strings
+            let len = read_int(cursor).await?;
+            let mut hash = HashMap::new();
+            for _ in 0..len {
+                let field = read_encoded_string(cursor).await?;
+                let value = read_encoded_string(cursor).await?;
+                hash.insert(field, value);
+            }
+            Type::Hash(hash)
+        }
+        value_type::ZSET_2 => {
+            // The size of the sorted set, followed by each member as a string and its score as a binary double
+            let len = read_int(cursor).await?;
+            let mut members = Vec::new();
+            for _ in 0..len {
+                let member = read_encoded_string(cursor).await?;
+                let score = cursor.read_f64_le().await?;
+                members.push((member, score));
+            }
+            // Keep the members ordered by score, and then lexically
+            members.sort_by(|(a, x), (b, y)| x.total_cmp(y).then_with(|| a.cmp(b)));
+            Type::SortedSet(members)
+        }
+        value_type::STREAM_LISTPACKS
+        | value_type::STREAM_LISTPACKS_2
+        | value_type::STREAM_LISTPACKS_3 => Type::Stream(read_stream(value_type, cursor).await?),
+        value_type::INTSET => {
+            // An intset is a string holding the encoding (the byte width of each integer),
+            // the number of integers, and the little-endian integers themselves
+            let length = read_int(cursor).await?;
+            let mut buf = vec![0u8; length as usize];
+            cursor.read_exact(&mut buf).await?;
+            Type::Set(parse_intset(&buf)?)
+        }
+        _ => return Err(format!("Unsupported RDB value type: {}", value_type).into()),
+    };
+    Ok(value)
+}
+
+/// The flags of a stream entry stored in a listpack
+mod stream_flag {
+    /// The entry was deleted
+    pub const DELETED: u64 = 1;
+    /// The entry has the same fields as the master entry of its listpack, so only its values are stored
+    pub const SAME_FIELDS: u64 = 2;
+}
+
+/// The maximum number of entries written to a single listpack of a stream
+const STREAM_NODE_MAX_ENTRIES: usize = 100;
+
+/// Reads a stream, stored as listpacks keyed by the ID of their master entry,
+/// followed by the number of entries, the last ID and (in newer versions) more metadata.
+/// Streams with consumer groups are not supported.
+async fn read_stream(
+    value_type: u8,
+    cursor: &mut Cursor<&Vec<u8>>,
+) -> Result<Stream, Box<dyn std::error::Error>> {
+    let mut entries = Vec::new();
+    let nodes = read_int(cursor).await?;
+    for _ in 0..nodes {
+        let key = read_encoded_bytes(cursor).await?;
+        if key.len() != 16 {
+            return Err(format!("Invalid stream node key of {} bytes", key.len()).into());
+        }
+        let master = StreamID::from_parts(
+            BigEndian::read_u64(&key[..8]),
+            BigEndian::read_u64(&key[8..]),
+        );
+        let node = listpack::decode(&read_encoded_bytes(cursor).await?)?;
+        entries.extend(read_stream_node(&master, &node)?);
+    }
+
+    let _len = read_int(cursor).await?;
+    let milliseconds = read_int(cursor).await?;
+    let sequence = read_int(cursor).await?;
+    if value_type != value_type::STREAM_LISTPACKS {
+        // The first ID, the maximal deleted ID and the number of entries ever added
+        for _ in 0..5 {
+            read_int(cursor).await?;
+        }
+    }
+    if read_int(cursor).await? != 0 {
+        return Err("Unsupported stream with consumer groups".into());
+    }
+
+    Ok(Stream {
+        entries,
+        last_id: StreamID::from_parts(milliseconds, sequence),
+    })
+}
+
+/// Reads the entries of a stream listpack. The listpack starts with the master entry:
+/// the number of live and deleted entries, the master fields and a terminating 0.
+/// Each entry then holds its flags, its ID relative to the master ID, its fields and values
+/// (or only its values if it has the master fields), and its number of elements.
+fn read_stream_node(
+    master: &StreamID,
+    node: &[Vec<u8>],
+) -> Result<Vec<StreamEntry>, Box<dyn std::error::Error>> {
+    let mut elements = node.iter();
+    let mut next = || -> Result<String, Box<dyn std::error::Error>> {
+        let element = elements
+            .next()
+            .ok_or("Invalid stream node: missing element")?;
+        Ok(String::from_utf8_lossy(element).to_string())
+    };
+
+    let count = next()?.parse::<u64>()? + next()?.parse::<u64>()?;
+    let master_fields = (0..next()?.parse::<u64>()?)
+        .map(|_| next())
+        .collect::<Result<Vec<_>, _>>()?;
+    next()?; // The end of the master entry
+
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let flags = next()?.parse::<u64>()?;
+        let id = StreamID::from_parts(
+            master
+                .milliseconds
+                .wrapping_add_signed(next()?.parse::<i64>()?),
+            master.sequence.wrapping_add_signed(next()?.parse::<i64>()?),
+        );
+        let mut fields = HashMap::new();
+        if flags & stream_flag::SAME_FIELDS != 0 {
+            for field in &master_fields {
+                fields.insert(field.clone(), next()?);
+            }
+        } else {
+            for _ in 0..next()?.parse::<u64>()? {
+                fields.insert(next()?, next()?);
+            }
+        }
+        next()?; // The number of elements of the entry
+        if flags & stream_flag::DELETED == 0 {
+            entries.push((id, fields));
+        }
+    }
+    Ok(entries)
+}
+
+// --------
+// ENCODING
+// --------
+
+/// The RDB version written by the encoder
+const VERSION: &str = "0011";
+
+/// Encodes the key-value pairs (with their expiry), grouped by database number, as an RDB file.
+/// This is the inverse of `parse`, and expiries are absolute Unix timestamps in milliseconds.
+/// Only the value types that can be parsed back are written; other values (such as streams) are skipped.
+pub fn encode(data: &Data) -> Vec<u8> {
+    let mut bytes = MAGIC_BYTES.to_vec();
+    bytes.extend(VERSION.as_bytes());
+
+    // Write the databases in order, so that the output is deterministic
+    let mut dbs: Vec<_> = data.iter().filter(|(_, data)| !data.is_empty()).collect();
+    dbs.sort_by_key(|(db, _)| **db);
+    for (db, data) in dbs {
+        let mut entries = Vec::new();
+        let (mut written, mut expires) = (0, 0);
+        for (key, (value, expiry)) in data {
+            let Some((value_type, value)) = encode_value(value) else {
+                println!(
+                    "[WARNING] Skipping unsupported value of key {} in RDB",
+                    String::from_utf8_lossy(key)
+                );
+                continue;
+            };
+            if let Some(expiry) = expiry {
+                entries.push(0xFC);
+                entries.extend((*expiry as u64).to_le_bytes());
+                expires += 1;
+            }
+            entries.push(value_type);
+            write_string(&mut entries, key);
+            entries.extend(value);
+            written += 1;
+        }
+
+        bytes.push(0xFE);
+        write_length(&mut bytes, *db);
+        bytes.push(0xFB);
+        write_length(&mut bytes, written);
+        write_length(&mut bytes, expires);
+        bytes.extend(entries);
+    }
+
+    // End of the file, followed by the checksum
+    bytes.push(0xFF);
+    let checksum = crc64::checksum(&bytes);
+    bytes.extend(checksum.to_le_bytes());
+    bytes
+}
+
+/// Encodes the value, returning its value type byte and the encoded contents.
+/// Returns `None` if the value cannot be stored in the RDB file.
+fn encode_value(value: &Type) -> Option<(u8, Vec<u8>)> {
+    let mut bytes = Vec::new();
+    let value_type = match value {
+        Type::BulkString(s) => {
+            write_string(&mut bytes, s);
+            value_type::STRING
+        }
+        Type::List(list) => {
+            write_length(&mut bytes, list.len());
+            for element in list {
+                write_string(&mut bytes, element.as_bytes());
+            }
+            value_type::LIST
+        }
+        Type::Set(set) => {
+            write_length(&mut bytes, set.len());
+            for member in set {
+                let Type::BulkString(member) = member else {
+                    return None;
+                };
+                write_string(&mut bytes, member);
+            }
+            value_type::SET
+        }
+        Type::Hash(hash) => {
+            write_length(&mut bytes, hash.len());
+            for (field, value) in hash {
+                write_string(&mut bytes, field.as_bytes());
+                write_string(&mut bytes, value.as_bytes());
+            }
+            value_type::HASH
+        }
+        Type::SortedSet(members) => {
+            write_length(&mut bytes, members.len());
+            for (member, score) in members {
+                write_string(&mut bytes, member.as_bytes());
+                bytes.extend(score.to_le_bytes());
+            }
+            value_type::ZSET_2
+        }
+        Type::Stream(stream) => {
+            write_stream(&mut bytes, stream);
+            value_type::STREAM_LISTPACKS
+        }
+        _ => return None,
+    };
+    Some((value_type, bytes))
+}
+
+/// Writes the stream as listpacks of up to `STREAM_NODE_MAX_ENTRIES` entries, each keyed by the ID
+/// of its first entry, followed by the number of entries, the last ID and no consumer groups.
+/// The fields of the first entry are the master fields of the listpack.
+fn write_stream(bytes: &mut Vec<u8>, stream: &Stream) {
+    let nodes = stream.entries.chunks(STREAM_NODE_MAX_ENTRIES);
+    write_length(bytes, nodes.len());
+    for node in nodes {
+        let master = &node[0].0;
+        let master_fields: Vec<&String> = node[0].1.keys().collect();
+        let mut elements = vec![
+            node.len().to_string(),
+            "0".to_string(),
+            master_fields.len().to_string(),
+        ];
+        elements.extend(master_fields.iter().map(|field| field.to_string()));
+        elements.push("0".to_string());
+
+        for (id, fields) in node {
+            let same_fields = fields.len() == master_fields.len()
+                && master_fields
+                    .iter()
+                    .all(|field| fields.contains_key(*field));
+            let flags = match same_fields {
+                true => stream_flag::SAME_FIELDS,
+                false => 0,
+            };
+            let start = elements.len();
+            elements.push(flags.to_string());
+            elements.push((id.milliseconds.wrapping_sub(master.milliseconds) as i64).to_string());
+            elements.push((id.sequence.wrapping_sub(master.sequence) as i64).to_string());
+            if same_fields {
+                elements.extend(master_fields.iter().map(|field| fields[*field].clone()));
+            } else {
+                elements.push(fields.len().to_string());
+                for (field, value) in fields {
+                    elements.push(field.clone());
+                    elements.push(value.clone());
+                }
+            }
+            elements.push((elements.len() - start).to_string());
+        }
+
+        let mut key = master.milliseconds.to_be_bytes().to_vec();
+        key.extend(master.sequence.to_be_bytes());
+        write_string(bytes, &key);
+        write_string(bytes, &listpack::encode(&elements));
+    }
+
+    write_length(bytes, stream.entries.len());
+    write_length(bytes, stream.last_id.milliseconds as usize);
+    write_length(bytes, stream.last_id.sequence as usize);
+    write_length(bytes, 0);
+}
+
+/// Writes the length encoding of the given length (in 1, 2, 5 or 9 bytes)
+fn write_length(bytes: &mut Vec<u8>, len: usize) {
+    match len {
+        0..=0x3F => bytes.push(len as u8),
+        0x40..=0x3FFF => bytes.extend([0x40 | (len >> 8) as u8, len as u8]),
+        0x4000..=0xFFFF_FFFF => {
+            bytes.push(0x80);
+            bytes.extend((len as u32).to_be_bytes());
+        }
+        _ => {
+            bytes.push(0x81);
+            bytes.extend((len as u64).to_be_bytes());
+        }
+    }
+}
+
+/// Writes the string, prefixed with its length encoding
+fn write_string(bytes: &mut Vec<u8>, s: &[u8]) {
+    write_length(bytes, s.len());
+    bytes.extend(s);
+}
+
+/// Parses the members of an intset encoded set
+fn parse_intset(buf: &[u8]) -> Result<HashSet<Type>, Box<dyn std::error::Error>> {
+    if buf.len() < 8 {
+        return Err("Invalid intset: missing header".into());
+    }
+    let encoding = LittleEndian::read_u32(&buf[0..4]) as usize;
+    let len = LittleEndian::read_u32(&buf[4..8]) as usize;
+    let contents = &buf[8..];
+    if !matches!(encoding, 2 | 4 | 8) || contents.len() != encoding * len {
+        return Err(format!("Invalid intset: encoding {} with {} members", encoding, len).into());
+    }
+
+    let members = contents
+        .chunks(encoding)
+        .map(|int| {
+            let int = match encoding {
+                2 => LittleEndian::read_i16(int) as i64,
+                4 => LittleEndian::read_i32(int) as i64,
+                _ => LittleEndian::read_i64(int),
+            };
+            Type::BulkString(int.to_string().into())
+        })
+        .collect();
+    Ok(members)
+}
+
+/// Reads a string as text, replacing any invalid UTF-8 sequences
 async fn read_encoded_string(
     cursor: &mut Cursor<&Vec<u8>>,
 ) -> Result<String, Box<dyn std::error::Error>> {
+    let bytes = read_encoded_bytes(cursor).await?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// Reads a string as raw bytes, so that binary-safe keys and values are kept as is
+async fn read_encoded_bytes(
+    cursor: &mut Cursor<&Vec<u8>>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let length = read_length_encoding(cursor)
         .await
-        .expect("Failed to read length");
+        .map_err(|e| format!("Failed to read length: {}", e))?;
     // println!("Length: {:?}", length);
     let str = match length {
         (len, false) => {
@@ -245,8 +700,8 @@ async fn read_encoded_string(
             cursor
                 .read_exact(&mut buf)
                 .await
-                .expect("Failed to read string");
-            String::from_utf8_lossy(&buf).to_string()
+                .map_err(|e| format!("Failed to read string: {}", e))?;
+            buf
         }
         (len, true) => {
             // Encoded, read the string as base64
@@ -254,20 +709,24 @@ async fn read_encoded_string(
             cursor
                 .read_exact(&mut buf)
                 .await
-                .expect("Failed to read string");
+                .map_err(|e| format!("Failed to read string: {}", e))?;
 
             let res = match len {
                 1 => buf[0] as i8 as i32,
                 2 => LittleEndian::read_i16(&buf) as i32,
                 4 => LittleEndian::read_i32(&buf),
-                _ => panic!("Invalid length for encoded string: {}", len),
+                _ => return Err(format!("Invalid length for encoded string: {}", len).into()),
             };
 
-            res.to_string()
+            res.to_string().into_bytes()
         }
     };
 
-    println!("Len: {:?}, String: {:?}", length, str);
+    println!(
+        "Len: {:?}, String: {:?}",
+        length,
+        String::from_utf8_lossy(&str)
+    );
 
     Ok(str)
 }
@@ -276,33 +735,219 @@ async fn read_encoded_string(
 // TESTS
 // -----
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-
-// #[tokio::test]
-// async fn test_rdb_default() {
-//     let bytes = helpers::base64_to_bytes(EMPTY_RDB);
-//     let rdb = parse(bytes).await.unwrap();
-//     assert_eq!(rdb.version, "0011");
-// }
-
-// #[tokio::test]
-// async fn test_rdb() {
-//     let bytes = [
-//         82, 69, 68, 73, 83, 48, 48, 48, 51, 250, 9, 114, 101, 100, 105, 115, 45, 118, 101, 114,
-//         5, 55, 46, 50, 46, 48, 250, 10, 114, 101, 100, 105, 115, 45, 98, 105, 116, 115, 192,
-//         64, 254, 0, 251, 3, 3, 252, 0, 156, 239, 18, 126, 1, 0, 0, 0, 9, 98, 108, 117, 101, 98,
-//         101, 114, 114, 121, 4, 112, 101, 97, 114, 252, 0, 12, 40, 138, 199, 1, 0, 0, 0, 4, 112,
-//         101, 97, 114, 9, 112, 105, 110, 101, 97, 112, 112, 108, 101, 252, 0, 12, 40, 138, 199,
-//         1, 0, 0, 0, 5, 103, 114, 97, 112, 101, 9, 98, 108, 117, 101, 98, 101, 114, 114, 121,
-//         255, 76, 205, 60, 203, 238, 60, 229, 217, 10,
-//     ];
-//     let rdb = parse(bytes.to_vec()).await.unwrap();
-//     assert_eq!(rdb.version, "0003");
-//     assert_eq!(rdb.data.len(), 1); // Only one key-value pair for now
-// }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers;
+
+    /// The contents of a small RDB file with the single key `pear` set to `apple`
+    const TEST_CONTENTS: [u8; 67] = [
+        82, 69, 68, 73, 83, 48, 48, 48, 51, 250, 9, 114, 101, 100, 105, 115, 45, 118, 101, 114, 5,
+        55, 46, 50, 46, 48, 250, 10, 114, 101, 100, 105, 115, 45, 98, 105, 116, 115, 192, 64, 254,
+        0, 251, 1, 0, 0, 4, 112, 101, 97, 114, 5, 97, 112, 112, 108, 101, 255, 98, 13, 59, 53, 179,
+        65, 228, 176, 10,
+    ];
+
+    /// Returns the test contents with the trailing checksum recomputed over the data.
+    /// The checksum bytes in `TEST_CONTENTS` do not match its data, as the file was edited by hand.
+    fn test_contents_with_valid_checksum() -> Vec<u8> {
+        let end = TEST_CONTENTS.iter().position(|b| *b == 0xFF).unwrap() + 1;
+        let mut bytes = TEST_CONTENTS[..end].to_vec();
+        bytes.extend(crc64::checksum(&bytes).to_le_bytes());
+        bytes
+    }
+
+    #[tokio::test]
+    async fn test_rdb_default() {
+        let bytes = helpers::base64_to_bytes(EMPTY_RDB);
+        let rdb = parse(bytes, true).await.unwrap();
+        assert_eq!(rdb.version, "0011");
+    }
+
+    #[tokio::test]
+    async fn should_verify_valid_checksum() {
+        let rdb = parse(test_contents_with_valid_checksum(), true)
+            .await
+            .unwrap();
+        assert_eq!(
+            rdb.data[&0].get(b"pear".as_slice()),
+            Some(&(Type::BulkString("apple".into()), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_checksum_mismatch_in_strict_mode() {
+        let mut bytes = test_contents_with_valid_checksum();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        assert!(parse(bytes, true).await.is_err());
+        assert!(parse(TEST_CONTENTS.to_vec(), true).await.is_err());
+    }
+
+    /// Builds an RDB file containing a single key of the given value type and encoded value
+    fn rdb_with_value(value_type: u8, key: &str, value: &[u8]) -> Vec<u8> {
+        let mut bytes = b"REDIS0003".to_vec();
+        bytes.extend([0xFE, 0, 0xFB, 1, 0, value_type, key.len() as u8]);
+        bytes.extend(key.as_bytes());
+        bytes.extend(value);
+        bytes.push(0xFF);
+        bytes
+    }
+
+    #[tokio::test]
+    async fn should_parse_list_value() {
+        let bytes = rdb_with_value(1, "list", &[2, 1, b'a', 1, b'b']);
+        let rdb = parse(bytes, true).await.unwrap();
+        assert_eq!(
+            rdb.data[&0].get(b"list".as_slice()),
+            Some(&(Type::List(vec!["a".into(), "b".into()]), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn should_parse_set_value() {
+        let bytes = rdb_with_value(2, "set", &[2, 1, b'a', 1, b'b']);
+        let rdb = parse(bytes, true).await.unwrap();
+        let expected = HashSet::from([Type::BulkString("a".into()), Type::BulkString("b".into())]);
+        assert_eq!(
+            rdb.data[&0].get(b"set".as_slice()),
+            Some(&(Type::Set(expected), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn should_parse_hash_value() {
+        let bytes = rdb_with_value(4, "hash", &[1, 1, b'f', 1, b'v']);
+        let rdb = parse(bytes, true).await.unwrap();
+        let expected = HashMap::from([("f".to_string(), "v".to_string())]);
+        assert_eq!(
+            rdb.data[&0].get(b"hash".as_slice()),
+            Some(&(Type::Hash(expected), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn should_parse_intset_value() {
+        // Encoding 2 (16-bit integers), 2 members: 1 and -2
+        let intset = [12, 2, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0xFE, 0xFF];
+        let bytes = rdb_with_value(11, "ints", &intset);
+        let rdb = parse(bytes, true).await.unwrap();
+        let expected = HashSet::from([Type::BulkString("1".into()), Type::BulkString("-2".into())]);
+        assert_eq!(
+            rdb.data[&0].get(b"ints".as_slice()),
+            Some(&(Type::Set(expected), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn should_parse_stream_skipping_deleted_entries() {
+        // A listpack with the master fields `f`, an entry with the same fields (5-1),
+        // a deleted entry (5-2) and an entry with its own fields and a lower sequence (6-0)
+        let node = listpack::encode(&[
+            "2", "1", "1", "f", "0", // Master entry
+            "2", "0", "0", "a", "4", // 5-1 f=a
+            "3", "0", "1", "b", "4", // 5-2 (deleted)
+            "0", "1", "-1", "1", "g", "c", "6", // 6-0 g=c
+        ]);
+        let mut value = vec![1, 16];
+        value.extend(5u64.to_be_bytes());
+        value.extend(1u64.to_be_bytes());
+        write_string(&mut value, &node);
+        // The length, the last ID, the first ID, the maximal deleted ID, the entries added and the groups
+        value.extend([2, 6, 0, 5, 1, 5, 2, 3, 0]);
+
+        let rdb = parse(rdb_with_value(19, "s", &value), true).await.unwrap();
+        let expected = Stream::new(vec![
+            (
+                StreamID::from_parts(5, 1),
+                HashMap::from([("f".to_string(), "a".to_string())]),
+            ),
+            (
+                StreamID::from_parts(6, 0),
+                HashMap::from([("g".to_string(), "c".to_string())]),
+            ),
+        ]);
+        assert_eq!(
+            rdb.data[&0].get(b"s".as_slice()),
+            Some(&(Type::Stream(expected), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_unsupported_value_type() {
+        let bytes = rdb_with_value(3, "zset", &[0]);
+        let err = parse(bytes, true).await.err().unwrap();
+        assert!(err.to_string().contains("Unsupported RDB value type: 3"));
+    }
+
+    #[tokio::test]
+    async fn should_return_error_for_truncated_data() {
+        let bytes = helpers::base64_to_bytes(EMPTY_RDB);
+        for len in [0, 7, 20, bytes.len() - 9] {
+            assert!(parse(bytes[..len].to_vec(), true).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn should_round_trip_encoded_data() {
+        let long = "x".repeat(20_000);
+        let set = HashSet::from([Type::BulkString("a".into()), Type::BulkString(vec![0xff])]);
+        let hash = HashMap::from([("f".to_string(), "v".repeat(100))]);
+        let zset = vec![("b".to_string(), -1.5), ("a".to_string(), 2.0)];
+        // Enough entries for several listpacks, with some entries having other fields
+        let mut stream = Stream::new(
+            (1..=250)
+                .map(|n| {
+                    let field = if n % 7 == 0 { "other" } else { "f" };
+                    let fields = HashMap::from([(field.to_string(), n.to_string())]);
+                    (
+                        StreamID::from_parts(1_700_000_000_000 + n / 3, n % 3),
+                        fields,
+                    )
+                })
+                .collect(),
+        );
+        stream.last_id = StreamID::from_parts(u64::MAX, 5);
+        let data = HashMap::from([
+            (
+                0,
+                HashMap::from([
+                    (b"s".to_vec(), (Type::BulkString(long.into()), None)),
+                    (b"l".to_vec(), (Type::List(vec!["a".into()]), None)),
+                    (
+                        vec![0xff, 0x00, 0xfe],
+                        (Type::BulkString(vec![0xc3, 0x28]), None),
+                    ),
+                    (
+                        b"expiring".to_vec(),
+                        (Type::Set(set), Some(get_time() + 60_000)),
+                    ),
+                ]),
+            ),
+            (
+                3,
+                HashMap::from([
+                    (b"h".to_vec(), (Type::Hash(hash), None)),
+                    (b"z".to_vec(), (Type::SortedSet(zset), None)),
+                    (b"st".to_vec(), (Type::Stream(stream), None)),
+                    (b"empty".to_vec(), (Type::Stream(Stream::new(vec![])), None)),
+                ]),
+            ),
+        ]);
+
+        let rdb = parse(encode(&data), true).await.unwrap();
+        assert_eq!(rdb.data, data);
+    }
+
+    #[tokio::test]
+    async fn should_accept_checksum_mismatch_in_lenient_mode() {
+        let rdb = parse(TEST_CONTENTS.to_vec(), false).await.unwrap();
+        assert_eq!(
+            rdb.data[&0].get(b"pear".as_slice()),
+            Some(&(Type::BulkString("apple".into()), None))
+        );
+    }
+}
 
 // TEST CONTENTS
 // [82, 69, 68, 73, 83, 48, 48, 48, 51, 250, 9, 114, 101, 100, 105, 115, 45, 118, 101, 114, 5, 55, 46, 50, 46, 48, 250, 10, 114, 101, 100, 105, 115, 45, 98, 105, 116, 115, 192, 64, 254, 0, 251, 1, 0, 0, 4, 112, 101, 97, 114, 5, 97, 112, 112, 108, 101, 255, 98, 13, 59, 53, 179, 65, 228, 176, 10]
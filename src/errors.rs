@@ -0,0 +1,122 @@
+// ------
+// ERRORS
+// ------
+
+/// The errors that can occur in the server.
+/// Unlike a boxed error, the variants can be matched on to handle specific failures,
+/// e.g. to wait for more data when a RESP value is incomplete.
+#[derive(Debug)]
+pub enum Error {
+    /// The data is not valid RESP
+    Protocol(String),
+    /// The data ends before the RESP value is complete, so more data is needed
+    Incomplete,
+    /// Reading from or writing to a stream failed
+    Io(std::io::Error),
+    /// The key holds a value of another type than the operation expects
+    WrongType,
+    /// The value is not an integer, or is out of range
+    NotInteger,
+}
+
+// Implement the `Display` trait for the `Error` type
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Protocol(message) => write!(f, "{}", message),
+            Error::Incomplete => write!(f, "Incomplete data: more bytes are needed"),
+            Error::Io(err) => write!(f, "{}", err),
+            Error::WrongType => write!(
+                f,
+                "WRONGTYPE Operation against a key holding the wrong kind of value"
+            ),
+            Error::NotInteger => write!(f, "ERR value is not an integer or out of range"),
+        }
+    }
+}
+
+// Implement the `Error` trait for the `Error` type
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// -----------
+// CONVERSIONS
+// -----------
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<crate::database::WrongType> for Error {
+    fn from(_: crate::database::WrongType) -> Self {
+        Error::WrongType
+    }
+}
+
+impl From<std::num::ParseIntError> for Error {
+    fn from(err: std::num::ParseIntError) -> Self {
+        Error::Protocol(err.to_string())
+    }
+}
+
+impl From<std::num::ParseFloatError> for Error {
+    fn from(err: std::num::ParseFloatError) -> Self {
+        Error::Protocol(err.to_string())
+    }
+}
+
+impl From<std::str::Utf8Error> for Error {
+    fn from(err: std::str::Utf8Error) -> Self {
+        Error::Protocol(err.to_string())
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Protocol(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Protocol(message.to_string())
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_into_boxed_error() {
+        let err: Box<dyn std::error::Error> = Error::from("invalid length '-2'").into();
+        assert_eq!(err.to_string(), "invalid length '-2'");
+    }
+
+    #[test]
+    fn should_convert_wrong_type_into_error_with_its_message() {
+        let err = Error::from(crate::database::WrongType);
+        assert!(matches!(err, Error::WrongType));
+        assert_eq!(err.to_string(), crate::database::WrongType.to_string());
+    }
+
+    #[test]
+    fn should_report_malformed_integer_as_protocol_error() {
+        assert!(matches!(
+            "abc".parse::<i64>().map_err(Error::from),
+            Err(Error::Protocol(_))
+        ));
+    }
+}
@@ -1,6 +1,8 @@
 // Library
 use base64::{prelude::BASE64_STANDARD, Engine};
 use rand::Rng;
+use std::time::Duration;
+use tokio::time::Instant;
 
 // ----------------
 // HELPER FUNCTIONS
@@ -40,7 +42,7 @@ pub fn split_host_and_port(
     let parts: Vec<&str> = addr.split(sep).collect();
 
     // Get the host and port parts
-    let host = parts.get(0).ok_or("Invalid address")?;
+    let host = parts.first().ok_or("Invalid address")?;
     let port = parts.get(1).ok_or("Invalid address")?;
     let port = port.parse::<u16>()?; // Convert port to u16
 
@@ -48,20 +50,120 @@ pub fn split_host_and_port(
     Ok((host.to_string(), port))
 }
 
+/// Timeouts longer than this (roughly 30 years) are treated as waiting forever
+const MAX_TIMEOUT: Duration = Duration::from_secs(86400 * 365 * 30);
+
+/// Returns the deadline the given timeout from now, for the blocking commands.
+/// Returns `None` if the timeout is too large for the timer, in which case the caller should wait forever.
+pub fn deadline(timeout: Duration) -> Option<Instant> {
+    match timeout > MAX_TIMEOUT {
+        true => None,
+        false => Instant::now().checked_add(timeout),
+    }
+}
+
 // ------------------------
 // BASE64 ENCODING/DECODING
 // ------------------------
 
 /// Convert a base64 encoded string to a byte vector
+#[allow(dead_code)]
 pub fn base64_to_bytes(base64: &str) -> Vec<u8> {
     BASE64_STANDARD.decode(base64).unwrap()
 }
 
-/// Convert a byte vector to a base64 encoded string
+// /// Convert a byte vector to a base64 encoded string
 // pub fn bytes_to_base64(bytes: &[u8]) -> String {
 //     BASE64_STANDARD.encode(bytes)
 // }
 
+// -------------
+// GLOB MATCHING
+// -------------
+
+/// Check whether the given text matches the glob-style pattern.
+/// The pattern supports the following special characters:
+/// - `*` matches any sequence of characters (including an empty one)
+/// - `?` matches exactly one character
+/// - `[abc]` matches one of the characters in the brackets, `[a-z]` matches a range,
+///   and `[^abc]` matches any character not in the brackets
+/// - `\` escapes the following character so that it is matched literally
+///
+/// ```rs
+/// assert!(glob_match("user:*", "user:42")); // true
+/// assert!(glob_match("h?llo", "hello")); // true
+/// assert!(glob_match("h[a-e]llo", "hallo")); // true
+/// ```
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_match_chars(&pattern, &text)
+}
+
+/// Recursively match the pattern characters against the text characters
+fn glob_match_chars(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => {
+            // Try every possible length for the sequence matched by the star
+            (0..=text.len()).any(|i| glob_match_chars(&pattern[1..], &text[i..]))
+        }
+        Some('?') => !text.is_empty() && glob_match_chars(&pattern[1..], &text[1..]),
+        Some('[') => {
+            let Some(c) = text.first() else {
+                return false;
+            };
+            match match_class(&pattern[1..], *c) {
+                Some((matched, rest)) => matched && glob_match_chars(rest, &text[1..]),
+                // An unterminated class is matched literally
+                None => *c == '[' && glob_match_chars(&pattern[1..], &text[1..]),
+            }
+        }
+        Some('\\') if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && glob_match_chars(&pattern[2..], &text[1..])
+        }
+        Some(p) => text.first() == Some(p) && glob_match_chars(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Match a character against a bracketed character class.
+/// The pattern should start just after the opening `[`.
+/// Returns whether the character matched and the rest of the pattern after the closing `]`,
+/// or `None` if the class is not terminated.
+fn match_class(pattern: &[char], c: char) -> Option<(bool, &[char])> {
+    let (negated, mut i) = match pattern.first() {
+        Some('^') => (true, 1),
+        _ => (false, 0),
+    };
+
+    let mut matched = false;
+    while i < pattern.len() {
+        match pattern[i] {
+            ']' => return Some((matched != negated, &pattern[i + 1..])),
+            '\\' if i + 1 < pattern.len() => {
+                matched |= pattern[i + 1] == c;
+                i += 2;
+            }
+            start if i + 2 < pattern.len() && pattern[i + 1] == '-' && pattern[i + 2] != ']' => {
+                let end = pattern[i + 2];
+                let (lo, hi) = if start <= end {
+                    (start, end)
+                } else {
+                    (end, start)
+                };
+                matched |= lo <= c && c <= hi;
+                i += 3;
+            }
+            other => {
+                matched |= other == c;
+                i += 1;
+            }
+        }
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,4 +226,37 @@ mod tests {
     //     let base64 = bytes_to_base64(bytes);
     //     assert_eq!(base64, "SGVsbG8gV29ybGQ=");
     // }
+
+    #[test]
+    fn should_glob_match_star() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("user:*", "user:42"));
+        assert!(!glob_match("user:*", "session:42"));
+        assert!(glob_match("*:42", "user:42"));
+    }
+
+    #[test]
+    fn should_glob_match_question_mark() {
+        assert!(glob_match("h?llo", "hello"));
+        assert!(glob_match("h?llo", "hallo"));
+        assert!(!glob_match("h?llo", "hllo"));
+        assert!(!glob_match("h?llo", "heello"));
+    }
+
+    #[test]
+    fn should_glob_match_character_classes() {
+        assert!(glob_match("h[ae]llo", "hello"));
+        assert!(!glob_match("h[ae]llo", "hillo"));
+        assert!(glob_match("h[a-e]llo", "hcllo"));
+        assert!(!glob_match("h[a-e]llo", "hfllo"));
+        assert!(glob_match("h[^e]llo", "hallo"));
+        assert!(!glob_match("h[^e]llo", "hello"));
+    }
+
+    #[test]
+    fn should_glob_match_escaped_characters() {
+        assert!(glob_match("what\\?", "what?"));
+        assert!(!glob_match("what\\?", "whats"));
+    }
 }
@@ -2,6 +2,7 @@
 mod commands;
 mod config;
 mod database;
+mod errors;
 mod helpers;
 mod parser;
 mod server;
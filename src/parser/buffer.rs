@@ -0,0 +1,382 @@
+// Library
+use super::{_parse, errors::ParserError, resp::Type, MAX_DEPTH};
+use crate::errors::Error;
+
+// ------
+// BUFFER
+// ------
+
+/// Accumulates the bytes read from a stream and yields complete RESP values.
+///
+/// A single RESP value may be split across multiple reads (or be larger than the read buffer),
+/// so the bytes are kept around until the whole value has been received.
+/// Only complete values are consumed; a partial value is left in the buffer for the next read.
+#[derive(Debug, Default)]
+pub struct Buffer {
+    /// The bytes that have been received but not yet parsed
+    data: Vec<u8>,
+    /// Whether the next value is the RDB file the master sends after `FULLRESYNC`.
+    /// The file is sent like a bulk string, but without the trailing CRLF.
+    expect_rdb: bool,
+    /// How far the next value has been framed, so that each read resumes where the last one stopped
+    framing: Framing,
+}
+
+impl Buffer {
+    /// Creates a new, empty buffer
+    pub fn new() -> Buffer {
+        Buffer::default()
+    }
+
+    /// Appends the bytes read from the stream to the buffer
+    pub fn extend(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    /// Returns true if there are no pending bytes in the buffer
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Frames the next value as the RDB file sent by the master on a full resynchronization
+    pub fn expect_rdb(&mut self) {
+        self.expect_rdb = true;
+        self.framing = Framing::default();
+    }
+
+    /// Parses and removes the next complete RESP value from the buffer, along with
+    /// the number of bytes it took up in the stream.
+    /// Returns `Ok(None)` if more data is needed to complete the value.
+    /// Returns an error if the data is not valid RESP. As the buffer cannot recover
+    /// from invalid data, all pending bytes are discarded in that case.
+    pub fn next_frame(&mut self) -> Result<Option<(Type, usize)>, Error> {
+        let len = match self.expect_rdb {
+            true => self.framing.rdb_len(&self.data),
+            false => self.framing.frame_len(&self.data),
+        };
+        let result = match len {
+            Ok(len) if self.expect_rdb => Ok((rdb_file(&self.data[..len]), len)),
+            Ok(len) => _parse(&self.data[..len]).map(|(value, _)| (value, len)),
+            // Keep the partial value until the rest of it is received
+            Err(Error::Incomplete) => return Ok(None),
+            Err(e) => Err(e),
+        };
+
+        self.framing = Framing::default();
+        match result {
+            Ok((value, len)) => {
+                self.data.drain(..len);
+                self.expect_rdb = false;
+                Ok(Some((value, len)))
+            }
+            Err(e) => {
+                self.data.clear();
+                Err(e)
+            }
+        }
+    }
+}
+
+// -------
+// FRAMING
+// -------
+
+/// The maximum length of a blob value, like Redis' default `proto-max-bulk-len` (512 MB)
+const MAX_BLOB_LENGTH: i64 = 512 * 1024 * 1024;
+
+/// The maximum number of elements of an aggregate value
+const MAX_AGGREGATE_LENGTH: i64 = 1024 * 1024;
+
+/// The maximum length of a line (a simple value or the header of a blob or aggregate value), excluding the CRLF
+const MAX_LINE_LENGTH: usize = 64 * 1024;
+
+/// The progress made in framing the value at the start of the pending bytes.
+/// Framing is iterative, so deeply nested values cannot overflow the stack,
+/// and it resumes from here on the next read instead of rescanning the bytes already framed.
+#[derive(Debug, Default)]
+struct Framing {
+    /// The offset of the next value to frame
+    pos: usize,
+    /// The number of values still expected by each enclosing aggregate, innermost last
+    remaining: Vec<i64>,
+    /// The offset up to which the line at `pos` has been searched for its CRLF
+    scanned: usize,
+}
+
+impl Framing {
+    /// Determines the number of bytes taken up by the RESP value at the start of the input.
+    /// Only the framing (type prefixes, lengths and CRLF terminators) is inspected, not the contents.
+    /// Returns `Error::Incomplete` if the input does not yet contain the complete value.
+    fn frame_len(&mut self, input: &[u8]) -> Result<usize, Error> {
+        loop {
+            let first_byte = *input.get(self.pos).ok_or(Error::Incomplete)?;
+            if !b"+-:_#,($!=*~>%".contains(&first_byte) {
+                return Err(format!("invalid first byte '{}'", first_byte as char).into());
+            }
+
+            // Every value starts with a CRLF terminated line
+            let line_end = self.line_end(input)?;
+            let mut end = line_end + 2;
+
+            match first_byte {
+                // Simple values consist of just the line
+                b'+' | b'-' | b':' | b'_' | b'#' | b',' | b'(' => {}
+
+                // Blob values are followed by `length` bytes of data and a CRLF
+                b'$' | b'!' | b'=' => {
+                    let length = parse_length(&input[self.pos + 1..line_end], MAX_BLOB_LENGTH)?;
+                    if length >= 0 {
+                        end += length as usize + 2;
+                        if input.len() < end {
+                            return Err(Error::Incomplete);
+                        }
+                    }
+                }
+
+                // Aggregate values are followed by `length` values (or `length` key-value pairs for maps)
+                _ => {
+                    let length =
+                        parse_length(&input[self.pos + 1..line_end], MAX_AGGREGATE_LENGTH)?;
+                    let count = match first_byte {
+                        b'%' => length.saturating_mul(2),
+                        _ => length,
+                    };
+                    if count > 0 {
+                        if self.remaining.len() >= MAX_DEPTH {
+                            return Err(ParserError::TooDeep(MAX_DEPTH).into());
+                        }
+                        self.remaining.push(count);
+                        self.advance(end);
+                        continue;
+                    }
+                }
+            }
+            self.advance(end);
+
+            // The value is complete, and so is every enclosing aggregate it was the last value of
+            loop {
+                match self.remaining.last_mut() {
+                    None => return Ok(self.pos),
+                    Some(count) if *count > 1 => {
+                        *count -= 1;
+                        break;
+                    }
+                    Some(_) => {
+                        self.remaining.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Determines the number of bytes taken up by the RDB file at the start of the input.
+    /// The file is sent as `$<length>\r\n` followed by its contents, without a trailing CRLF.
+    fn rdb_len(&mut self, input: &[u8]) -> Result<usize, Error> {
+        let first_byte = *input.first().ok_or(Error::Incomplete)?;
+        if first_byte != b'$' {
+            return Err(format!(
+                "invalid first byte '{}' for the RDB file",
+                first_byte as char
+            )
+            .into());
+        }
+        let line_end = self.line_end(input)?;
+        let length = parse_length(&input[1..line_end], MAX_BLOB_LENGTH)?;
+        if length < 0 {
+            return Err(format!("invalid length '{}' for the RDB file", length).into());
+        }
+        let data_end = line_end + 2 + length as usize;
+        match input.len() >= data_end {
+            true => Ok(data_end),
+            false => Err(Error::Incomplete),
+        }
+    }
+
+    /// Moves on to the value at the given offset
+    fn advance(&mut self, pos: usize) {
+        self.pos = pos;
+        self.scanned = pos;
+    }
+
+    /// Finds the CRLF that terminates the line at `pos`, and returns its offset.
+    /// The search resumes where the previous one stopped, and lines longer than `MAX_LINE_LENGTH` are rejected.
+    fn line_end(&mut self, input: &[u8]) -> Result<usize, Error> {
+        let from = self.scanned.max(self.pos);
+        let limit = input.len().min(self.pos + MAX_LINE_LENGTH + 2);
+        if let Some(offset) = input[from..limit]
+            .windows(2)
+            .position(|window| window == b"\r\n")
+        {
+            return Ok(from + offset);
+        }
+        if limit - self.pos >= MAX_LINE_LENGTH + 2 {
+            return Err(format!("line longer than {} bytes", MAX_LINE_LENGTH).into());
+        }
+        // The last byte may be the CR of a CRLF split across reads, so it is searched again
+        self.scanned = limit.saturating_sub(1).max(self.pos);
+        Err(Error::Incomplete)
+    }
+}
+
+/// Parses the length from the header line of a blob or aggregate value.
+/// The length must be at most `max`, and cannot be negative other than -1 (for null values).
+fn parse_length(bytes: &[u8], max: i64) -> Result<i64, Error> {
+    let length = std::str::from_utf8(bytes)?;
+    match length.parse::<i64>() {
+        Ok(parsed) if (-1..=max).contains(&parsed) => Ok(parsed),
+        _ => Err(format!("invalid length '{}'", length).into()),
+    }
+}
+
+/// Extracts the contents of the RDB file from its frame
+fn rdb_file(frame: &[u8]) -> Type {
+    let data_start = frame
+        .windows(2)
+        .position(|window| window == b"\r\n")
+        .map_or(frame.len(), |line_end| line_end + 2);
+    Type::RDBFile(frame[data_start..].to_vec())
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a command array from the arguments
+    fn command(args: &[&str]) -> Type {
+        Type::Array(
+            args.iter()
+                .map(|arg| Type::BulkString(arg.as_bytes().to_vec()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn should_parse_pipelined_commands_fed_one_byte_at_a_time() {
+        let input = b"*1\r\n$4\r\nPING\r\n*2\r\n$4\r\nECHO\r\n$3\r\nhey\r\n";
+        let mut buffer = Buffer::new();
+        let mut values = Vec::new();
+        for byte in input {
+            buffer.extend(&[*byte]);
+            while let Some(value) = buffer.next_frame().unwrap() {
+                values.push(value);
+            }
+        }
+        assert_eq!(
+            values,
+            vec![(command(&["PING"]), 14), (command(&["ECHO", "hey"]), 23)]
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn should_keep_partial_value_for_next_read() {
+        let mut buffer = Buffer::new();
+        buffer.extend(b"*2\r\n$3\r\nGET\r\n$3\r\nk");
+        assert!(buffer.next_frame().unwrap().is_none());
+        assert!(!buffer.is_empty());
+        buffer.extend(b"ey\r\n");
+        assert_eq!(
+            buffer.next_frame().unwrap(),
+            Some((command(&["GET", "key"]), 22))
+        );
+        assert!(buffer.next_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn should_distinguish_invalid_data_from_partial_data() {
+        let mut buffer = Buffer::new();
+        buffer.extend(b"*1\r\n$abc\r\n");
+        assert!(buffer.next_frame().is_err());
+        assert!(buffer.is_empty());
+
+        buffer.extend(b"?\r\n");
+        assert!(buffer.next_frame().is_err());
+    }
+
+    #[test]
+    fn should_frame_nested_and_null_values() {
+        let mut buffer = Buffer::new();
+        buffer.extend(b"%1\r\n+key\r\n*2\r\n:1\r\n$-1\r\n");
+        assert!(matches!(
+            buffer.next_frame().unwrap(),
+            Some((Type::Map(_), 23))
+        ));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn should_frame_rdb_file_without_trailing_crlf() {
+        let mut buffer = Buffer::new();
+        buffer.expect_rdb();
+        buffer.extend(b"$11\r\nREDIS0003\xFF\x00*1\r\n$4\r\nPING\r\n");
+        assert_eq!(
+            buffer.next_frame().unwrap(),
+            Some((Type::RDBFile(b"REDIS0003\xFF\x00".to_vec()), 16))
+        );
+        assert_eq!(buffer.next_frame().unwrap(), Some((command(&["PING"]), 14)));
+    }
+
+    #[test]
+    fn should_frame_rdb_like_bulk_strings_as_regular_values() {
+        let mut buffer = Buffer::new();
+        buffer.extend(b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$11\r\nREDIS0011ab\r\n");
+        assert_eq!(
+            buffer.next_frame().unwrap(),
+            Some((command(&["SET", "k", "REDIS0011ab"]), 38))
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn should_reject_deeply_nested_values() {
+        let mut buffer = Buffer::new();
+        buffer.extend(&b"*1\r\n".repeat(200_000));
+        assert!(matches!(buffer.next_frame(), Err(Error::Protocol(_))));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn should_reject_overlong_lines() {
+        let mut buffer = Buffer::new();
+        buffer.extend(b"+");
+        for _ in 0..64 {
+            buffer.extend(&[b'a'; 1024]);
+            assert!(buffer.next_frame().unwrap().is_none());
+        }
+        buffer.extend(b"a\r\n");
+        assert!(matches!(buffer.next_frame(), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    fn should_resume_framing_across_reads() {
+        let mut buffer = Buffer::new();
+        for chunk in [&b"*2\r\n*1\r"[..], b"\n:1\r\n$3\r", b"\nab", b"c\r\n"] {
+            assert!(buffer.next_frame().unwrap().is_none());
+            buffer.extend(chunk);
+        }
+        assert!(matches!(
+            buffer.next_frame().unwrap(),
+            Some((Type::Array(_), 21))
+        ));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn should_reject_invalid_and_oversized_lengths() {
+        for input in [
+            &b"$-2\r\nab\r\n"[..],
+            b"*-5\r\n",
+            b"%-2\r\n",
+            b"$536870913\r\n",
+            b"*1048577\r\n",
+        ] {
+            let mut buffer = Buffer::new();
+            buffer.extend(input);
+            assert!(buffer.next_frame().is_err(), "{:?}", input);
+        }
+    }
+}
@@ -1,3 +1,6 @@
+// Library
+use crate::errors::Error;
+
 // -------------
 // PARSER ERRORS
 // -------------
@@ -9,6 +12,10 @@ pub enum ParserError {
     EmptyInput,
     /// The first byte of the input data is invalid
     InvalidFirstByte(u8, u8), // Actual, Expected
+    /// The declared length is negative (other than -1 for null values)
+    InvalidLength(i64),
+    /// The aggregate values are nested deeper than the maximum depth
+    TooDeep(usize),
 }
 
 // Implement the `Display` trait for the `ParserError` type
@@ -24,9 +31,25 @@ impl std::fmt::Display for ParserError {
                     *actual as char, *expected as char
                 )
             }
+
+            ParserError::InvalidLength(length) => write!(f, "Invalid length: {}", length),
+
+            ParserError::TooDeep(depth) => {
+                write!(f, "Aggregate values nested deeper than {} levels", depth)
+            }
         }
     }
 }
 
 // Implement the `Error` trait for the `ParserError` type
 impl std::error::Error for ParserError {}
+
+// Convert the `ParserError` into the crate-level `Error`
+impl From<ParserError> for Error {
+    fn from(err: ParserError) -> Self {
+        match err {
+            ParserError::EmptyInput => Error::Incomplete,
+            err => Error::Protocol(err.to_string()),
+        }
+    }
+}
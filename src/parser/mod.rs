@@ -1,28 +1,54 @@
 // Library
+use crate::errors::Error;
+use errors::ParserError;
+use std::cell::Cell;
+pub mod buffer;
 mod errors;
 pub mod reader;
 pub mod resp;
 
-/// Parses the given input data and returns the corresponding `RESPData` and the remaining input
-fn _parse(input: &[u8]) -> Result<(resp::Type, &[u8]), Box<dyn std::error::Error>> {
+/// The maximum nesting depth of aggregate values, so that parsing cannot overflow the stack
+const MAX_DEPTH: usize = 32;
+
+thread_local! {
+    /// The nesting depth of the value being parsed. As parsing never awaits, it is tracked per thread.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Parses the given input data and returns the corresponding `Type` and the remaining input.
+/// Aggregate values parse their elements through this function, so it also limits the nesting depth.
+fn _parse(input: &[u8]) -> Result<(resp::Type, &[u8]), Error> {
+    let depth = DEPTH.get();
+    if depth > MAX_DEPTH {
+        return Err(ParserError::TooDeep(MAX_DEPTH).into());
+    }
+    DEPTH.set(depth + 1);
+    let result = parse_value(input);
+    DEPTH.set(depth);
+    result
+}
+
+/// Parses the value at the start of the input according to its first byte
+fn parse_value(input: &[u8]) -> Result<(resp::Type, &[u8]), Error> {
     // Extract the first byte from the input, which indicates the data type
     let first_byte = input.first().ok_or("Empty input")?;
 
     // Match on the first_byte to determine the data type and parse the input accordingly
     match first_byte {
-        b'+' => resp::simple_string::parse(&input),
-        b'-' => resp::simple_error::parse(&input),
-        b':' => resp::integer::parse(&input),
-        b'$' => resp::bulk_string::parse(&input),
-        b'*' => resp::array::parse(&input),
-        b'_' => resp::null::parse(&input),
-        b'#' => resp::boolean::parse(&input),
-        b',' => resp::double::parse(&input),
-        b'(' => resp::big_number::parse(&input),
-        b'!' => resp::bulk_error::parse(&input),
-        b'=' => resp::verbatim_string::parse(&input),
-        b'%' => resp::map::parse(&input),
-        b'~' => resp::set::parse(&input),
+        b'+' => resp::simple_string::parse(input),
+        b'-' => resp::simple_error::parse(input),
+        b':' => resp::integer::parse(input),
+        b'$' => resp::bulk_string::parse(input),
+        b'*' => resp::array::parse(input),
+        b'_' => resp::null::parse(input),
+        b'#' => resp::boolean::parse(input),
+        b',' => resp::double::parse(input),
+        b'(' => resp::big_number::parse(input),
+        b'!' => resp::bulk_error::parse(input),
+        b'=' => resp::verbatim_string::parse(input),
+        b'%' => resp::map::parse(input),
+        b'~' => resp::set::parse(input),
+        b'>' => resp::push::parse(input),
         _ => Err(format!("Invalid first byte in {}", String::from_utf8_lossy(input)).into()),
     }
 }
@@ -31,8 +57,9 @@ fn _parse(input: &[u8]) -> Result<(resp::Type, &[u8]), Box<dyn std::error::Error
 // PARSE
 // -----
 
-/// Parses the given input data and returns the corresponding `RESPData`
-pub fn parse(input: &[u8]) -> Result<Vec<resp::Type>, Box<dyn std::error::Error>> {
+/// Parses the given input data and returns the corresponding `Type` values
+#[allow(dead_code)]
+pub fn parse(input: &[u8]) -> Result<Vec<resp::Type>, Error> {
     // The parsed data
     let mut data = Vec::new();
 
@@ -60,7 +87,7 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
@@ -74,6 +101,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_tell_incomplete_data_from_invalid_data() {
+        assert!(matches!(parse(b"$5\r\nhel"), Err(Error::Incomplete)));
+        assert!(matches!(parse(b":12"), Err(Error::Incomplete)));
+        assert!(matches!(parse(b"?12\r\n"), Err(Error::Protocol(_))));
+    }
+
     #[test]
     fn should_error_on_empty_input() {
         let input = b"\r\n";
@@ -128,6 +162,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_reject_deeply_nested_values() {
+        let nested = |depth: usize| [b"*1\r\n".repeat(depth), b":1\r\n".to_vec()].concat();
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+        assert!(matches!(parse(&nested(200_000)), Err(Error::Protocol(_))));
+    }
+
     #[test]
     fn should_parse_null_bulk_string() {
         let input = b"$-1\r\n";
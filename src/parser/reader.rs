@@ -1,5 +1,6 @@
 // Library
 use super::errors::ParserError;
+use crate::errors::Error;
 
 // ---------
 // CONSTANTS
@@ -21,7 +22,7 @@ pub struct BytesReader<'a> {
 }
 
 /// Create a new `BytesReader` instance
-pub fn read(input: &[u8]) -> BytesReader {
+pub fn read(input: &[u8]) -> BytesReader<'_> {
     BytesReader {
         slice: input,
         start_pos: 0,
@@ -51,6 +52,7 @@ impl<'a> BytesReader<'a> {
     /// let mut bytes = reader::read(input); // Create a new BytesReader instance
     /// let pos = bytes.find(&b'w').unwrap(); // => 6
     /// ```
+    #[allow(dead_code)]
     pub fn find(&mut self, bytes: &[u8]) -> Option<usize> {
         let pos = self.slice[self.start_pos..self.end_pos]
             .windows(bytes.len())
@@ -66,10 +68,8 @@ impl<'a> BytesReader<'a> {
     /// let mut bytes = reader::read(input); // Create a new BytesReader instance
     /// let (first, rest) = bytes.split(b'w').unwrap(); // => (b"hello ", b"world")
     /// ```   
-    pub fn split(
-        &mut self,
-        bytes: &[u8],
-    ) -> Result<(BytesReader, BytesReader), Box<dyn std::error::Error>> {
+    #[allow(dead_code)]
+    pub fn split(&mut self, bytes: &[u8]) -> Result<(BytesReader<'_>, BytesReader<'_>), Error> {
         let position = self.find(bytes).unwrap();
         let (first, rest) = self.slice[self.start_pos..].split_at(position);
         let (_, rest) = rest.split_at(bytes.len());
@@ -85,7 +85,7 @@ impl<'a> BytesReader<'a> {
     /// let mut bytes = reader::read(input);   // Create a new BytesReader instance
     /// let pos = bytes.find_crlf().unwrap();  // => 11
     /// ```
-    pub fn find_crlf(&mut self) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+    pub fn find_crlf(&mut self) -> Result<(usize, usize), Error> {
         let start_pos = self
             .slice
             .windows(CRLF.len())
@@ -97,7 +97,7 @@ impl<'a> BytesReader<'a> {
 
     // /// Split at the first CRLF sequence in the byte slice.
     // /// Return the byte slices before and after the CRLF sequence.
-    // pub fn split_crlf(&mut self) -> Result<(BytesReader, BytesReader), Box<dyn std::error::Error>> {
+    // pub fn split_crlf(&mut self) -> Result<(BytesReader, BytesReader), Error> {
     //     let (start, _) = self.find_crlf()?;
     //     let (first, rest) = self.slice.split_at(start);
     //     let (_, rest) = rest.split_at(CRLF.len());
@@ -112,10 +112,10 @@ impl<'a> BytesReader<'a> {
     /// let bytes = reader::read(input);   // Create a new BytesReader instance
     /// let first_byte = bytes.first().unwrap(); // => b'h'
     /// ```
-    pub fn first(&self) -> Result<u8, Box<dyn std::error::Error>> {
-        match self.slice.get(0) {
-            Some(b) => Ok(b.clone()),
-            None => Err(Box::new(ParserError::EmptyInput)),
+    pub fn first(&self) -> Result<u8, Error> {
+        match self.slice.first() {
+            Some(b) => Ok(*b),
+            None => Err(ParserError::EmptyInput.into()),
         }
     }
 
@@ -155,7 +155,7 @@ impl<'a> BytesReader<'a> {
         let byte_slice = &self.slice[self.start_pos..self.end_pos];
         self.start_pos = 0;
         self.end_pos = 0;
-        return byte_slice;
+        byte_slice
     }
 
     /// Return the byte slice as a string slice
@@ -188,11 +188,11 @@ impl<'a> BytesReader<'a> {
     /// let mut bytes = reader::read(input); // Create a new BytesReader instance
     /// let integer = bytes.slice(1, 6).parse::<i64>().unwrap(); // => 12345
     /// ```
-    pub fn parse<T: std::str::FromStr>(&mut self) -> Result<T, Box<dyn std::error::Error>>
+    pub fn parse<T: std::str::FromStr>(&mut self) -> Result<T, Error>
     where
-        T::Err: std::error::Error + 'static,
+        T::Err: Into<Error>,
     {
-        Ok(self.as_str()?.parse::<T>()?)
+        self.as_str()?.parse::<T>().map_err(Into::into)
     }
 }
 
@@ -225,6 +225,14 @@ impl std::fmt::Display for BytesReaderError {
 // Implement the `Error` trait for the `BytesReaderError` type
 impl std::error::Error for BytesReaderError {}
 
+// Convert the `BytesReaderError` into the crate-level `Error`
+impl From<BytesReaderError> for Error {
+    fn from(_: BytesReaderError) -> Self {
+        // The CRLF sequence may still be received
+        Error::Incomplete
+    }
+}
+
 // -----
 // TESTS
 // -----
@@ -234,7 +242,7 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
@@ -348,6 +356,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn should_parse_double() {
         let input = b",3.14\r\n";
         let mut bytes = read(input);
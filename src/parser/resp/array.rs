@@ -1,5 +1,6 @@
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{_parse, errors::ParserError, reader};
 
 /// The first byte of an array value.
@@ -20,7 +21,7 @@ const FIRST_BYTE: u8 = b'*';
 /// ```sh
 /// *3\r\n:1\r\n:2\r\n:3\r\n => [1, 2, 3]
 /// ```
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Check if the input is long enough to contain the array value
     if input.len() < 4 {
         return Err(ArrayParserError::InsufficientData(input.len()).into());
@@ -32,9 +33,7 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
     // Check if the input starts with the asterisk `*` character
     let first_byte = bytes.first()?;
     if first_byte != FIRST_BYTE {
-        return Err(Box::new(ParserError::InvalidFirstByte(
-            first_byte, FIRST_BYTE,
-        )));
+        return Err(ParserError::InvalidFirstByte(first_byte, FIRST_BYTE).into());
     }
 
     // Find the position of the first CRLF sequence and the start of the array data
@@ -47,6 +46,9 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
     if length == -1 {
         return Ok((Type::Null, &input[data_start_pos..]));
     }
+    if length < 0 {
+        return Err(ParserError::InvalidLength(length).into());
+    }
 
     // If the length is 0, the array is empty
     if length <= 0 {
@@ -101,6 +103,14 @@ impl std::fmt::Display for ArrayParserError {
 // Implement the `Error` trait for the array parser error
 impl std::error::Error for ArrayParserError {}
 
+// Convert the `ArrayParserError` into the crate-level `Error`
+impl From<ArrayParserError> for Error {
+    fn from(_: ArrayParserError) -> Self {
+        // The rest of the array may still be received
+        Error::Incomplete
+    }
+}
+
 // -----
 // TESTS
 // -----
@@ -110,7 +120,7 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
@@ -128,8 +138,8 @@ mod tests {
     fn should_parse_bulk_string_array() {
         let input = b"*2\r\n$5\r\nhello\r\n$5\r\nworld\r\n";
         let expected = vec![
-            Type::BulkString("hello".to_string()),
-            Type::BulkString("world".to_string()),
+            Type::BulkString("hello".into()),
+            Type::BulkString("world".into()),
         ];
         match parse(input) {
             Ok((actual, _)) => assert_eq!(actual, Type::Array(expected)),
@@ -198,7 +208,7 @@ mod tests {
         let expected = vec![
             Type::Integer(1),
             Type::SimpleString("OK".to_string()),
-            Type::BulkString("foobar".to_string()),
+            Type::BulkString("foobar".into()),
         ];
         match parse(input) {
             Ok((actual, _)) => assert_eq!(actual, Type::Array(expected)),
@@ -213,7 +223,7 @@ mod tests {
             Type::Integer(-23),
             Type::Array(vec![Type::Integer(1), Type::Integer(2), Type::Integer(3)]),
             Type::Array(vec![
-                Type::BulkString("hello".to_string()),
+                Type::BulkString("hello".into()),
                 Type::SimpleError("world".to_string()),
             ]),
         ];
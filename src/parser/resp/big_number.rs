@@ -1,5 +1,6 @@
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{errors::ParserError, reader};
 
 /// The first byte of a big number
@@ -22,28 +23,63 @@ const FIRST_BYTE: u8 = b'(';
 /// (1234567890\r\n // 1234567890
 /// (-1234567890\r\n // -1234567890
 /// ```
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+///
+/// The value is kept as a string of digits, as it may not fit in any native integer type.
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Create a reader to help extract information from the input byte slice
     let mut bytes = reader::read(input);
 
     // Check if the input starts with the left parenthesis `(` character
     let first_byte = bytes.first()?;
     if first_byte != FIRST_BYTE {
-        return Err(Box::new(ParserError::InvalidFirstByte(
-            first_byte, FIRST_BYTE,
-        )));
+        return Err(ParserError::InvalidFirstByte(first_byte, FIRST_BYTE).into());
     }
 
     // Find the position of the CRLF sequence
     let (crlf_pos, rest_pos) = bytes.find_crlf()?;
 
-    // Parse the big number
-    let big_number = bytes.slice(1, crlf_pos).as_str()?.parse::<i64>()?;
+    // Extract the big number and check that it is an optionally signed sequence of digits
+    let big_number = bytes.slice(1, crlf_pos).as_string()?;
+    let digits = big_number.strip_prefix(['+', '-']).unwrap_or(&big_number);
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(BigNumberParserError::InvalidDigits(big_number).into());
+    }
 
     // Return the big number and the remaining input byte slice
     Ok((Type::BigNumber(big_number), &input[rest_pos..]))
 }
 
+// ------
+// ERRORS
+// ------
+
+/// Errors that can occur while parsing a big number
+#[derive(Debug)]
+pub enum BigNumberParserError {
+    InvalidDigits(String),
+}
+
+// Implement the `Display` trait for `BigNumberParserError`
+impl std::fmt::Display for BigNumberParserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BigNumberParserError::InvalidDigits(value) => {
+                write!(f, "Invalid input. '{}' is not a valid big number", value)
+            }
+        }
+    }
+}
+
+// Implement the `Error` trait for `BigNumberParserError`
+impl std::error::Error for BigNumberParserError {}
+
+// Convert the `BigNumberParserError` into the crate-level `Error`
+impl From<BigNumberParserError> for Error {
+    fn from(err: BigNumberParserError) -> Self {
+        Error::Protocol(err.to_string())
+    }
+}
+
 // -----
 // TESTS
 // -----
@@ -53,14 +89,14 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
     #[test]
     fn should_parse_big_number() {
         let input = b"(1234567890\r\n";
-        let expected = Type::BigNumber(1234567890);
+        let expected = Type::BigNumber("1234567890".into());
         match parse(input) {
             Ok((actual, _)) => assert_eq!(actual, expected),
             Err(err) => show(err),
@@ -70,13 +106,35 @@ mod tests {
     #[test]
     fn should_parse_negative_big_number() {
         let input = b"(-1234567890\r\n";
-        let expected = Type::BigNumber(-1234567890);
+        let expected = Type::BigNumber("-1234567890".into());
         match parse(input) {
             Ok((actual, _)) => assert_eq!(actual, expected),
             Err(err) => show(err),
         }
     }
 
+    #[test]
+    fn should_parse_number_beyond_i64() {
+        let input = b"(3492890328409238509324850943850943825024385\r\n";
+        let expected = Type::BigNumber("3492890328409238509324850943850943825024385".into());
+        match parse(input) {
+            Ok((actual, rest)) => {
+                assert_eq!(actual, expected);
+                assert!(rest.is_empty());
+                assert_eq!(actual.as_bytes(), input);
+                assert_eq!(actual.to_string().as_bytes(), input);
+            }
+            Err(err) => show(err),
+        }
+    }
+
+    #[test]
+    fn should_error_on_invalid_digits() {
+        assert!(parse(b"(12a4\r\n").is_err());
+        assert!(parse(b"(-\r\n").is_err());
+        assert!(parse(b"(\r\n").is_err());
+    }
+
     #[test]
     fn should_error_on_invalid_first_byte() {
         let input = b"(1234567890";
@@ -1,5 +1,6 @@
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{
     errors::ParserError,
     reader::{self, CRLF},
@@ -23,21 +24,19 @@ const FIRST_BYTE: u8 = b'#';
 /// #t\r\n // true
 /// #f\r\n // false
 /// ```
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Check if the input is long enough to contain the boolean value
     if input.len() < 4 {
         return Err(BooleanParserError::InsufficientData(input.len()).into());
     }
-    
+
     // Create a reader to help extract information from the input byte slice
     let bytes = reader::read(input);
 
     // Check if the input starts with the hash `#` character
     let first_byte = bytes.first()?;
     if first_byte != FIRST_BYTE {
-        return Err(Box::new(ParserError::InvalidFirstByte(
-            first_byte, FIRST_BYTE,
-        )));
+        return Err(ParserError::InvalidFirstByte(first_byte, FIRST_BYTE).into());
     }
 
     // Create a reader to extract information from the bytes
@@ -46,17 +45,18 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
     // Find the position of the CRLF sequence
     let (crlf_pos, crlf_end_pos) = bytes.find_crlf()?;
 
-    // Extract the boolean value
-    let boolean = match input[1] {
-        b't' => true,
-        b'f' => false,
-        _ => return Err(BooleanParserError::InvalidBooleanCharacter(input[1]).into())
+    // Extract the boolean value. It must be exactly one byte between the prefix and the CRLF
+    let boolean = match bytes.slice(1, crlf_pos).as_bytes() {
+        [b't'] => true,
+        [b'f'] => false,
+        [] => return Err(BooleanParserError::MissingBooleanCharacter.into()),
+        [byte, ..] => return Err(BooleanParserError::InvalidBooleanCharacter(*byte).into()),
     };
 
     // Check if the boolean value is followed by the CRLF sequence
     if !input[crlf_pos..crlf_end_pos].starts_with(CRLF) {
         return Err(
-            BooleanParserError::InvalidTerminator(input[crlf_pos..crlf_end_pos].to_vec()).into()
+            BooleanParserError::InvalidTerminator(input[crlf_pos..crlf_end_pos].to_vec()).into(),
         );
     }
 
@@ -75,6 +75,7 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
 #[derive(Debug)]
 pub enum BooleanParserError {
     InsufficientData(usize),
+    MissingBooleanCharacter,
     InvalidBooleanCharacter(u8),
     InvalidTerminator(Vec<u8>),
 }
@@ -85,6 +86,8 @@ impl std::fmt::Display for BooleanParserError {
         match self {
             BooleanParserError::InsufficientData(len) => 
                 write!(f, "Insufficient data. The input length is {} but it should contain at least 4 bytes to represent boolean values", len),
+            BooleanParserError::MissingBooleanCharacter =>
+                write!(f, "Missing boolean value. Expected 't' or 'f' before the CRLF sequence"),
             BooleanParserError::InvalidBooleanCharacter(byte) => 
                 write!(f, "Invalid boolean value. Expected 't' or 'f' but got {}", *byte as char),
             BooleanParserError::InvalidTerminator(terminator) => 
@@ -96,6 +99,16 @@ impl std::fmt::Display for BooleanParserError {
 // Implement the `Error` trait for the boolean error
 impl std::error::Error for BooleanParserError {}
 
+// Convert the `BooleanParserError` into the crate-level `Error`
+impl From<BooleanParserError> for Error {
+    fn from(err: BooleanParserError) -> Self {
+        match err {
+            BooleanParserError::InsufficientData(_) => Error::Incomplete,
+            err => Error::Protocol(err.to_string()),
+        }
+    }
+}
+
 // -----
 // TESTS
 // -----
@@ -105,7 +118,7 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
@@ -116,6 +129,18 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn should_error_on_missing_boolean_value() {
+        assert!(parse(b"#\r\n").is_err());
+        match parse(b"#\r\n#t\r\n") {
+            Ok(res) => panic!("Expected an error but got {:?}", res),
+            Err(err) => assert_eq!(
+                err.to_string(),
+                BooleanParserError::MissingBooleanCharacter.to_string()
+            ),
+        }
+    }
+
     #[test]
     fn should_error_on_invalid_first_byte() {
         let input = b"invalid";
@@ -149,7 +174,7 @@ mod tests {
         let result = parse(input);
         assert!(result.is_err());
     }
-    
+
     #[test]
     fn should_error_on_invalid_input_crlf() {
         let input = b"#t\n";
@@ -166,5 +191,4 @@ mod tests {
             Err(err) => show(err),
         }
     }
-
 }
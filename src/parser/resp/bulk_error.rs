@@ -1,5 +1,6 @@
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{
     errors::ParserError,
     reader::{self, CRLF},
@@ -27,16 +28,14 @@ const FIRST_BYTE: u8 = b'!';
 /// ```
 ///
 /// As a convention the error begins with an uppercase word denoting the error type.
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Create a reader to help extract information from the input byte slice
     let mut bytes = reader::read(input);
 
     // Check if the input starts with the exclamation mark `!` character
     let first_byte = bytes.first()?;
     if first_byte != FIRST_BYTE {
-        return Err(Box::new(ParserError::InvalidFirstByte(
-            first_byte, FIRST_BYTE,
-        )));
+        return Err(ParserError::InvalidFirstByte(first_byte, FIRST_BYTE).into());
     }
 
     // Find the position of the CRLF sequence in the input
@@ -44,10 +43,23 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
 
     // Extract the length of the error message
     let length = bytes.slice(1, len_end_pos).parse::<i64>()?;
+    if length < 0 {
+        return Err(BulkErrorParserError::NegativeLength(length).into());
+    }
 
     // Calculate the position of the end of the error message
     let error_end_pos = data_start_pos + length as usize;
 
+    // Check if there is enough data to parse the error message and the final CRLF
+    if error_end_pos + CRLF.len() > input.len() {
+        return Err(BulkErrorParserError::InvalidLength(length as usize, input.len()).into());
+    }
+
+    // Check if the error message is followed by the CRLF sequence
+    if &input[error_end_pos..error_end_pos + CRLF.len()] != CRLF {
+        return Err(BulkErrorParserError::MissingTerminator.into());
+    }
+
     // Extract the error message
     let error_message = bytes
         .slice(data_start_pos, error_end_pos)
@@ -61,6 +73,54 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
     ))
 }
 
+// ------
+// ERRORS
+// ------
+
+/// Errors that can occur while parsing a bulk error
+#[derive(Debug)]
+pub enum BulkErrorParserError {
+    NegativeLength(i64),
+    InvalidLength(usize, usize),
+    MissingTerminator,
+}
+
+// Implement the `Display` trait for `BulkErrorParserError`
+impl std::fmt::Display for BulkErrorParserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BulkErrorParserError::NegativeLength(length) => {
+                write!(f, "Invalid input. Bulk error length cannot be {}", length)
+            }
+            BulkErrorParserError::InvalidLength(expected, actual) => {
+                write!(
+                    f,
+                    "Invalid input. Expected a bulk error of length {} but got {}",
+                    expected, actual
+                )
+            }
+            BulkErrorParserError::MissingTerminator => {
+                write!(f, "Invalid input. Expected CRLF after the bulk error")
+            }
+        }
+    }
+}
+
+// Implement the `Error` trait for `BulkErrorParserError`
+impl std::error::Error for BulkErrorParserError {}
+
+// Convert the `BulkErrorParserError` into the crate-level `Error`
+impl From<BulkErrorParserError> for Error {
+    fn from(err: BulkErrorParserError) -> Self {
+        match err {
+            BulkErrorParserError::InvalidLength(..) | BulkErrorParserError::MissingTerminator => {
+                Error::Incomplete
+            }
+            err => Error::Protocol(err.to_string()),
+        }
+    }
+}
+
 // -----
 // TESTS
 // -----
@@ -70,7 +130,7 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
@@ -84,6 +144,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_parse_empty_bulk_error() {
+        let input = b"!0\r\n\r\n";
+        match parse(input) {
+            Ok((data, rest)) => {
+                assert_eq!(data, Type::BulkError("".into()));
+                assert!(rest.is_empty());
+            }
+            Err(err) => show(err),
+        }
+    }
+
+    #[test]
+    fn should_return_remaining_input() {
+        let input = b"!21\r\nSYNTAX invalid syntax\r\n+OK\r\n";
+        match parse(input) {
+            Ok((data, rest)) => {
+                assert_eq!(data, Type::BulkError("SYNTAX invalid syntax".into()));
+                assert_eq!(rest, b"+OK\r\n");
+            }
+            Err(err) => show(err),
+        }
+    }
+
+    #[test]
+    fn should_round_trip_bulk_error() {
+        let error = Type::BulkError("SYNTAX invalid syntax".into());
+        let input = error.as_bytes();
+        assert_eq!(input, b"!21\r\nSYNTAX invalid syntax\r\n");
+        assert_eq!(error.to_string().as_bytes(), input);
+        match parse(&input) {
+            Ok((actual, rest)) => {
+                assert_eq!(actual, error);
+                assert!(rest.is_empty());
+            }
+            Err(err) => show(err),
+        }
+    }
+
+    #[test]
+    fn should_not_parse_invalid_length() {
+        assert!(parse(b"!abc\r\n").is_err());
+        assert!(parse(b"!-1\r\n").is_err());
+    }
+
+    #[test]
+    fn should_not_parse_insufficient_data() {
+        assert!(parse(b"!13\r\nError\r\n").is_err());
+    }
+
+    #[test]
+    fn should_not_parse_missing_crlf() {
+        assert!(parse(b"!5\r\nErrorXX").is_err());
+    }
+
     #[test]
     fn should_fail_to_parse_bulk_error() {
         let input = b"?13\r\nError message\r\n";
@@ -1,5 +1,6 @@
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{
     errors::ParserError,
     reader::{self, CRLF},
@@ -23,7 +24,7 @@ const FIRST_BYTE: u8 = b'$';
 /// ```sh
 /// 6\r\nfoobar\r\n => "foobar"
 /// ```
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Check if the input is long enough to contain the bulk string
     if input.len() < 4 {
         return Err(BulkStringParserError::InsufficientData(input.len()).into());
@@ -35,9 +36,7 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
     // Check if the input starts with the dollar `$` character
     let first_byte = bytes.first()?;
     if first_byte != FIRST_BYTE {
-        return Err(Box::new(ParserError::InvalidFirstByte(
-            first_byte, FIRST_BYTE,
-        )));
+        return Err(ParserError::InvalidFirstByte(first_byte, FIRST_BYTE).into());
     }
 
     // Find the position of the first CRLF sequence and the start of the bulk string data
@@ -53,28 +52,21 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
             &input[data_start_pos..], // Remaining bytes
         ));
     }
-
-    // Check if there is enough data to parse the bulk string
-    if data_start_pos + length as usize > input.len() {
-        return Err(BulkStringParserError::InvalidLength(length as usize, input.len()).into());
+    if length < 0 {
+        return Err(ParserError::InvalidLength(length).into());
     }
 
-    // Calculate the position of the end of the bulk string data
+    // Check if there is enough data to parse the bulk string and the final CRLF
     let data_end_pos = data_start_pos + length as usize;
+    if data_end_pos + CRLF.len() > input.len() {
+        return Err(BulkStringParserError::InvalidLength(length as usize, input.len()).into());
+    }
 
-    // Extract the bulk string from the input and convert it to a String
+    // Extract the bulk string from the input
     let bulk_string = bytes.slice(data_start_pos, data_end_pos).as_bytes();
 
-    // Check if the data begins with REDIS0011
-    if bulk_string.starts_with(b"REDIS0011") {
-        return Ok((
-            Type::RDBFile(bulk_string.to_vec()),
-            &input[data_end_pos..], // Remaining bytes
-        ));
-    }
-
-    // Convert the bulk string to a String
-    let bulk_string = std::str::from_utf8(bulk_string)?.to_string();
+    // Keep the raw bytes, as bulk strings are binary-safe
+    let bulk_string = bulk_string.to_vec();
 
     // Return the parsed bulk string and the remaining input
     Ok((
@@ -115,6 +107,14 @@ impl std::fmt::Display for BulkStringParserError {
 // Implement the `Error` trait for `BulkStringParserError`
 impl std::error::Error for BulkStringParserError {}
 
+// Convert the `BulkStringParserError` into the crate-level `Error`
+impl From<BulkStringParserError> for Error {
+    fn from(_: BulkStringParserError) -> Self {
+        // The rest of the data may still be received
+        Error::Incomplete
+    }
+}
+
 // -----
 // TESTS
 // -----
@@ -124,14 +124,14 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
     #[test]
     fn should_parse_bulk_string() {
         let input = b"$6\r\nfoobar\r\n";
-        let expected = Type::BulkString("foobar".to_string());
+        let expected = Type::BulkString("foobar".into());
         match parse(input) {
             Ok((actual, _)) => assert_eq!(actual, expected),
             Err(error) => show(error),
@@ -141,13 +141,28 @@ mod tests {
     #[test]
     fn should_parse_empty_bulk_string() {
         let input = b"$0\r\n\r\n";
-        let expected = Type::BulkString("".to_string());
+        let expected = Type::BulkString("".into());
         match parse(input) {
             Ok((actual, _)) => assert_eq!(actual, expected),
             Err(error) => show(error),
         }
     }
 
+    #[test]
+    fn should_round_trip_binary_data() {
+        let data = b"\xFF\xFEbinary\r\ndata\x00".to_vec();
+        let input = Type::BulkString(data.clone()).as_bytes();
+        assert_eq!(input, b"$15\r\n\xFF\xFEbinary\r\ndata\x00\r\n");
+        match parse(&input) {
+            Ok((actual, rest)) => {
+                assert_eq!(actual, Type::BulkString(data));
+                assert!(rest.is_empty());
+                assert_eq!(actual.as_bytes(), input);
+            }
+            Err(error) => show(error),
+        }
+    }
+
     #[test]
     fn should_parse_null_bulk_string() {
         let input = b"$-1\r\n";
@@ -187,4 +202,16 @@ mod tests {
         let input = b"$foobar";
         assert!(parse(input).is_err());
     }
+
+    #[test]
+    fn should_not_parse_negative_length() {
+        let input = b"$-2\r\nfoo\r\n";
+        assert!(matches!(parse(input), Err(Error::Protocol(_))));
+    }
+
+    #[test]
+    fn should_not_parse_data_without_final_crlf() {
+        let input = b"$3\r\nfoo";
+        assert!(matches!(parse(input), Err(Error::Incomplete)));
+    }
 }
@@ -1,5 +1,6 @@
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{errors::ParserError, reader};
 
 /// The first byte of a double value
@@ -41,16 +42,14 @@ const FIRST_BYTE: u8 = b',';
 /// ,-inf\r\n // -inf
 /// ,nan\r\n // NaN
 /// ```
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Create a reader to help extract information from the input byte slice
     let mut bytes = reader::read(input);
 
     // Check if the input starts with the comma `,` character
     let first_byte = bytes.first()?;
     if first_byte != FIRST_BYTE {
-        return Err(Box::new(ParserError::InvalidFirstByte(
-            first_byte, FIRST_BYTE,
-        )));
+        return Err(ParserError::InvalidFirstByte(first_byte, FIRST_BYTE).into());
     }
 
     // Find the position of the CRLF sequence
@@ -64,18 +63,18 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
         _ => (),
     }
 
-    // Check if the number is an integer
-    if !bytes.contains(&b'.') {
-        // Parse the double value so that it handles the exponents
-        let double = bytes.slice(1, crlf_pos).parse::<f64>()?;
-        // Convert the double to an integer
-        let integer = double as i64;
-        return Ok((Type::Integer(integer), &input[rest_pos..]));
-    }
-
-    // Parse the double value
+    // Parse the double value, which also handles the exponents
     let double = bytes.slice(1, crlf_pos).parse::<f64>()?;
 
+    // A number without a fractional part is an integer encoded as a double.
+    // A negative exponent can still make it fractional (e.g. `1e-2`), in which case it stays a double.
+    // So does a number too large for an integer (e.g. `1e30`), rather than being clamped.
+    // The upper bound is exclusive, as `i64::MAX as f64` rounds up to 2^63.
+    let fits = (i64::MIN as f64..i64::MAX as f64).contains(&double);
+    if !bytes.contains(&b'.') && double.fract() == 0.0 && fits {
+        return Ok((Type::Integer(double as i64), &input[rest_pos..]));
+    }
+
     // Return the double value and the remaining bytes
     Ok((Type::Double(double), &input[rest_pos..]))
 }
@@ -89,11 +88,12 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_parse_double() {
         let input = b",3.14\r\n";
         match parse(input) {
@@ -103,6 +103,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_parse_negative_double() {
         let input = b",-3.14\r\n";
         match parse(input) {
@@ -129,6 +130,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_negative_double_with_negative_exponent() {
+        let input = b",-3.14e-2\r\n";
+        match parse(input) {
+            Ok((actual, _)) => assert_eq!(actual, Type::Double(-0.0314)),
+            Err(err) => show(err),
+        }
+    }
+
+    // Integers encoded as doubles are coerced to `Integer`, as the client should return native integers
     #[test]
     fn test_parse_integer_as_double() {
         let input = b",3\r\n";
@@ -147,6 +158,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_integer_too_large_for_i64_as_double() {
+        match parse(b",1e30\r\n") {
+            Ok((actual, _)) => assert_eq!(actual, Type::Double(1e30)),
+            Err(err) => show(err),
+        }
+        match parse(b",-9223372036854775808\r\n") {
+            Ok((actual, _)) => assert_eq!(actual, Type::Integer(i64::MIN)),
+            Err(err) => show(err),
+        }
+        match parse(b",9223372036854775808\r\n") {
+            Ok((actual, _)) => assert_eq!(actual, Type::Double(9223372036854775808.0)),
+            Err(err) => show(err),
+        }
+    }
+
+    #[test]
+    fn test_parse_fractional_integer_with_negative_exponent() {
+        let input = b",1e-2\r\n";
+        match parse(input) {
+            Ok((actual, _)) => assert_eq!(actual, Type::Double(0.01)),
+            Err(err) => show(err),
+        }
+    }
+
     #[test]
     fn test_parse_positive_infinity() {
         let input = b",inf\r\n";
@@ -184,6 +220,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_round_trip_special_values() {
+        for (value, encoded) in [
+            (f64::INFINITY, b",inf\r\n".as_slice()),
+            (f64::NEG_INFINITY, b",-inf\r\n"),
+            (f64::NAN, b",nan\r\n"),
+        ] {
+            let double = Type::Double(value);
+            assert_eq!(double.as_bytes(), encoded);
+            assert_eq!(double.to_string().as_bytes(), encoded);
+            match parse(encoded) {
+                Ok((actual, _)) if value.is_nan() => assert!(actual.is_nan()),
+                Ok((actual, _)) => assert_eq!(actual, double),
+                Err(err) => show(err),
+            }
+        }
+    }
+
     #[test]
     fn test_parse_insufficient_data() {
         let input = b",3.14";
@@ -1,5 +1,6 @@
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{_parse, errors::ParserError, reader};
 use std::collections::HashMap;
 
@@ -21,7 +22,7 @@ const FIRST_BYTE: u8 = b'%';
 /// ```sh
 /// %2\r\n+key1\r\n:1\r\n+key2\r\n:2\r\n => {"key1": 1, "key2": 2}
 /// ```
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Create a reader to help extract information from the input byte slice
     let mut bytes = reader::read(input);
 
@@ -41,6 +42,9 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
     if length == -1 {
         return Ok((Type::Null, &input[data_start_pos..]));
     }
+    if length < 0 {
+        return Err(ParserError::InvalidLength(length).into());
+    }
 
     // If the length is 0, the map is empty
     if length <= 0 {
@@ -8,6 +8,7 @@ pub(crate) mod double;
 pub(crate) mod integer;
 pub(crate) mod map;
 pub(crate) mod null;
+pub(crate) mod push;
 pub(crate) mod set;
 pub(crate) mod simple_error;
 pub(crate) mod simple_string;
@@ -1,5 +1,6 @@
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{errors::ParserError, reader};
 
 // The first byte of a null value
@@ -19,16 +20,14 @@ const FIRST_BYTE: u8 = b'_';
 /// ```sh
 /// _\r\n
 /// ```
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Create a reader to help extract information from the input byte slice
     let mut bytes = reader::read(input);
 
     // Check if the input starts with the underscore `_` character
     let first_byte = bytes.first()?;
     if first_byte != FIRST_BYTE {
-        return Err(Box::new(ParserError::InvalidFirstByte(
-            first_byte, FIRST_BYTE,
-        )));
+        return Err(ParserError::InvalidFirstByte(first_byte, FIRST_BYTE).into());
     }
 
     // Find the position of the first CRLF sequence
@@ -72,6 +71,13 @@ impl std::fmt::Display for NullParserError {
 // Implement the `Error` trait for the `NullParserError` type
 impl std::error::Error for NullParserError {}
 
+// Convert the `NullParserError` into the crate-level `Error`
+impl From<NullParserError> for Error {
+    fn from(err: NullParserError) -> Self {
+        Error::Protocol(err.to_string())
+    }
+}
+
 // -----
 // TESTS
 // -----
@@ -81,7 +87,7 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
@@ -0,0 +1,187 @@
+// Library
+use super::Type;
+use crate::errors::Error;
+use crate::parser::{_parse, errors::ParserError, reader};
+
+/// The first byte of a push value.
+const FIRST_BYTE: u8 = b'>';
+
+// ----------
+// PARSE PUSH
+// ----------
+
+/// Parses a RESP push from the given input data.
+///
+/// Pushes use the following encoding format:
+/// - A prefix of `>` followed by the number of elements in the push.
+/// - Each element in the push is encoded according to the rules of the RESP protocol.
+/// - CRLF terminator sequence at the end of the push.
+///
+/// Example:
+/// ```sh
+/// >2\r\n+message\r\n+hello\r\n => ["message", "hello"]
+/// ```
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
+    // Check if the input is long enough to contain the push value
+    if input.len() < 4 {
+        return Err(PushParserError::InsufficientData(input.len()).into());
+    }
+
+    // Create a reader to help extract information from the input byte slice
+    let mut bytes = reader::read(input);
+
+    // Check if the input starts with the greater-than `>` character
+    let first_byte = bytes.first()?;
+    if first_byte != FIRST_BYTE {
+        return Err(ParserError::InvalidFirstByte(first_byte, FIRST_BYTE).into());
+    }
+
+    // Find the position of the first CRLF sequence and the start of the push data
+    let (len_end_pos, data_start_pos) = bytes.find_crlf()?;
+
+    // Extract the "length" of the push
+    let length = bytes.slice(1, len_end_pos).parse::<i64>()?;
+
+    // Unlike arrays, pushes cannot be null
+    if length < 0 {
+        return Err(PushParserError::InvalidLength(length).into());
+    }
+
+    // Parse the elements of the push
+    let mut elements = Vec::new();
+    let mut remaining = &input[data_start_pos..];
+    // Iterate for the length of the push
+    for _ in 0..length {
+        let (element, rest) = _parse(remaining)?;
+        elements.push(element);
+        remaining = rest;
+    }
+
+    // Return the parsed push and the remaining input
+    Ok((
+        Type::Push(elements),
+        remaining, // Remaining bytes
+    ))
+}
+
+// ------
+// ERRORS
+// ------
+
+/// Errors that can occur during push parsing.
+/// These errors are returned as boxed trait objects.
+/// This allows the caller to handle errors without knowing the exact type.
+#[derive(Debug)]
+pub enum PushParserError {
+    InsufficientData(usize),
+    InvalidLength(i64),
+}
+
+// Implement the `Display` trait for the push parser error
+impl std::fmt::Display for PushParserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PushParserError::InsufficientData(len) => write!(
+                f,
+                "Insufficient data. The input length is {} but it should contain at least 4 bytes to represent push values",
+                len
+            ),
+            PushParserError::InvalidLength(len) => {
+                write!(f, "Invalid push length {}. Pushes cannot be null", len)
+            }
+        }
+    }
+}
+
+// Implement the `Error` trait for the push parser error
+impl std::error::Error for PushParserError {}
+
+// Convert the `PushParserError` into the crate-level `Error`
+impl From<PushParserError> for Error {
+    fn from(err: PushParserError) -> Self {
+        match err {
+            PushParserError::InsufficientData(_) => Error::Incomplete,
+            err => Error::Protocol(err.to_string()),
+        }
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper function to display errors in the test output
+    fn show(err: Error) {
+        panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
+    }
+
+    #[test]
+    fn should_parse_push() {
+        let input = b">2\r\n+message\r\n$5\r\nhello\r\n";
+        let expected = vec![
+            Type::SimpleString("message".to_string()),
+            Type::BulkString("hello".into()),
+        ];
+        match parse(input) {
+            Ok((actual, _)) => assert_eq!(actual, Type::Push(expected)),
+            Err(err) => show(err),
+        }
+    }
+
+    #[test]
+    fn should_parse_empty_push() {
+        let input = b">0\r\n";
+        match parse(input) {
+            Ok((actual, _)) => assert_eq!(actual, Type::Push(vec![])),
+            Err(err) => show(err),
+        }
+    }
+
+    #[test]
+    fn should_not_parse_null_push() {
+        let input = b">-1\r\n";
+        assert!(parse(input).is_err());
+    }
+
+    #[test]
+    fn should_not_parse_missing_data() {
+        let input = b">3\r\n:1\r\n";
+        assert!(parse(input).is_err());
+    }
+
+    #[test]
+    fn should_support_nesting() {
+        let input = b">3\r\n:1\r\n>1\r\n+inner\r\n*2\r\n:2\r\n:3\r\n";
+        let expected = vec![
+            Type::Integer(1),
+            Type::Push(vec![Type::SimpleString("inner".to_string())]),
+            Type::Array(vec![Type::Integer(2), Type::Integer(3)]),
+        ];
+        match parse(input) {
+            Ok((actual, _)) => assert_eq!(actual, Type::Push(expected)),
+            Err(err) => show(err),
+        }
+    }
+
+    #[test]
+    fn should_round_trip_as_bytes() {
+        let push = Type::Push(vec![
+            Type::BulkString("message".into()),
+            Type::BulkString("channel".into()),
+            Type::Push(vec![Type::Integer(-7)]),
+        ]);
+        let bytes = push.as_bytes();
+        assert_eq!(bytes, push.to_string().as_bytes());
+        match parse(&bytes) {
+            Ok((actual, rest)) => {
+                assert_eq!(actual, push);
+                assert!(rest.is_empty());
+            }
+            Err(err) => show(err),
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashSet;
 
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{_parse, errors::ParserError, reader};
 
 /// The first byte of a set value.
@@ -22,16 +23,14 @@ const FIRST_BYTE: u8 = b'~';
 /// ```sh
 /// ~3\r\n:1\r\n:2\r\n:3\r\n => {1, 2, 3}
 /// ```
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Create a reader to help extract information from the input byte slice
     let mut bytes = reader::read(input);
 
     // Check if the input starts with the tilde `~` character
     let first_byte = bytes.first()?;
     if first_byte != FIRST_BYTE {
-        return Err(Box::new(ParserError::InvalidFirstByte(
-            first_byte, FIRST_BYTE,
-        )));
+        return Err(ParserError::InvalidFirstByte(first_byte, FIRST_BYTE).into());
     }
 
     // Find the position of the first CRLF sequence and the start of the set data
@@ -40,8 +39,13 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
     // Extract the "length" of the set
     let length = bytes.slice(1, len_end_pos).parse::<i64>()?;
 
+    // Unlike arrays, sets cannot be null
+    if length < 0 {
+        return Err(ParserError::InvalidLength(length).into());
+    }
+
     // If the length is 0, the set is empty
-    if length <= 0 {
+    if length == 0 {
         return Ok((
             Type::Set(HashSet::new()),
             &input[data_start_pos..], // Remaining bytes
@@ -57,8 +61,11 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
         remaining = rest;
     }
 
-    // Return the parsed set
-    Ok((Type::Set(elements), &input[data_start_pos..]))
+    // Return the parsed set and the remaining input
+    Ok((
+        Type::Set(elements),
+        remaining, // Remaining bytes
+    ))
 }
 
 // -----
@@ -70,7 +77,7 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
@@ -108,6 +115,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_return_remaining_input() {
+        let input = b"~2\r\n:1\r\n:2\r\n+OK\r\n";
+        let expected = Type::Set(HashSet::from([Type::Integer(1), Type::Integer(2)]));
+        match parse(input) {
+            Ok((actual, remaining)) => {
+                assert_eq!(actual, expected);
+                assert_eq!(remaining, b"+OK\r\n");
+            }
+            Err(err) => show(err),
+        }
+    }
+
     #[test]
     fn should_not_parse_invalid_first_byte() {
         let input = b"*3\r\n:1\r\n:2\r\n:3\r\n";
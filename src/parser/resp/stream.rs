@@ -3,7 +3,37 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// An entry of a stream: its ID and its field-value pairs
+pub type StreamEntry = (StreamID, HashMap<String, String>);
+
+/// A stream: its entries, ordered by ID, and the ID of the last entry ever added to it.
+/// The last ID is kept apart from the entries, so that new IDs cannot go backwards
+/// when the newest entries are deleted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stream {
+    pub entries: Vec<StreamEntry>,
+    pub last_id: StreamID,
+}
+
+impl Stream {
+    /// Creates a stream from its entries, with the last ID being the ID of the last entry (or `0-0`)
+    pub fn new(entries: Vec<StreamEntry>) -> Stream {
+        let last_id = match entries.last() {
+            Some((id, _)) => id.clone(),
+            None => StreamID::from_parts(0, 0),
+        };
+        Stream { entries, last_id }
+    }
+
+    /// Appends the entry, which must have a greater ID than the last ID
+    pub fn push(&mut self, entry: StreamEntry) {
+        self.last_id = entry.0.clone();
+        self.entries.push(entry);
+    }
+}
+
+/// Stream IDs are ordered by their milliseconds, and then by their sequence number
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct StreamID {
     pub milliseconds: u64,
     pub sequence: u64,
@@ -32,58 +62,31 @@ impl StreamID {
         }
     }
 
-    pub fn from_id(id: &str) -> StreamID {
-        match id.split_once("-") {
-            Some((milliseconds, sequence)) => {
-                let milliseconds = milliseconds.parse::<u64>().unwrap_or(0);
-                let sequence = sequence.parse::<u64>().unwrap_or(0);
-                StreamID {
-                    milliseconds,
-                    sequence,
-                }
-            }
-            None => StreamID {
-                milliseconds: 0,
-                sequence: 0,
-            },
-        }
-    }
-
-    pub fn parse(id: &str, last_entry: Option<(StreamID, HashMap<String, String>)>) -> StreamID {
-        let timestamp = get_unix_timestamp();
+    /// Parses the ID of a new entry, as given to XADD, based on the last ID of the stream.
+    /// The ID is either explicit (`5-3`), has an auto-generated sequence number (`5-*`),
+    /// or is fully auto-generated from the current time (`*`).
+    /// Returns `None` if the ID is not valid.
+    pub fn parse(id: &str, last: Option<StreamID>) -> Option<StreamID> {
         match id {
             "*" => {
-                if let Some((last, _)) = last_entry {
-                    if timestamp == last.milliseconds {
-                        StreamID {
-                            milliseconds: timestamp,
-                            sequence: last.sequence + 1,
-                        }
-                    } else {
-                        StreamID::default()
-                    }
-                } else {
-                    StreamID::default()
-                }
+                // Use the current time, unless the last entry is ahead of the clock
+                let milliseconds = match &last {
+                    Some(last) => get_unix_timestamp().max(last.milliseconds),
+                    None => get_unix_timestamp(),
+                };
+                let sequence = parse_sequence("*", milliseconds, last.as_ref())?;
+                Some(StreamID::from_parts(milliseconds, sequence))
+            }
+            _ => {
+                let (milliseconds, sequence) = id.split_once("-")?;
+                let milliseconds = milliseconds.parse::<u64>().ok()?;
+                let sequence = parse_sequence(sequence, milliseconds, last.as_ref())?;
+                Some(StreamID::from_parts(milliseconds, sequence))
             }
-            _ => match id.split_once("-") {
-                Some((milliseconds, sequence)) => {
-                    let milliseconds = parse_milliseconds(milliseconds);
-                    let sequence = parse_sequence(sequence, milliseconds, last_entry);
-                    StreamID {
-                        milliseconds,
-                        sequence,
-                    }
-                }
-                None => StreamID::default(),
-            },
         }
     }
 
-    pub fn to_string(&self) -> String {
-        format!("{}-{}", self.milliseconds, self.sequence)
-    }
-
+    #[allow(dead_code)]
     pub fn next(&self) -> StreamID {
         StreamID {
             milliseconds: self.milliseconds,
@@ -96,31 +99,17 @@ impl StreamID {
     }
 }
 
-fn parse_milliseconds(milliseconds: &str) -> u64 {
-    match milliseconds {
-        "*" => get_unix_timestamp(),
-        _ => milliseconds.parse::<u64>().unwrap_or(0),
-    }
-}
-
-fn parse_sequence(
-    sequence: &str,
-    milliseconds: u64,
-    last_entry: Option<(StreamID, HashMap<String, String>)>,
-) -> u64 {
+/// Parses the sequence number of a new entry.
+/// An auto-generated (`*`) sequence number follows the last entry if it has the same milliseconds,
+/// and otherwise starts at 0 (or 1 for the milliseconds 0, as `0-0` is not a valid ID).
+fn parse_sequence(sequence: &str, milliseconds: u64, last: Option<&StreamID>) -> Option<u64> {
     match sequence {
-        "*" => {
-            if let Some((last, _)) = last_entry {
-                if milliseconds == last.milliseconds {
-                    last.sequence + 1
-                } else {
-                    0
-                }
-            } else {
-                1
-            }
-        }
-        _ => sequence.parse::<u64>().unwrap_or(0),
+        "*" => match last {
+            Some(last) if last.milliseconds == milliseconds => last.sequence.checked_add(1),
+            _ if milliseconds == 0 => Some(1),
+            _ => Some(0),
+        },
+        _ => sequence.parse::<u64>().ok(),
     }
 }
 
@@ -2,7 +2,7 @@
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
-use super::stream::StreamID;
+use super::stream::Stream;
 
 // ---------------------------------------
 // REDIS SERIALIZATION PROTOCOL DATA TYPES
@@ -95,12 +95,15 @@ pub enum Type {
     ///
     /// While RESP3 has a new data type for _Null_, RESP2 uses _Bulk Strings_ to represent null values.
     /// A _Bulk String_ with a length of `-1` represents a null value.
+    /// Null values are represented by `Type::Null`, so an empty `BulkString` is always `$0\r\n\r\n`.
     ///
     /// Example:
     /// ```sh
     /// $-1\r\n
     /// ```
-    BulkString(String),
+    ///
+    /// Bulk strings are binary-safe, so the data is stored as raw bytes rather than as UTF-8 text.
+    BulkString(Vec<u8>),
 
     /// An **Array** is a sequence of RESP values. The first byte of the _Array_ is the asterisk `*` character,
     /// followed by the _number of elements_ in the array, and the CRLF sequence.
@@ -127,6 +130,10 @@ pub enum Type {
     /// ```sh
     /// _\r\n
     /// ```
+    ///
+    /// The RESP2 null bulk string (`$-1\r\n`) and null array (`*-1\r\n`) are also parsed as `Null`.
+    /// `Null` is always written as a null bulk string, as it is understood by both RESP2 and RESP3 clients,
+    /// so RESP3 clients never receive the `_` form.
     Null,
 
     /// A _Boolean_ value is a simple data type that represents a boolean value.
@@ -167,16 +174,17 @@ pub enum Type {
     /// A *Big Number* is a simple data type that represents a big number.
     /// A big number is represented by the left parenthesis `(` character followed by the big number itself.
     /// The big number is terminated by the CRLF sequence.
-    /// A big number is a signed, base-10, 64-bit integer.
+    /// A big number is a signed, base-10 integer of arbitrary size.
     /// The big number can be positive or negative.
     /// The big number is used to represent large integers that can't be represented by the integer data type.
+    /// The digits (and the optional sign) are stored as they were received.
     ///
     /// Example:
     /// ```sh
     /// (1234567890\r\n // 1234567890
     /// (-1234567890\r\n // -1234567890
     /// ```
-    BigNumber(i64),
+    BigNumber(String),
 
     /// A *Bulk Error* is a data type that represents an error message.
     /// A bulk error is encoded as follows:
@@ -206,7 +214,7 @@ pub enum Type {
     ///
     /// Example:
     /// ```sh
-    /// =6\r\nutf-8:foobar\r\n => "foobar"
+    /// =10\r\ntxt:foobar\r\n => "foobar"
     /// ```
     VerbatimString(String, String),
 
@@ -239,7 +247,22 @@ pub enum Type {
     ///
     /// Sets are similar to arrays but with the distinction that sets contain unique elements.
     Set(HashSet<Type>),
-    // TODO: Pushes
+
+    /// A *Push* is a data type for out-of-band data sent by the server, such as pub/sub messages.
+    /// Pushes are encoded like arrays, but with a different prefix:
+    /// - A prefix of `>`
+    /// - The number of elements in the push
+    /// - CRLF terminator sequence
+    /// - Each element in the push is encoded according to the rules of the RESP protocol
+    ///
+    /// Example:
+    /// ```sh
+    /// >2\r\n+message\r\n+hello\r\n => ["message", "hello"]
+    /// ```
+    ///
+    /// Clients should treat pushes separately from the replies to their commands.
+    Push(Vec<Type>),
+
     /// RDB file format
     /// RDB files are the binary representation of the Redis database.
     /// The RDB file format is used for persistence and backups.
@@ -248,35 +271,28 @@ pub enum Type {
     /// Stream data type
     /// A Stream has many entries.
     /// Each stream entry has an ID and a list of key-value pairs.
-    Stream(Vec<(StreamID, HashMap<String, String>)>),
-}
+    Stream(Stream),
 
-impl Eq for Type {
-    fn assert_receiver_is_total_eq(&self) {
-        // Implement this method to assert that the receiver is `Eq`
-        // This method is called when you use the `assert_eq!` macro
-        // to compare two `Type` values
-        // You can use this method to perform additional checks
-        // before comparing the two values
-        // Modify the code below based on the structure of Type
-        match self {
-            Type::Map(map) => {
-                // Check if the map is empty
-                if map.is_empty() {
-                    panic!("Map is empty");
-                }
-            }
-            Type::Set(set) => {
-                // Check if the set is empty
-                if set.is_empty() {
-                    panic!("Set is empty");
-                }
-            }
-            _ => {}
-        }
-    }
+    /// List data type
+    /// A List is a sequence of strings, ordered by insertion.
+    /// Elements can be pushed to and popped from both ends of the list.
+    List(Vec<String>),
+
+    /// Hash data type
+    /// A Hash is a collection of field-value pairs, where both fields and values are strings.
+    Hash(HashMap<String, String>),
+
+    /// Sorted Set data type
+    /// A Sorted Set is a collection of unique members, each associated with a floating point score.
+    /// The members are kept ordered by score, with members of equal score ordered lexically.
+    SortedSet(Vec<(String, f64)>),
 }
 
+// `Type` is used as a key in maps and sets, so it is marked `Eq`.
+// Equality is not reflexive for doubles that are NaN (or values containing them),
+// so such values should not be used as keys.
+impl Eq for Type {}
+
 impl Hash for Type {
     fn hash<H: Hasher>(&self, state: &mut H) {
         // Implement the hash function for Type
@@ -287,17 +303,54 @@ impl Hash for Type {
         // Modify the code below based on the structure of Type
         match self {
             Type::Map(map) => {
-                // Hash the map by hashing each key-value pair
-                for (key, value) in map {
-                    key.hash(state);
-                    value.hash(state);
-                }
+                // Combine the hashes of the entries so that the iteration order does not matter
+                std::mem::discriminant(self).hash(state);
+                map.len().hash(state);
+                unordered_hash(map.iter()).hash(state);
             }
             Type::Set(set) => {
-                // Hash the set by hashing each element
-                for element in set {
-                    element.hash(state);
-                }
+                std::mem::discriminant(self).hash(state);
+                set.len().hash(state);
+                unordered_hash(set.iter()).hash(state);
+            }
+            Type::Hash(hash) => {
+                // Hash the fields in sorted order, so that equal hashes produce the same hash
+                std::mem::discriminant(self).hash(state);
+                let mut pairs: Vec<_> = hash.iter().collect();
+                pairs.sort();
+                pairs.hash(state);
+            }
+            Type::SimpleString(string) => {
+                // Hash the contents, so that keys are spread across the buckets (and database shards)
+                std::mem::discriminant(self).hash(state);
+                string.hash(state);
+            }
+            Type::BulkString(bytes) => {
+                std::mem::discriminant(self).hash(state);
+                bytes.hash(state);
+            }
+            Type::Integer(integer) => {
+                std::mem::discriminant(self).hash(state);
+                integer.hash(state);
+            }
+            Type::SimpleError(string) | Type::BigNumber(string) | Type::BulkError(string) => {
+                std::mem::discriminant(self).hash(state);
+                string.hash(state);
+            }
+            Type::Boolean(boolean) => {
+                std::mem::discriminant(self).hash(state);
+                boolean.hash(state);
+            }
+            Type::Double(double) => {
+                // Hash the bits, treating -0.0 as 0.0 since the two compare equal
+                std::mem::discriminant(self).hash(state);
+                let double = if *double == 0.0 { 0.0 } else { *double };
+                double.to_bits().hash(state);
+            }
+            Type::VerbatimString(encoding, string) => {
+                std::mem::discriminant(self).hash(state);
+                encoding.hash(state);
+                string.hash(state);
             }
             _ => {
                 // For other types, hash the discriminant value
@@ -307,6 +360,17 @@ impl Hash for Type {
     }
 }
 
+/// Hashes each item on its own and sums the hashes, so that the result does not depend on the order of the items
+fn unordered_hash<T: Hash>(items: impl Iterator<Item = T>) -> u64 {
+    items
+        .map(|item| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            item.hash(&mut hasher);
+            hasher.finish()
+        })
+        .fold(0, u64::wrapping_add)
+}
+
 impl std::fmt::Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -317,11 +381,7 @@ impl std::fmt::Display for Type {
             Type::Integer(i) => write!(f, ":{}\r\n", i),
 
             Type::BulkString(s) => {
-                if s == "" {
-                    write!(f, "$-1\r\n")
-                } else {
-                    write!(f, "${}\r\n{}\r\n", s.len(), s)
-                }
+                write!(f, "${}\r\n{}\r\n", s.len(), String::from_utf8_lossy(s))
             }
 
             Type::Array(arr) => {
@@ -336,17 +396,25 @@ impl std::fmt::Display for Type {
 
             Type::Boolean(b) => write!(f, "#{}\r\n", if *b { 't' } else { 'f' }),
 
-            Type::Double(d) => write!(f, ",{}\r\n", d),
+            Type::Double(d) => write!(f, ",{}\r\n", format_double(*d)),
 
             Type::BigNumber(n) => write!(f, "({}\r\n", n),
 
-            Type::BulkError(e) => write!(f, "!{}\r\n", e),
+            Type::BulkError(e) => write!(f, "!{}\r\n{}\r\n", e.len(), e),
 
-            Type::VerbatimString(e, s) => write!(f, "={}\r\n{}:{}\r\n", s.len(), s, e),
+            Type::VerbatimString(e, s) => {
+                write!(f, "={}\r\n{}:{}\r\n", e.len() + 1 + s.len(), e, s)
+            }
 
             Type::Map(map) => {
                 write!(f, "%{}\r\n", map.len())?;
-                for (key, value) in map {
+                // Sort the entries by key so that the output is stable
+                let mut entries: Vec<(String, String)> = map
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                entries.sort();
+                for (key, value) in entries {
                     write!(f, "{}{}", key, value)?;
                 }
                 Ok(())
@@ -354,19 +422,31 @@ impl std::fmt::Display for Type {
 
             Type::Set(set) => {
                 write!(f, "~{}\r\n", set.len())?;
-                for elem in set {
+                // Sort the elements so that the output is stable
+                let mut elements: Vec<String> = set.iter().map(|elem| elem.to_string()).collect();
+                elements.sort();
+                for elem in elements {
                     write!(f, "{}", elem)?;
                 }
                 Ok(())
             }
 
+            Type::Push(elements) => {
+                write!(f, ">{}\r\n", elements.len())?;
+                for elem in elements {
+                    write!(f, "{}", elem)?;
+                }
+                Ok(())
+            }
+
+            // Like a bulk string, but without the trailing CRLF.
+            // The binary data is written lossily, so use `as_bytes` to send the file.
             Type::RDBFile(data) => {
-                let len = data.len();
-                write!(f, "$({}\r\n{:?}", len, data)
+                write!(f, "${}\r\n{}", data.len(), String::from_utf8_lossy(data))
             }
 
-            Type::Stream(entries) => {
-                for (id, fields) in entries {
+            Type::Stream(stream) => {
+                for (id, fields) in &stream.entries {
                     write!(f, "{}:\r\n", id)?;
                     for (key, value) in fields {
                         write!(f, "\t{}: {}", key, value)?;
@@ -374,11 +454,50 @@ impl std::fmt::Display for Type {
                 }
                 Ok(())
             }
+
+            Type::List(elements) => {
+                write!(f, "*{}\r\n", elements.len())?;
+                for elem in elements {
+                    write!(f, "${}\r\n{}\r\n", elem.len(), elem)?;
+                }
+                Ok(())
+            }
+
+            Type::Hash(hash) => {
+                write!(f, "%{}\r\n", hash.len())?;
+                // Sort the fields so that the output is stable
+                let mut pairs: Vec<_> = hash.iter().collect();
+                pairs.sort();
+                for (field, value) in pairs {
+                    write!(f, "${}\r\n{}\r\n", field.len(), field)?;
+                    write!(f, "${}\r\n{}\r\n", value.len(), value)?;
+                }
+                Ok(())
+            }
+
+            Type::SortedSet(members) => {
+                write!(f, "*{}\r\n", members.len() * 2)?;
+                for (member, score) in members {
+                    let score = format_double(*score);
+                    write!(f, "${}\r\n{}\r\n", member.len(), member)?;
+                    write!(f, "${}\r\n{}\r\n", score.len(), score)?;
+                }
+                Ok(())
+            }
         }
     }
 }
 
 impl Type {
+    /// Returns the contents of a bulk string as text.
+    /// Returns `None` if the value is not a bulk string, or if the bulk string is not valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Type::BulkString(data) => std::str::from_utf8(data).ok(),
+            _ => None,
+        }
+    }
+
     pub fn as_bytes(&self) -> Vec<u8> {
         match &self {
             Type::SimpleString(data) => vec![b'+']
@@ -398,14 +517,10 @@ impl Type {
                 .collect(),
             Type::BulkString(data) => {
                 let mut bytes = vec![b'$'];
-                if data.is_empty() {
-                    bytes.extend(vec![b'-', b'1', b'\r', b'\n']);
-                } else {
-                    bytes.extend(data.len().to_string().as_bytes().to_vec());
-                    bytes.extend(vec![b'\r', b'\n']);
-                    bytes.extend(data.as_bytes().to_vec());
-                    bytes.extend(vec![b'\r', b'\n']);
-                }
+                bytes.extend(data.len().to_string().as_bytes().to_vec());
+                bytes.extend(vec![b'\r', b'\n']);
+                bytes.extend(data);
+                bytes.extend(vec![b'\r', b'\n']);
                 bytes
             }
             Type::Array(data) => {
@@ -419,7 +534,8 @@ impl Type {
                 }
                 bytes
             }
-            Type::Null => vec![b'_', b'\r', b'\n'],
+            // Encoded as a null bulk string, which is understood by both RESP2 and RESP3 clients
+            Type::Null => vec![b'$', b'-', b'1', b'\r', b'\n'],
 
             Type::Boolean(data) => vec![b'#']
                 .into_iter()
@@ -429,13 +545,13 @@ impl Type {
 
             Type::Double(data) => vec![b',']
                 .into_iter()
-                .chain(data.to_string().as_bytes().to_vec())
+                .chain(format_double(*data).as_bytes().to_vec())
                 .chain(vec![b'\r', b'\n'])
                 .collect(),
 
             Type::BigNumber(data) => vec![b'(']
                 .into_iter()
-                .chain(data.to_string().as_bytes().to_vec())
+                .chain(data.as_bytes().to_vec())
                 .chain(vec![b'\r', b'\n'])
                 .collect(),
 
@@ -443,12 +559,19 @@ impl Type {
                 .into_iter()
                 .chain(data.len().to_string().as_bytes().to_vec())
                 .chain(vec![b'\r', b'\n'])
+                .chain(data.as_bytes().to_vec())
+                .chain(vec![b'\r', b'\n'])
                 .collect(),
 
             Type::VerbatimString(encoding, data) => {
                 let mut bytes = vec![b'=']
                     .into_iter()
-                    .chain(data.len().to_string().as_bytes().to_vec())
+                    .chain(
+                        (encoding.len() + 1 + data.len())
+                            .to_string()
+                            .as_bytes()
+                            .to_vec(),
+                    )
                     .chain(vec![b'\r', b'\n'])
                     .collect::<Vec<u8>>();
                 bytes.extend(encoding.as_bytes());
@@ -464,9 +587,15 @@ impl Type {
                     .chain(data.len().to_string().as_bytes().to_vec())
                     .chain(vec![b'\r', b'\n'])
                     .collect::<Vec<u8>>();
-                for (key, value) in data {
-                    bytes.extend(key.as_bytes());
-                    bytes.extend(value.as_bytes());
+                // Sort the entries by key so that the output is stable
+                let mut entries: Vec<(Vec<u8>, Vec<u8>)> = data
+                    .iter()
+                    .map(|(key, value)| (key.as_bytes(), value.as_bytes()))
+                    .collect();
+                entries.sort();
+                for (key, value) in entries {
+                    bytes.extend(key);
+                    bytes.extend(value);
                 }
                 bytes
             }
@@ -477,6 +606,21 @@ impl Type {
                     .chain(data.len().to_string().as_bytes().to_vec())
                     .chain(vec![b'\r', b'\n'])
                     .collect::<Vec<u8>>();
+                // Sort the elements so that the output is stable
+                let mut items: Vec<Vec<u8>> = data.iter().map(Type::as_bytes).collect();
+                items.sort();
+                for item in items {
+                    bytes.extend(item);
+                }
+                bytes
+            }
+
+            Type::Push(data) => {
+                let mut bytes = vec![b'>']
+                    .into_iter()
+                    .chain(data.len().to_string().as_bytes().to_vec())
+                    .chain(vec![b'\r', b'\n'])
+                    .collect::<Vec<u8>>();
                 for item in data {
                     bytes.extend(item.as_bytes());
                 }
@@ -492,9 +636,9 @@ impl Type {
                 bytes.extend(data);
                 bytes
             }
-            Type::Stream(entries) => {
+            Type::Stream(stream) => {
                 let mut bytes = Vec::new();
-                for (id, fields) in entries {
+                for (id, fields) in &stream.entries {
                     bytes.extend(id.as_bytes());
                     bytes.extend(b":\r\n");
                     for (key, value) in fields {
@@ -505,6 +649,218 @@ impl Type {
                 }
                 bytes
             }
+
+            Type::List(elements) => {
+                let mut bytes = vec![b'*']
+                    .into_iter()
+                    .chain(elements.len().to_string().as_bytes().to_vec())
+                    .chain(vec![b'\r', b'\n'])
+                    .collect::<Vec<u8>>();
+                for elem in elements {
+                    bytes.extend(format!("${}\r\n", elem.len()).as_bytes());
+                    bytes.extend(elem.as_bytes());
+                    bytes.extend(b"\r\n");
+                }
+                bytes
+            }
+
+            Type::Hash(hash) => {
+                let mut bytes = vec![b'%']
+                    .into_iter()
+                    .chain(hash.len().to_string().as_bytes().to_vec())
+                    .chain(vec![b'\r', b'\n'])
+                    .collect::<Vec<u8>>();
+                // Sort the fields so that the output is stable
+                let mut pairs: Vec<_> = hash.iter().collect();
+                pairs.sort();
+                for (field, value) in pairs {
+                    for s in [field, value] {
+                        bytes.extend(format!("${}\r\n", s.len()).as_bytes());
+                        bytes.extend(s.as_bytes());
+                        bytes.extend(b"\r\n");
+                    }
+                }
+                bytes
+            }
+
+            Type::SortedSet(members) => {
+                let mut bytes = vec![b'*']
+                    .into_iter()
+                    .chain((members.len() * 2).to_string().as_bytes().to_vec())
+                    .chain(vec![b'\r', b'\n'])
+                    .collect::<Vec<u8>>();
+                for (member, score) in members {
+                    for s in [member.clone(), format_double(*score)] {
+                        bytes.extend(format!("${}\r\n", s.len()).as_bytes());
+                        bytes.extend(s.as_bytes());
+                        bytes.extend(b"\r\n");
+                    }
+                }
+                bytes
+            }
+        }
+    }
+}
+
+/// Formats a double as expected by RESP3.
+/// Infinity and NaN are written as `inf`, `-inf` and `nan`, instead of Rust's `inf`, `-inf` and `NaN`.
+pub fn format_double(value: f64) -> String {
+    match value {
+        v if v.is_nan() => "nan".into(),
+        f64::INFINITY => "inf".into(),
+        f64::NEG_INFINITY => "-inf".into(),
+        v => v.to_string(),
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash_of(value: &Type) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn should_serialize_hash_as_map() {
+        let hash = Type::Hash(HashMap::from([("field".into(), "value".into())]));
+        let expected = b"%1\r\n$5\r\nfield\r\n$5\r\nvalue\r\n";
+        assert_eq!(hash.as_bytes(), expected);
+        assert_eq!(hash.to_string().as_bytes(), expected);
+    }
+
+    #[test]
+    fn should_distinguish_empty_bulk_string_from_null() {
+        let empty = Type::BulkString("".into());
+        assert_eq!(empty.as_bytes(), b"$0\r\n\r\n");
+        assert_eq!(empty.to_string().as_bytes(), b"$0\r\n\r\n");
+        assert_eq!(Type::Null.as_bytes(), b"$-1\r\n");
+        assert_eq!(Type::Null.to_string().as_bytes(), b"$-1\r\n");
+    }
+
+    #[test]
+    fn should_round_trip_set() {
+        let set = Type::Set(HashSet::from([
+            Type::Integer(3),
+            Type::Integer(1),
+            Type::Integer(2),
+        ]));
+        let bytes = set.as_bytes();
+        assert_eq!(bytes, b"~3\r\n:1\r\n:2\r\n:3\r\n");
+        assert_eq!(set.to_string().as_bytes(), bytes);
+        let (parsed, remaining) = crate::parser::resp::set::parse(&bytes).unwrap();
+        assert_eq!(parsed, set);
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn should_serialize_map_deterministically() {
+        let entries: Vec<(Type, Type)> = (0..32)
+            .map(|i| (Type::Integer(i), Type::BulkString(format!("v{}", i).into())))
+            .collect();
+        let a = Type::Map(entries.iter().cloned().collect());
+        let b = Type::Map(entries.into_iter().rev().collect());
+        assert_eq!(a.as_bytes(), a.as_bytes());
+        assert_eq!(a.as_bytes(), b.as_bytes());
+        assert_eq!(a.to_string(), b.to_string());
+
+        let map = Type::Map(HashMap::from([
+            (Type::SimpleString("b".into()), Type::Integer(2)),
+            (Type::SimpleString("a".into()), Type::Integer(1)),
+        ]));
+        let expected = b"%2\r\n+a\r\n:1\r\n+b\r\n:2\r\n";
+        assert_eq!(map.as_bytes(), expected);
+        assert_eq!(map.to_string().as_bytes(), expected);
+    }
+
+    #[test]
+    fn should_serialize_hash_fields_in_order() {
+        let hash = Type::Hash(HashMap::from([
+            ("b".into(), "2".into()),
+            ("a".into(), "1".into()),
+        ]));
+        let expected = b"%2\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n$1\r\n2\r\n";
+        assert_eq!(hash.as_bytes(), expected);
+        assert_eq!(hash.to_string().as_bytes(), expected);
+    }
+
+    #[test]
+    fn should_display_rdb_file_like_its_bytes() {
+        let file = Type::RDBFile(b"REDIS0011".to_vec());
+        assert_eq!(file.as_bytes(), b"$9\r\nREDIS0011");
+        assert_eq!(file.to_string().as_bytes(), file.as_bytes());
+
+        let file = Type::RDBFile(vec![b'R', 0xff, 0xfe]);
+        assert!(file.to_string().starts_with("$3\r\n"));
+        assert!(file.as_bytes().starts_with(b"$3\r\n"));
+    }
+
+    #[test]
+    fn should_hash_collections_regardless_of_order() {
+        let elements: Vec<Type> = (0..32).map(Type::Integer).collect();
+        let a = Type::Set(elements.iter().cloned().collect());
+        let b = Type::Set(elements.iter().rev().cloned().collect());
+        assert_eq!(hash_of(&a), hash_of(&b));
+
+        let entries: Vec<(Type, Type)> = (0..32)
+            .map(|i| (Type::Integer(i), Type::Integer(-i)))
+            .collect();
+        let a = Type::Map(entries.iter().cloned().collect());
+        let b = Type::Map(entries.into_iter().rev().collect());
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_ne!(hash_of(&a), hash_of(&Type::Set(HashSet::new())));
+    }
+
+    #[test]
+    fn should_compare_empty_collections() {
+        assert_eq!(Type::Map(HashMap::new()), Type::Map(HashMap::new()));
+        assert_eq!(Type::Set(HashSet::new()), Type::Set(HashSet::new()));
+    }
+
+    #[test]
+    fn should_serialize_empty_hash() {
+        let hash = Type::Hash(HashMap::new());
+        assert_eq!(hash.as_bytes(), b"%0\r\n");
+    }
+
+    #[test]
+    fn should_hash_distinct_scalars_distinctly() {
+        let map = HashMap::from([
+            (Type::BulkString("a".into()), 1),
+            (Type::BulkString("b".into()), 2),
+        ]);
+        assert_eq!(map.get(&Type::BulkString("a".into())), Some(&1));
+        assert_eq!(map.get(&Type::BulkString("b".into())), Some(&2));
+
+        let pairs = [
+            (Type::BulkString("a".into()), Type::BulkString("b".into())),
+            (Type::SimpleError("a".into()), Type::SimpleError("b".into())),
+            (Type::Boolean(true), Type::Boolean(false)),
+            (Type::Double(1.5), Type::Double(2.5)),
+            (Type::BigNumber("1".into()), Type::BigNumber("2".into())),
+        ];
+        for (a, b) in pairs {
+            assert_ne!(hash_of(&a), hash_of(&b));
         }
+        assert_eq!(hash_of(&Type::Double(0.0)), hash_of(&Type::Double(-0.0)));
+    }
+
+    #[test]
+    fn should_hash_equal_hashes_equally() {
+        let pairs: Vec<(String, String)> = (0..32)
+            .map(|i| (format!("field{}", i), format!("value{}", i)))
+            .collect();
+        let a = Type::Hash(pairs.iter().cloned().collect());
+        let b = Type::Hash(pairs.into_iter().rev().collect());
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_ne!(hash_of(&a), hash_of(&Type::Hash(HashMap::new())));
     }
 }
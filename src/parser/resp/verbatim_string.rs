@@ -1,5 +1,6 @@
 // Library
 use super::Type;
+use crate::errors::Error;
 use crate::parser::{
     errors::ParserError,
     reader::{self, CRLF},
@@ -16,7 +17,7 @@ const FIRST_BYTE: u8 = b'=';
 ///
 /// A verbatim string is encoded as follows:
 /// - A prefix of `=`
-/// - The length of the verbatim string
+/// - The length of the payload (the encoding, the colon and the data)
 /// - The CRLF terminator sequence
 /// - Exactly 3 bytes representing the data's encoding
 /// - The colon `:` character to separate the encoding from the data
@@ -25,11 +26,11 @@ const FIRST_BYTE: u8 = b'=';
 ///
 /// Example:
 /// ```sh
-/// =6\r\nutf-8:foobar\r\n => "foobar"
+/// =10\r\ntxt:foobar\r\n => "foobar"
 /// ```
 ///
-/// TODO: Add URL to the specification
-pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>> {
+/// See: https://redis.io/docs/latest/develop/reference/protocol-spec/#verbatim-strings
+pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Error> {
     // Create a reader to help extract information from the input byte slice
     let mut bytes = reader::read(input);
 
@@ -42,36 +43,34 @@ pub fn parse(input: &[u8]) -> Result<(Type, &[u8]), Box<dyn std::error::Error>>
     // Find the position of the first CRLF sequence and the start of the verbatim string data
     let (len_end_pos, data_start_pos) = bytes.find_crlf()?;
 
-    // Parse the length of the verbatim string
-    let length = bytes.slice(1, len_end_pos).parse::<i64>()?;
+    // Parse the length of the payload
+    let length = bytes.slice(1, len_end_pos).parse::<usize>()?;
 
     // Calculate the total length of the verbatim string
     // data_start_pos = (length of the prefix + length of the CRLF terminator sequence)
-    // 3 bytes for the encoding
-    // 1 byte for the colon separator
-    // `length` bytes for the verbatim string data
+    // `length` bytes for the encoding, the colon separator and the verbatim string data
     // 2 bytes for the CRLF terminator sequence
-    let total_length = data_start_pos + 3 + 1 + length as usize + CRLF.len();
+    let data_end_pos = data_start_pos + length;
+    let total_length = data_end_pos + CRLF.len();
 
     // Check if there is enough data to parse the verbatim string
-    if input.len() < total_length {
+    if input.len() < total_length || &input[data_end_pos..total_length] != CRLF {
         return Err(VerbatimStringParserError::InvalidLength(total_length, input.len()).into());
     }
 
-    // Extract the verbatim string data
-    let data = bytes.slice(data_start_pos, data_start_pos + length as usize);
-
-    // Extract the encoding and the verbatim string data
-    let (mut encoding_part, mut verbatim_string_part) = data
-        .split(b":")
-        .map_err(|_| VerbatimStringParserError::MissingEncodingSeparator)?;
-
-    // Only take the length for verbatim string data
-    let verbatim_string = verbatim_string_part.slice(0, length as usize);
+    // Split the payload into the 3 byte encoding and the verbatim string data
+    let payload = &input[data_start_pos..data_end_pos];
+    if payload.len() < 4 || payload[3] != b':' {
+        return Err(VerbatimStringParserError::MissingEncodingSeparator.into());
+    }
+    let encoding = bytes
+        .slice(data_start_pos, data_start_pos + 3)
+        .as_string()?;
+    let verbatim_string = bytes.slice(data_start_pos + 4, data_end_pos).as_string()?;
 
     // Return the verbatim string and the remaining input
     Ok((
-        Type::VerbatimString(encoding_part.as_string()?, verbatim_string.as_string()?),
+        Type::VerbatimString(encoding, verbatim_string),
         &input[total_length..], // Remaining bytes
     ))
 }
@@ -108,6 +107,16 @@ impl std::fmt::Display for VerbatimStringParserError {
 // Implement the `Error` trait for `BulkStringParserError`
 impl std::error::Error for VerbatimStringParserError {}
 
+// Convert the `VerbatimStringParserError` into the crate-level `Error`
+impl From<VerbatimStringParserError> for Error {
+    fn from(err: VerbatimStringParserError) -> Self {
+        match err {
+            VerbatimStringParserError::InvalidLength(..) => Error::Incomplete,
+            err => Error::Protocol(err.to_string()),
+        }
+    }
+}
+
 // -----
 // TESTS
 // -----
@@ -117,16 +126,16 @@ mod tests {
     use super::*;
 
     /// Helper function to display errors in the test output
-    fn show(err: Box<dyn std::error::Error>) {
+    fn show(err: Error) {
         panic!("\u{001b}[31mERROR [{:?}]: {}\u{001b}[0m", err, err);
     }
 
     #[test]
     fn test_parse() {
-        let input = b"=6\r\nutf-8:foobar";
+        let input = b"=10\r\ntxt:foobar\r\n";
         match parse(input) {
             Ok((Type::VerbatimString(encoding, verbatim_string), remaining)) => {
-                assert_eq!(encoding, "utf-8");
+                assert_eq!(encoding, "txt");
                 assert_eq!(verbatim_string, "foobar");
                 assert_eq!(remaining, b"");
             }
@@ -137,33 +146,48 @@ mod tests {
 
     #[test]
     fn test_parse_invalid_first_byte() {
-        let input = b"6\r\nutf-8:foobar";
+        let input = b"10\r\ntxt:foobar\r\n";
         assert!(parse(input).is_err())
     }
 
     #[test]
     fn test_parse_invalid_length() {
-        let input = b"=6\r\nutf-8:foo";
+        let input = b"=10\r\ntxt:foo\r\n";
         assert!(parse(input).is_err())
     }
 
     #[test]
     fn test_parse_missing_encoding_separator() {
-        let input = b"=6\r\nutf-8foobar";
+        let input = b"=10\r\ntxtfoobar\r\n";
         assert!(parse(input).is_err())
     }
 
     #[test]
     fn test_parse_remaining() {
-        let input = b"=6\r\nutf-8:foobar\r\nremaining";
+        let input = b"=10\r\ntxt:foobar\r\nremaining";
         match parse(input) {
             Ok((Type::VerbatimString(encoding, verbatim_string), remaining)) => {
-                assert_eq!(encoding, "utf-8");
+                assert_eq!(encoding, "txt");
                 assert_eq!(verbatim_string, "foobar");
-                assert_eq!(remaining, b"\r\nremaining");
+                assert_eq!(remaining, b"remaining");
             }
             Err(err) => show(err),
             _ => panic!("Unexpected Type"),
         }
     }
+
+    #[test]
+    fn test_round_trip() {
+        let value = Type::VerbatimString("txt".into(), "Some string".into());
+        let expected = b"=15\r\ntxt:Some string\r\n";
+        assert_eq!(value.as_bytes(), expected);
+        assert_eq!(value.to_string().as_bytes(), expected);
+        match parse(&value.as_bytes()) {
+            Ok((actual, remaining)) => {
+                assert_eq!(actual, value);
+                assert!(remaining.is_empty());
+            }
+            Err(err) => show(err),
+        }
+    }
 }
@@ -0,0 +1,51 @@
+// Library
+use std::collections::HashMap;
+
+// ---
+// ACL
+// ---
+
+/// The name of the user every connection is authenticated as when it is opened
+pub const DEFAULT_USER: &str = "default";
+
+/// A user of the access control list.
+/// Users have no per-command permissions yet, so an authenticated user may run every command.
+#[derive(Debug, Clone)]
+pub struct User {
+    /// Whether the user may authenticate
+    pub enabled: bool,
+    /// The passwords accepted for the user, or `None` if the user accepts any password (`nopass`)
+    pub passwords: Option<Vec<String>>,
+}
+
+/// Creates the access control list with only the default user, which accepts any password
+pub fn new() -> HashMap<String, User> {
+    let default = User {
+        enabled: true,
+        passwords: None,
+    };
+    HashMap::from([(DEFAULT_USER.to_string(), default)])
+}
+
+impl User {
+    /// Returns true if the user is enabled and accepts the password
+    pub fn authenticate(&self, password: &str) -> bool {
+        self.enabled
+            && match &self.passwords {
+                Some(passwords) => passwords.iter().any(|p| p == password),
+                None => true,
+            }
+    }
+
+    /// Describes the user with the given name, in the format used by ACL LIST.
+    /// Passwords are never revealed, so only the `nopass` flag is described.
+    pub fn describe(&self, name: &str) -> String {
+        let mut rules = vec![format!("user {}", name)];
+        rules.push(if self.enabled { "on" } else { "off" }.into());
+        if self.passwords.is_none() {
+            rules.push("nopass".into());
+        }
+        rules.push("~* &* +@all".into());
+        rules.join(" ")
+    }
+}
@@ -1,23 +1,30 @@
 // Library
 use crate::{
     commands,
-    parser::{self, resp},
-    server::Server,
+    parser::{buffer::Buffer, resp},
+    server::{self, Server},
+};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    time::{Duration, Instant},
 };
-use std::net::SocketAddr;
-use std::sync::Arc;
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     net::TcpStream,
-    sync::{mpsc, Mutex},
+    sync::{broadcast, mpsc, Mutex},
 };
 
 // ----------
 // CONNECTION
 // ----------
 
-/// The size of the buffer to read incoming data
-const BUFFER_SIZE: usize = 1024;
+/// The id to assign to the next connection. Ids are unique and never reused.
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
 
 /// Represents a connection to a client.
 /// Contains the stream, the address of the client, and a buffer to store incoming data.
@@ -39,12 +46,86 @@ pub struct Connection {
     /// The buffer used to store incoming data from the client.
     /// The buffer is used to read data from the stream and process it.
     /// The buffer is cleared after each request is processed.
-    buffer: [u8; BUFFER_SIZE],
+    /// Its size only limits how much is read at a time, as partial commands are kept in `pending`.
+    buffer: Vec<u8>,
+
+    /// The bytes received from the client that have not been parsed yet.
+    /// A command may be split across multiple reads, so incomplete commands are kept here
+    /// until the rest of the command has been received.
+    pending: Buffer,
 
     /// The kind of connection (Main or Replication)
     /// The role is used to determine the type of connection (master or replica).
     /// The role is set when the connection is created.
     pub kind: Kind,
+
+    /// The index of the logical database selected by the client (with the SELECT command).
+    /// Every connection starts on database 0.
+    pub db: usize,
+
+    /// The unique id of the connection, assigned in the order connections are created.
+    pub id: u64,
+
+    /// The name of the connection, if the client set one (with the CLIENT SETNAME command).
+    pub name: Option<String>,
+
+    /// The name of the user the connection is authenticated as (with the AUTH command).
+    /// Every connection starts as the default user.
+    pub user: String,
+
+    /// The RESP protocol version negotiated with the client (with the HELLO command).
+    /// Every connection starts on RESP2.
+    pub protocol: u8,
+
+    /// The port the client listens on, if the client is a replica that advertised it
+    /// (with the REPLCONF listening-port command).
+    pub listening_port: Option<u16>,
+
+    /// The channels the client is subscribed to (with the SUBSCRIBE command).
+    pub subscriptions: HashSet<String>,
+
+    /// The channel patterns the client is subscribed to (with the PSUBSCRIBE command).
+    pub pattern_subscriptions: HashSet<String>,
+
+    /// The sender for the messages published to the subscribed channels.
+    /// A clone of the sender is registered with the server for every subscribed channel.
+    pub messages_sender: mpsc::UnboundedSender<Vec<resp::Type>>,
+
+    /// The messages published to the subscribed channels, waiting to be written to the client.
+    messages: mpsc::UnboundedReceiver<Vec<resp::Type>>,
+
+    /// The stream of write commands to propagate, if the client is a replica (accepted with PSYNC).
+    /// The subscription is made while the replica is registered, so that no write is missed in between.
+    pub replication: Option<broadcast::Receiver<resp::Type>>,
+
+    /// The last replication offset acknowledged by the replica (with REPLCONF ACK).
+    pub ack_offset: u64,
+}
+
+/// Reads from the stream into the buffer, giving up once the timeout (if any) elapses.
+/// Returns `None` if the timeout elapsed before anything was read.
+async fn read_with_timeout(
+    stream: &mut TcpStream,
+    buffer: &mut [u8],
+    timeout: Option<Duration>,
+) -> Option<std::io::Result<usize>> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, stream.read(buffer))
+            .await
+            .ok(),
+        None => Some(stream.read(buffer).await),
+    }
+}
+
+/// The details of a connected client, as reported by CLIENT LIST
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    /// The address of the client
+    pub addr: SocketAddr,
+    /// The name of the client, if it set one (with the CLIENT SETNAME command)
+    pub name: Option<String>,
+    /// When the client connected
+    pub connected_at: Instant,
 }
 
 /// The kind of connection (Main or Replication)
@@ -55,12 +136,27 @@ pub enum Kind {
 }
 
 /// Instantiate a new Connection with the provided TcpStream and SocketAddr.
-pub fn new(stream: TcpStream, addr: SocketAddr, kind: Kind) -> Connection {
+/// The buffer size is the number of bytes read from the stream at a time.
+pub fn new(stream: TcpStream, addr: SocketAddr, kind: Kind, buffer_size: usize) -> Connection {
+    let (messages_sender, messages) = mpsc::unbounded_channel();
     Connection {
         stream,
         addr,
-        buffer: [0; BUFFER_SIZE],
+        buffer: vec![0; buffer_size],
+        pending: Buffer::new(),
         kind,
+        db: 0,
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        name: None,
+        user: server::acl::DEFAULT_USER.into(),
+        protocol: 2,
+        listening_port: None,
+        subscriptions: HashSet::new(),
+        pattern_subscriptions: HashSet::new(),
+        messages_sender,
+        messages,
+        replication: None,
+        ack_offset: 0,
     }
 }
 
@@ -75,6 +171,40 @@ impl Connection {
         Ok(bytes_read)
     }
 
+    /// Reads the `FULLRESYNC` reply of the master to PSYNC, and frames the next value as the RDB file that follows it.
+    /// The reply is read a byte at a time, so that none of the RDB file is consumed along with it.
+    pub async fn read_fullresync(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut line = Vec::new();
+        while !line.ends_with(b"\r\n") {
+            line.push(self.stream.read_u8().await?);
+        }
+        if !line.starts_with(b"+FULLRESYNC") {
+            let line = String::from_utf8_lossy(&line);
+            return Err(format!("Unexpected reply to PSYNC: {}", line.trim_end()).into());
+        }
+        self.pending.expect_rdb();
+        Ok(())
+    }
+
+    /// Reads from the stream, and returns the complete RESP values received so far along with their size in bytes.
+    /// An incomplete value is kept in the pending bytes until the rest of it has been received.
+    /// Returns `None` once the stream has been closed.
+    pub async fn read_frames(
+        &mut self,
+    ) -> Result<Option<Vec<(resp::Type, usize)>>, Box<dyn std::error::Error>> {
+        let bytes_read = self.stream.read(&mut self.buffer).await?;
+        if bytes_read == 0 {
+            return Ok(None);
+        }
+        self.pending.extend(&self.buffer[..bytes_read]);
+
+        let mut frames = Vec::new();
+        while let Some(frame) = self.pending.next_frame()? {
+            frames.push(frame);
+        }
+        Ok(Some(frames))
+    }
+
     /// Writes data to the stream.
     /// The write_data method is called when the server needs to send a response to the client.
     /// The server will write the response to the stream, which will be sent to the client.
@@ -84,17 +214,6 @@ impl Connection {
         Ok(())
     }
 
-    /// Returns a slice of the buffer containing the read data.
-    /// The read_buffer method should be called after reading data from the stream.
-    ///
-    /// ```rs
-    /// let bytes_read = connection.read().await?;
-    /// let buffer = connection.read_buffer(bytes_read);
-    /// ```
-    pub fn read_buffer(&self, len: usize) -> &[u8] {
-        &self.buffer[..len]
-    }
-
     // /// Parses the buffer and returns the data as a string.
     // pub fn parse_from_buffer(&mut self) -> String {
     //     String::from_utf8_lossy(&self.buffer).to_string()
@@ -114,52 +233,84 @@ impl Connection {
         wait_channel: &Arc<Mutex<(mpsc::Sender<u64>, mpsc::Receiver<u64>)>>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         println!("New connection from {}", self.addr);
+        let (mut shutdown, idle_timeout) = {
+            let server = server.lock().await;
+            (server.shutdown_signal(), server.idle_timeout)
+        };
         loop {
-            // Read the incoming data from the stream
-            let bytes_read = self.read().await?;
+            // Only clients are closed when idle. Subscribed clients are expected to wait for messages,
+            // and the master's replication connection may legitimately be quiet.
+            let idle_timeout = match self.kind {
+                Kind::Main if self.subscription_count() == 0 => idle_timeout,
+                _ => None,
+            };
+
+            // Read the incoming data from the stream,
+            // while forwarding the messages published to the subscribed channels.
+            // The commands are only interrupted between reads, so a shutdown lets the commands in flight finish.
+            let bytes_read = tokio::select! {
+                bytes_read = read_with_timeout(&mut self.stream, &mut self.buffer, idle_timeout) => match bytes_read {
+                    Some(bytes_read) => bytes_read?,
+                    None => {
+                        println!("Closing idle connection from {}", self.addr);
+                        break;
+                    }
+                },
+                Some(message) = self.messages.recv() => {
+                    self.write_push(message).await?;
+                    continue;
+                }
+                _ = server::shutdown_requested(&mut shutdown) => break,
+            };
             println!("Bytes read: {}", bytes_read);
             if bytes_read == 0 {
                 // If no data was read, this typically indicates that the end of the
                 // stream has been reached and the connection should be closed.
+                if !self.pending.is_empty() {
+                    println!("Discarding incomplete command from {}", self.addr);
+                }
                 break;
             }
 
-            // Parse the incoming data
-            let request = self.read_buffer(bytes_read);
-            // println!(
-            //     "Received: {} {:?}",
-            //     String::from_utf8_lossy(request),
-            //     request
-            // );
-
-            let mut err_response: Option<String> = None;
-            let mut cmds: Vec<parser::resp::Type> = Vec::new();
-            match parser::parse(request) {
-                Ok(c) => cmds = c,
-                Err(e) => {
-                    err_response = Some(format!("-ERR {}\r\n", e));
-                }
-            }
-            // println!("Parsed: {:?} of len", cmds);
+            // Add the incoming data to the pending bytes
+            self.pending.extend(&self.buffer[..bytes_read]);
 
-            if let Some(r) = err_response {
-                self.write_all(r.as_bytes()).await?;
-                continue;
+            // Parse all the complete commands received so far.
+            // An incomplete command is kept in the pending bytes until the next read,
+            // while invalid data stops the parsing, as the commands after it cannot be framed.
+            let mut protocol_error = None;
+            let mut cmds: Vec<(resp::Type, usize)> = Vec::new();
+            loop {
+                match self.pending.next_frame() {
+                    Ok(Some(cmd)) => cmds.push(cmd),
+                    Ok(None) => break,
+                    Err(e) => {
+                        protocol_error = Some(e);
+                        break;
+                    }
+                }
             }
 
             // Iterate over the parsed commands
             // There can be multiple commands in a single request
-            for cmd in cmds {
+            for (cmd, len) in cmds {
                 match cmd {
                     resp::Type::Array(command) => {
                         println!("Array: {:?}", command);
                         commands::handle(&command, self, server, wait_channel).await?;
+
+                        // A replica counts every byte of the commands it processed from the master.
+                        // The offset is updated after handling the command, so that a
+                        // REPLCONF GETACK reports the offset without its own bytes.
+                        if self.kind == Kind::Replication {
+                            server.lock().await.repl_offset += len as u64;
+                        }
                     }
-                    resp::Type::RDBFile(_data) => {
-                        // let response =
-                        //     resp::Type::Array(vec![resp::Type::SimpleString("OK".into())]);
-                        // self.write_all(&response.as_bytes()).await?;
-                        continue;
+                    resp::Type::RDBFile(data) => {
+                        // The master's snapshot replaces the replica's dataset on a full resync
+                        let db = server.lock().await.db.clone();
+                        db.clear_all().await;
+                        db.restore(data).await;
                     }
                     _ => {
                         let response = resp::Type::SimpleError("ERR unknown command\r\n".into());
@@ -167,15 +318,53 @@ impl Connection {
                     }
                 }
             }
+
+            // Like Redis, reply with the protocol error and close the connection,
+            // as there is no way to tell where the next command starts
+            if let Some(e) = protocol_error {
+                println!("Protocol error from {}: {}", self.addr, e);
+                self.write_error(format!("ERR Protocol error: {}", e))
+                    .await?;
+                break;
+            }
         }
         println!("Connection closed for {}", self.addr);
 
+        // Stop receiving the messages published to the subscribed channels
+        if self.subscription_count() > 0 {
+            let mut s = server.lock().await;
+            for channel in self.subscriptions.drain() {
+                s.unsubscribe(&channel, self.id);
+            }
+            for pattern in self.pattern_subscriptions.drain() {
+                s.punsubscribe(&pattern, self.id);
+            }
+        }
+
         let response = resp::Type::SimpleString("DONE".into());
         self.write_all(&response.as_bytes()).await?;
         // Once we are out of the loop, the connection will be closed.
         Ok(())
     }
 
+    /// Returns the number of channels and patterns the client is subscribed to.
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions.len() + self.pattern_subscriptions.len()
+    }
+
+    /// Writes an out-of-band message (such as a published message) to the client.
+    /// The message is sent as a push on RESP3, and as an array on RESP2.
+    pub async fn write_push(
+        &mut self,
+        elements: Vec<resp::Type>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let response = match self.protocol {
+            3 => resp::Type::Push(elements),
+            _ => resp::Type::Array(elements),
+        };
+        self.write_all(&response.as_bytes()).await
+    }
+
     /// Write an OK response to the client.
     pub async fn write_ok(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let response = resp::Type::SimpleString("OK".into());
@@ -193,3 +382,174 @@ impl Connection {
         Ok(())
     }
 }
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{self, replication::Role};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn should_handle_commands_larger_than_the_buffer() {
+        let server = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+        let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (accepted, addr) = listener.accept().await.unwrap();
+
+        // Use a buffer much smaller than the command, so it takes many reads
+        let mut conn = new(accepted, addr, Kind::Main, 64);
+        tokio::spawn(async move {
+            conn.handle(&server, &wait_channel)
+                .await
+                .expect("Failed to handle connection");
+        });
+
+        let value = "x".repeat(2048);
+        let request = format!(
+            "*3\r\n$3\r\nSET\r\n$6\r\nbigkey\r\n${}\r\n{}\r\n*2\r\n$3\r\nGET\r\n$6\r\nbigkey\r\n",
+            value.len(),
+            value
+        );
+        client.write_all(request.as_bytes()).await.unwrap();
+
+        let expected = format!("+OK\r\n${}\r\n{}\r\n", value.len(), value);
+        let mut response = Vec::new();
+        let mut chunk = [0; 512];
+        while response.len() < expected.len() {
+            let n = client.read(&mut chunk).await.unwrap();
+            assert!(n > 0, "connection closed before the full response");
+            response.extend_from_slice(&chunk[..n]);
+        }
+        assert_eq!(response, expected.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn should_track_replica_offset_from_processed_commands() {
+        let mut replica = server::new("127.0.0.1", 0);
+        replica.role = Role::Replica("127.0.0.1:6379".into());
+        let server = Arc::new(Mutex::new(replica));
+        let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut master = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (accepted, addr) = listener.accept().await.unwrap();
+
+        // The replica only replies to GETACK on the replication connection
+        let mut conn = new(accepted, addr, Kind::Replication, 64);
+        let s = Arc::clone(&server);
+        tokio::spawn(async move {
+            conn.handle(&s, &wait_channel)
+                .await
+                .expect("Failed to handle connection");
+        });
+
+        let set = "*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n";
+        let ping = "*1\r\n$4\r\nPING\r\n";
+        let getack = "*3\r\n$8\r\nREPLCONF\r\n$6\r\nGETACK\r\n$1\r\n*\r\n";
+
+        // GETACK reports the bytes processed before it, excluding its own
+        let request = format!("{}{}{}", set, ping, getack);
+        master.write_all(request.as_bytes()).await.unwrap();
+        let offset = (set.len() + ping.len()).to_string();
+        let expected = format!(
+            "*3\r\n$8\r\nREPLCONF\r\n$3\r\nACK\r\n${}\r\n{}\r\n",
+            offset.len(),
+            offset
+        );
+        let mut response = vec![0; expected.len()];
+        master.read_exact(&mut response).await.unwrap();
+        assert_eq!(response, expected.as_bytes());
+
+        // ... and is counted towards the offset once it has been processed
+        master.write_all(getack.as_bytes()).await.unwrap();
+        let offset = (set.len() + ping.len() + getack.len()).to_string();
+        let expected = format!(
+            "*3\r\n$8\r\nREPLCONF\r\n$3\r\nACK\r\n${}\r\n{}\r\n",
+            offset.len(),
+            offset
+        );
+        let mut response = vec![0; expected.len()];
+        master.read_exact(&mut response).await.unwrap();
+        assert_eq!(response, expected.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn should_close_idle_connections() {
+        let mut s = server::new("127.0.0.1", 0);
+        s.idle_timeout = Some(Duration::from_millis(200));
+        let server = Arc::new(Mutex::new(s));
+        let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        // Connect an idle client and an active client
+        let mut clients = Vec::new();
+        for _ in 0..2 {
+            let client = TcpStream::connect(listener.local_addr().unwrap())
+                .await
+                .unwrap();
+            let (accepted, addr) = listener.accept().await.unwrap();
+            let mut conn = new(accepted, addr, Kind::Main, 64);
+            let (server, wait_channel) = (Arc::clone(&server), Arc::clone(&wait_channel));
+            tokio::spawn(async move {
+                conn.handle(&server, &wait_channel)
+                    .await
+                    .expect("Failed to handle connection");
+            });
+            clients.push(client);
+        }
+        let (mut idle, mut active) = (clients.remove(0), clients.remove(0));
+
+        // The active client keeps sending commands for longer than the timeout
+        let ping = b"*1\r\n$4\r\nPING\r\n";
+        let mut response = [0; 7];
+        for _ in 0..5 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            active.write_all(ping).await.unwrap();
+            active.read_exact(&mut response).await.unwrap();
+            assert_eq!(&response, b"+PONG\r\n");
+        }
+
+        // ... while the idle client has been disconnected (reading to the end only returns once it is closed)
+        let mut rest = Vec::new();
+        idle.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b"+DONE\r\n");
+    }
+
+    #[tokio::test]
+    async fn should_close_connection_on_protocol_error() {
+        let server = Arc::new(Mutex::new(server::new("127.0.0.1", 0)));
+        let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (accepted, addr) = listener.accept().await.unwrap();
+        let mut conn = new(accepted, addr, Kind::Main, 64);
+        tokio::spawn(async move {
+            conn.handle(&server, &wait_channel)
+                .await
+                .expect("Failed to handle connection");
+        });
+
+        // The command before the malformed data is still handled
+        client
+            .write_all(b"*1\r\n$4\r\nPING\r\n?bad\r\n*1\r\n$4\r\nPING\r\n")
+            .await
+            .unwrap();
+
+        // Reading to the end only returns once the connection is closed
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await.unwrap();
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("+PONG\r\n-ERR Protocol error: invalid first byte '?'\r\n"));
+        assert_eq!(response.matches("+PONG").count(), 1);
+    }
+}
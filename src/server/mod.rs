@@ -1,21 +1,42 @@
 // Library
-use crate::{config::Config, database, helpers, parser::resp::Type};
-use std::{net::SocketAddr, sync::Arc};
+use crate::{
+    config::{self, Config},
+    database, helpers,
+    parser::resp::Type,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 use tokio::{
     net::TcpListener,
-    sync::{broadcast, mpsc, Mutex},
+    sync::{broadcast, mpsc, watch, Mutex},
+    task::JoinSet,
 };
 
 // Modules
+pub mod acl;
 pub mod connection;
-use connection::Kind;
+use connection::{ClientInfo, Connection, Kind};
 pub mod replication;
-use replication::Role;
+use replication::{Replica, Role};
+pub mod stats;
+use stats::Stats;
 
 // ----------
 // TCP SERVER
 // ----------
 
+/// The Redis version reported to clients
+pub const VERSION: &str = "7.2.0";
+
+/// How long to wait for the connections to finish their commands when shutting down
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Struct to hold information about the Server and its configuration
 #[derive(Clone)]
 pub struct Server {
@@ -23,17 +44,33 @@ pub struct Server {
     // host: &'static str,
 
     /// The port to listen on (default is 6379)
-    port: u16,
+    pub port: u16,
 
     /// The full address (host:port) to listen on
     pub addr: String,
 
+    /// The number of bytes read from each connection at a time
+    buffer_size: usize,
+
+    /// The number of milliseconds between the background sweeps that evict expired keys
+    expiry_interval: u64,
+
+    /// How long a client connection may stay idle before it is closed (`None` keeps it open forever)
+    pub idle_timeout: Option<Duration>,
+
+    /// Whether the background sweeps evict expired keys (toggled with DEBUG SET-ACTIVE-EXPIRE).
+    /// Expired keys are still removed when they are accessed while this is disabled.
+    pub active_expire: Arc<AtomicBool>,
+
     /// The database instance to store data
     pub db: database::Database,
 
     /// The role of the server (master or replica)
     pub role: Role,
 
+    /// The random ID that identifies this run of the server
+    pub run_id: String,
+
     /// The master replication ID is used to identify the master server in a replication setup.
     /// The master server will generate a new ID every time it starts.
     /// The replica server will use this ID to identify the master server.
@@ -50,12 +87,50 @@ pub struct Server {
     pub repl_offset: u64,
 
     /// The list of replica servers connected to this master server.
-    /// Stores the address of each replica server connected to this master server.
-    pub replicas: Vec<SocketAddr>,
+    /// Stores the address, listening port and offset of each replica server connected to this master server.
+    pub replicas: Vec<Replica>,
+
+    /// The index of the database that the replication stream last selected.
+    /// A SELECT command is propagated before any write to a different database.
+    pub repl_db: usize,
 
     /// The broadcast sender is used to send the server instance to each thread.
     /// This allows each thread to access the server instance and share data across threads.
     pub sender: broadcast::Sender<Type>,
+
+    /// Notifies the clients blocked on XREAD whenever an entry is added to a stream.
+    /// Each event holds the database index and the key of the stream.
+    pub stream_events: broadcast::Sender<(usize, Type)>,
+
+    /// Notifies the clients blocked on BLPOP and BRPOP whenever elements are pushed to a list.
+    /// Each event holds the database index and the key of the list.
+    pub list_events: broadcast::Sender<(usize, Type)>,
+
+    /// The subscribers of each pub/sub channel.
+    /// Maps the channel name to the message senders of the subscribed connections, by connection id.
+    pub channels: HashMap<String, HashMap<u64, mpsc::UnboundedSender<Vec<Type>>>>,
+
+    /// The subscribers of each pub/sub channel pattern (with the PSUBSCRIBE command).
+    /// Maps the glob-style pattern to the message senders of the subscribed connections, by connection id.
+    pub patterns: HashMap<String, HashMap<u64, mpsc::UnboundedSender<Vec<Type>>>>,
+
+    /// The classes of keyspace events published through pub/sub (the `notify-keyspace-events` flags).
+    /// Empty when keyspace notifications are disabled, which is the default.
+    pub notify_keyspace_events: String,
+
+    /// Signals the server and its connections to shut down.
+    /// Holds `Some(save)` once a shutdown has been requested, where `save` tells whether to save the dataset first.
+    shutdown: Arc<watch::Sender<Option<bool>>>,
+
+    /// The counters of connections and commands reported by INFO
+    pub stats: Arc<Stats>,
+
+    /// The users of the access control list, by name
+    pub users: HashMap<String, acl::User>,
+
+    /// The clients connected to the server, by connection id.
+    /// Clients are registered when their connection is accepted, and removed when it is closed.
+    pub clients: HashMap<u64, ClientInfo>,
 }
 
 /// Creates a new Server instance with the given host and port
@@ -64,17 +139,112 @@ pub fn new(host: &'static str, port: u16) -> Server {
         // host,
         port,
         addr: format!("{}:{}", host, port),
+        buffer_size: config::DEFAULT_BUFFER_SIZE,
+        expiry_interval: config::DEFAULT_EXPIRY_INTERVAL,
+        idle_timeout: None,
+        active_expire: Arc::new(AtomicBool::new(true)),
         role: Role::Master,
         db: database::new(),
+        run_id: helpers::generate_id(40),
         master_replid: helpers::generate_id(40),
         master_repl_offset: 0,
         repl_offset: 0,
         replicas: Vec::new(),
+        repl_db: 0,
         sender: broadcast::channel(16).0,
+        stream_events: broadcast::channel(16).0,
+        list_events: broadcast::channel(16).0,
+        channels: HashMap::new(),
+        patterns: HashMap::new(),
+        notify_keyspace_events: String::new(),
+        shutdown: Arc::new(watch::channel(None).0),
+        stats: Arc::new(stats::new()),
+        users: acl::new(),
+        clients: HashMap::new(),
     }
 }
 
 impl Server {
+    /// Requests the server to shut down.
+    /// The server stops accepting connections, lets the connections finish the commands in flight,
+    /// and saves the dataset to the RDB file first if `save` is true.
+    pub fn request_shutdown(&self, save: bool) {
+        self.shutdown.send_replace(Some(save));
+    }
+
+    /// Subscribes to the shutdown signal, to be awaited with `shutdown_requested`
+    pub fn shutdown_signal(&self) -> watch::Receiver<Option<bool>> {
+        self.shutdown.subscribe()
+    }
+
+    /// Registers the connection as a connected client
+    pub fn register_client(&mut self, connection: &Connection) {
+        let client = ClientInfo {
+            addr: connection.addr,
+            name: connection.name.clone(),
+            connected_at: Instant::now(),
+        };
+        self.clients.insert(connection.id, client);
+    }
+
+    /// Removes the connection with the given id from the connected clients
+    pub fn unregister_client(&mut self, id: u64) {
+        self.clients.remove(&id);
+    }
+
+    /// Removes the connection with the given id from the subscribers of the channel.
+    /// The channel is removed once it has no subscribers left.
+    pub fn unsubscribe(&mut self, channel: &str, id: u64) {
+        remove_subscriber(&mut self.channels, channel, id);
+    }
+
+    /// Removes the connection with the given id from the subscribers of the pattern.
+    /// The pattern is removed once it has no subscribers left.
+    pub fn punsubscribe(&mut self, pattern: &str, id: u64) {
+        remove_subscriber(&mut self.patterns, pattern, id);
+    }
+
+    /// Publishes the message to the subscribers of the channel, and of the patterns matching the channel.
+    /// Subscribers whose connection has been closed are removed.
+    /// Returns the number of clients that received the message.
+    pub fn publish(&mut self, channel: &str, message: Type) -> usize {
+        let mut received = 0;
+
+        // Send the message to every subscriber of the channel
+        if let Some(subscribers) = self.channels.get_mut(channel) {
+            let message = vec![
+                Type::BulkString("message".into()),
+                Type::BulkString(channel.into()),
+                message.clone(),
+            ];
+            subscribers.retain(|_, sender| sender.send(message.clone()).is_ok());
+            received += subscribers.len();
+        }
+
+        // Send the message to every subscriber of a pattern matching the channel
+        for (pattern, subscribers) in self.patterns.iter_mut() {
+            if !helpers::glob_match(pattern, channel) {
+                continue;
+            }
+            let message = vec![
+                Type::BulkString("pmessage".into()),
+                Type::BulkString(pattern.as_str().into()),
+                Type::BulkString(channel.into()),
+                message.clone(),
+            ];
+            subscribers.retain(|_, sender| sender.send(message.clone()).is_ok());
+            received += subscribers.len();
+        }
+
+        // Forget the channels and patterns that no longer have any subscribers
+        self.channels
+            .retain(|_, subscribers| !subscribers.is_empty());
+        self.patterns
+            .retain(|_, subscribers| !subscribers.is_empty());
+
+        received
+    }
+
     /// Configures the server with the given configuration parameters.
     /// The server will set the replica-of address, directory, and dbfilename based on the configuration.
     /// Does NOT configure the port as it must be set when the server is instantiated.
@@ -94,6 +264,18 @@ impl Server {
             self.db.dbfilename = dbfilename;
         }
 
+        // Set the connection buffer size
+        self.buffer_size = config.buffer_size;
+
+        // Set the interval between the expiry sweeps
+        self.expiry_interval = config.expiry_interval;
+
+        // Set the idle timeout of the client connections
+        self.idle_timeout = match config.timeout {
+            0 => None,
+            seconds => Some(Duration::from_secs(seconds)),
+        };
+
         // Load the database
         self.db.load().await?;
 
@@ -113,6 +295,11 @@ impl Server {
         let server = Arc::new(Mutex::new(self.clone()));
         let wait_channel = Arc::new(Mutex::new(mpsc::channel::<u64>(64)));
 
+        // Periodically evict the expired keys in the background
+        let interval = Duration::from_millis(self.expiry_interval);
+        let active = Arc::clone(&self.active_expire);
+        let sweeper = tokio::spawn(sweep_expired_keys(self.db.clone(), interval, active));
+
         // TODO: There seems to be a race condition here. There is a possibility
         // that the connection isn't established before the master server sends data.
 
@@ -123,8 +310,18 @@ impl Server {
         }
 
         // Handle the main connection
-        self.handle_main_connections(&server, &wait_channel).await?;
+        let result = self.handle_main_connections(&server, &wait_channel).await;
+        sweeper.abort();
+        result?;
+
+        // Save the dataset before exiting, if requested
+        if *self.shutdown.borrow() == Some(true) {
+            let db = server.lock().await.db.clone();
+            db.save().await?;
+            println!("[{}] Saved the database before shutting down", self.addr);
+        }
 
+        println!("[{}] Server shut down", self.addr);
         Ok(())
     }
 
@@ -141,7 +338,10 @@ impl Server {
             self.addr, master_addr
         );
         // Send handshake and establish connection with the master server
-        let mut connection = self.role.send_handshake(self.port).await?;
+        let mut connection = self
+            .role
+            .send_handshake(self.port, self.buffer_size)
+            .await?;
         println!("[{}] Connection Established to {}", self.addr, master_addr);
 
         // Clone the Arc<Mutex<Server>> instance
@@ -161,6 +361,8 @@ impl Server {
 
     /// Handles the main logic for the server.
     /// Listens for incoming connections on the server's address and spawns a new thread to handle each connection.
+    /// Returns once the server is shut down (on request, or with Ctrl-C) and the connections have finished,
+    /// or the shutdown timeout has elapsed.
     async fn handle_main_connections(
         &self,
         server: &Arc<Mutex<Server>>,
@@ -169,22 +371,162 @@ impl Server {
         // Bind the server to the address and start listening for incoming connections
         let listener = TcpListener::bind(&self.addr).await?;
         println!("[{}] Server is listening on {}", self.addr, self.port);
-        Ok(while let Ok((stream, addr)) = listener.accept().await {
+        let mut shutdown = self.shutdown_signal();
+        let mut connections = JoinSet::new();
+        loop {
+            let (stream, addr) = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(_) => break,
+                },
+                _ = shutdown_requested(&mut shutdown) => break,
+                _ = tokio::signal::ctrl_c() => {
+                    self.request_shutdown(false);
+                    break;
+                }
+            };
+
             // Create a new Connection instance for the incoming connection
-            let mut connection = connection::new(stream, addr, Kind::Main);
+            self.stats.record_connection();
+            let mut connection = connection::new(stream, addr, Kind::Main, self.buffer_size);
 
             // Clone the Arc<Mutex<Server>> instance
-            let server = Arc::clone(&server);
+            let server = Arc::clone(server);
             let wait_channel = Arc::clone(wait_channel);
 
             // ... and spawn a new thread for each incoming connection
-            tokio::spawn(async move {
+            connections.spawn(async move {
                 println!("New main connection from {}", connection.addr);
-                connection
+                server.lock().await.register_client(&connection);
+                let result = connection
                     .handle(&server, &wait_channel)
                     .await
-                    .expect("Failed to handle connection");
+                    .map_err(|e| e.to_string());
+                server.lock().await.unregister_client(connection.id);
+                result.expect("Failed to handle connection");
             });
-        })
+        }
+
+        // Stop accepting connections, and wait for the open ones to finish their commands
+        println!("[{}] Shutting down...", self.addr);
+        drop(listener);
+        let drained = tokio::time::timeout(SHUTDOWN_TIMEOUT, async {
+            while connections.join_next().await.is_some() {}
+        });
+        if drained.await.is_err() {
+            println!(
+                "[{}] Closing the connections that did not finish",
+                self.addr
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Waits until a shutdown of the server is requested.
+/// Returns whether the dataset should be saved before shutting down.
+pub async fn shutdown_requested(signal: &mut watch::Receiver<Option<bool>>) -> bool {
+    loop {
+        if let Some(save) = *signal.borrow_and_update() {
+            return save;
+        }
+        if signal.changed().await.is_err() {
+            // The server is gone, so no shutdown can be requested anymore
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+/// Evicts the expired keys from the database every interval, for as long as the server runs.
+/// Expired keys are otherwise only removed when they are accessed.
+/// The sweeps are skipped while `active` is false.
+async fn sweep_expired_keys(db: database::Database, interval: Duration, active: Arc<AtomicBool>) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        if active.load(Ordering::Relaxed) {
+            db.evict_expired().await;
+        }
+    }
+}
+
+/// Removes the subscriber with the given id from the channel (or pattern) with the given name.
+/// The name is removed from the subscribers map once it has no subscribers left.
+fn remove_subscriber(
+    subscribers: &mut HashMap<String, HashMap<u64, mpsc::UnboundedSender<Vec<Type>>>>,
+    name: &str,
+    id: u64,
+) {
+    if let Some(senders) = subscribers.get_mut(name) {
+        senders.remove(&id);
+        if senders.is_empty() {
+            subscribers.remove(name);
+        }
+    }
+}
+
+// -----
+// TESTS
+// -----
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_helpers;
+
+    #[tokio::test]
+    async fn should_sweep_expired_keys_that_are_never_read() {
+        let server = Arc::new(Mutex::new(new("127.0.0.1", 0)));
+        let mut client = test_helpers::client_for(&server).await;
+        client.send(&["SET", "k", "v", "PX", "10"]).await;
+        let db = server.lock().await.db.clone();
+        assert_eq!(db.keys(0).await.len(), 1);
+
+        let active = Arc::clone(&server.lock().await.active_expire);
+        tokio::spawn(sweep_expired_keys(
+            db.clone(),
+            Duration::from_millis(20),
+            active,
+        ));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(db.keys(0).await.is_empty());
+    }
+
+    /// Runs the server in the background, and returns the handle to the result of `run`
+    fn spawn_run(server: &Server) -> tokio::task::JoinHandle<Result<(), String>> {
+        let server = server.clone();
+        tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+    }
+
+    #[tokio::test]
+    async fn should_return_from_run_after_shutdown() {
+        let server = new("127.0.0.1", 0);
+        let run = spawn_run(&server);
+        server.request_shutdown(false);
+        let result = tokio::time::timeout(Duration::from_secs(1), run).await;
+        assert_eq!(result.unwrap().unwrap(), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn should_save_dataset_on_shutdown() {
+        let mut server = new("127.0.0.1", 0);
+        server.db.dir = std::env::temp_dir().to_string_lossy().into_owned();
+        server.db.dbfilename = format!("shutdown-{}.rdb", helpers::generate_id(8));
+        let key = Type::BulkString("k".into());
+        server
+            .db
+            .set(0, key.clone(), Type::BulkString("v".into()), None)
+            .await;
+
+        let run = spawn_run(&server);
+        server.request_shutdown(true);
+        let result = tokio::time::timeout(Duration::from_secs(1), run).await;
+        assert_eq!(result.unwrap().unwrap(), Ok(()));
+
+        let mut restored = database::new();
+        restored.dir = server.db.dir.clone();
+        restored.dbfilename = server.db.dbfilename.clone();
+        restored.load().await.unwrap();
+        assert_eq!(restored.keys(0).await, vec![key]);
     }
 }
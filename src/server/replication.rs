@@ -33,10 +33,7 @@ pub enum Role {
 impl Role {
     /// Returns true if the server is a master
     pub fn is_master(&self) -> bool {
-        match self {
-            Role::Master => true,
-            _ => false,
-        }
+        matches!(self, Role::Master)
     }
 
     // /// Returns true if the server is a replica
@@ -60,6 +57,7 @@ impl Role {
     /// 2. Send a REPLCONF listening-port <PORT> command to inform the master server of the port that the replica server is listening on.
     /// 3. Send a REPLCONF capa psync2 command to inform the master server that the replica server supports the PSYNC2 command.
     /// 4. Send a PSYNC <REPLID> <OFFSET> command to synchronize the replica server with the master server.
+    ///
     /// The REPLID and OFFSET are used to identify the replication stream and the offset of the last received command.
     /// The REPLID is "?" if the replica server is syncing for the first time.
     /// The OFFSET is -1 if the replica server is syncing for the first time.
@@ -69,6 +67,7 @@ impl Role {
     pub async fn send_handshake(
         &self,
         port: u16,
+        buffer_size: usize,
     ) -> Result<connection::Connection, Box<dyn std::error::Error>> {
         // Get the address of the replication master.
         // Return an error if the server is a master. Master servers cannot send handshakes.
@@ -82,8 +81,9 @@ impl Role {
         let (_, master_port) = helpers::split_host_and_port(addr.clone(), ":")?;
         let mut connection = connection::new(
             stream,
-            SocketAddr::new(IpAddr::from_str("127.0.0.1").unwrap(), master_port.clone()),
+            SocketAddr::new(IpAddr::from_str("127.0.0.1").unwrap(), master_port),
             Kind::Replication,
+            buffer_size,
         );
 
         // Send a PING
@@ -113,6 +113,20 @@ impl std::fmt::Display for Role {
     }
 }
 
+// REPLICA
+// -------
+
+/// A replica connected to this master server
+#[derive(Debug, Clone)]
+pub struct Replica {
+    /// The address of the replication connection of the replica
+    pub addr: SocketAddr,
+    /// The port the replica listens on, as advertised with `REPLCONF listening-port`
+    pub port: u16,
+    /// The replication offset last acknowledged by the replica
+    pub offset: u64,
+}
+
 // PING
 // ----
 
@@ -182,6 +196,6 @@ async fn send_psync(
         bulk_string(offset.to_string().as_str()),
     ]);
     connection.write_all(&response.as_bytes()).await?;
-    connection.read().await?; // Read the FULLRESYNC response (not used)
+    connection.read_fullresync().await?; // Read the FULLRESYNC response, which the RDB file follows
     Ok(())
 }
@@ -0,0 +1,72 @@
+// Library
+use crate::commands::COMMANDS;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+// -----
+// STATS
+// -----
+
+/// Counters describing the activity of the server, as reported by INFO.
+/// The counters are atomics, so that they can be updated without locking the server.
+pub struct Stats {
+    /// The number of connections accepted by the server
+    total_connections_received: AtomicU64,
+    /// The number of commands processed by the server
+    total_commands_processed: AtomicU64,
+    /// The number of calls of each command, by the (uppercase) command name
+    calls: HashMap<&'static str, AtomicU64>,
+}
+
+/// Creates a new Stats instance with all the counters at zero
+pub fn new() -> Stats {
+    Stats {
+        total_connections_received: AtomicU64::new(0),
+        total_commands_processed: AtomicU64::new(0),
+        calls: COMMANDS
+            .iter()
+            .map(|command| (*command, AtomicU64::new(0)))
+            .collect(),
+    }
+}
+
+impl Stats {
+    /// Records an accepted connection
+    pub fn record_connection(&self) {
+        self.total_connections_received
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a processed command. Unknown commands only count towards the total.
+    pub fn record_command(&self, command: &str) {
+        self.total_commands_processed
+            .fetch_add(1, Ordering::Relaxed);
+        if let Some(calls) = self.calls.get(command) {
+            calls.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the number of connections accepted by the server
+    pub fn total_connections_received(&self) -> u64 {
+        self.total_connections_received.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of commands processed by the server
+    pub fn total_commands_processed(&self) -> u64 {
+        self.total_commands_processed.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of calls of each command that was called at least once, sorted by name
+    pub fn calls(&self) -> Vec<(&'static str, u64)> {
+        let mut calls: Vec<_> = self
+            .calls
+            .iter()
+            .map(|(command, calls)| (*command, calls.load(Ordering::Relaxed)))
+            .filter(|(_, calls)| *calls > 0)
+            .collect();
+        calls.sort();
+        calls
+    }
+}